@@ -3,9 +3,11 @@
 
 use crate::cell::Cell;
 use crate::config::{
-    BLINK_ANIMATION_STEP, BLINK_COUNT_MAX, BOARD_HEIGHT, BOARD_WIDTH, PUSH_DOWN_STEP_DURATION,
+    BLINK_ANIMATION_STEP, BLINK_COUNT_MAX, BOARD_HEIGHT, BOARD_WIDTH, GRAVITY_STEP_DURATION,
+    PUSH_DOWN_STEP_DURATION,
 };
 use crate::game_color::GameColor;
+use crate::gravity::GravityMode;
 use crate::scoring::ColorMaxChains; // MaxChainsの正しい型名
 use std::time::Duration;
 
@@ -21,6 +23,8 @@ pub enum Animation {
         solid_line_y: usize,
         start_time: Duration,
     },
+    /// Cascade重力でブロックが1段落下するアニメーション
+    GravityFall { start_time: Duration },
 }
 
 /// アニメーション処理結果
@@ -29,6 +33,7 @@ pub struct AnimationResult {
     pub continuing_animations: Vec<Animation>,
     pub completed_line_blinks: Vec<Vec<usize>>, // 完了したLineBlink のラインリスト
     pub completed_push_downs: Vec<usize>,       // 完了したPush Down のsolid_line_y
+    pub completed_gravity_falls: usize,         // 完了したGravityFall の数
 }
 
 impl AnimationResult {
@@ -37,6 +42,7 @@ impl AnimationResult {
             continuing_animations: Vec::new(),
             completed_line_blinks: Vec::new(),
             completed_push_downs: Vec::new(),
+            completed_gravity_falls: 0,
         }
     }
 }
@@ -93,6 +99,16 @@ pub fn update_animations(
                     });
                 }
             }
+            Animation::GravityFall { start_time } => {
+                if current_time - start_time >= GRAVITY_STEP_DURATION {
+                    // 1段落下 → 呼び出し元で重力ステップを実行
+                    result.completed_gravity_falls += 1;
+                } else {
+                    result
+                        .continuing_animations
+                        .push(Animation::GravityFall { start_time });
+                }
+            }
         }
     }
 
@@ -133,13 +149,55 @@ pub fn process_push_down_step(
     }
 }
 
+/// 複数のPush Downを同時に1ステップ進める（CLI版とWASM版共通）
+///
+/// 下のラインから順に処理する。下のラインが1段下がると、その上の盤面は丸ごと1段運ばれるため、
+/// 上のラインは行を消さずに移動し、ライン同士の間隔が保たれる。
+/// 下のラインが着地した後は、上のラインが間のブロックを消しながら下がり続けて間隔が詰まる。
+/// 戻り値は下のラインから順に並んだ各ラインの結果
+pub fn process_push_down_steps(
+    board: &mut Vec<Vec<Cell>>,
+    current_board_height: &mut usize,
+    solid_lines: &[usize],
+) -> Vec<PushDownStepResult> {
+    let mut lines = solid_lines.to_vec();
+    lines.sort_by(|a, b| b.cmp(a));
+
+    let mut results = Vec::new();
+    let mut lower_line_moved = false;
+    for solid_line_y in lines {
+        if lower_line_moved {
+            // 下のラインの移動で既に1段運ばれている
+            results.push(PushDownStepResult::Moved {
+                new_solid_line_y: solid_line_y + 1,
+            });
+            continue;
+        }
+
+        let result = process_push_down_step(board, current_board_height, solid_line_y);
+        lower_line_moved = matches!(result, PushDownStepResult::Moved { .. });
+        results.push(result);
+    }
+
+    results
+}
+
 /// Push Downステップの結果
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum PushDownStepResult {
     Completed,
     Moved { new_solid_line_y: usize },
 }
 
+/// 盤面から揃っているラインをすべて検出（CLI版とWASM版共通）
+/// 下から上の順に返す
+pub fn find_complete_lines(board: &[Vec<Cell>], current_board_height: usize) -> Vec<usize> {
+    (0..current_board_height)
+        .rev()
+        .filter(|&y| board[y].iter().all(|cell| cell.is_block()))
+        .collect()
+}
+
 /// ライン消去時のスコア計算（CLI版とWASM版共通）
 pub fn calculate_line_clear_score(
     board: &Vec<Vec<Cell>>,
@@ -160,7 +218,7 @@ pub fn calculate_line_clear_score(
                 let points = (count as u32) * max_chains.get(color) * 10;
                 scores.push((color, points));
             }
-            _ => {} // Empty cells and other types are ignored
+            _ => {} // Empty cells and other types (Solid・特殊ブロック) are ignored
         }
     }
 
@@ -168,44 +226,244 @@ pub fn calculate_line_clear_score(
 }
 
 /// ライン消去処理の共通ロジック（CLI版とWASM版共通）
+///
+/// LineBlink完了時に、揃ったラインをまとめて処理する。
+/// 1. 消去ラインの爆弾ブロックを爆発させる
+/// 2. 最下段から連続して揃ったライン（Bottom line）は標準のテトリスと同様に取り除く
+/// 3. 残りのライン（Non-bottom line）より下の孤立ブロックを消去し、ラインをSolid化する
+///
+/// Bottom lineだけが揃った場合は`gravity_mode`に従う。
+/// Naive以外ではラインを空にしたまま残し、Stickyはその場で塊ごと落下させる。
+/// Cascadeの落下は呼び出し元がGravityFallアニメーションで進める。
+/// Non-bottom lineを含む消去では常にNaiveと同じ処理になる。
+///
+/// 戻り値: (取り除いたBottom line, Solid化したラインのy座標, 消去した孤立ブロック数)
+/// Solid化したラインの座標はBottom line除去後の位置で、そのままPushDownの開始位置になる
 pub fn process_line_clear(
     board: &mut Vec<Vec<Cell>>,
     current_board_height: usize,
     lines: &[usize],
-) -> (Vec<usize>, Vec<usize>) {
-    let mut bottom_lines_cleared = Vec::new();
-    let mut non_bottom_lines_cleared = Vec::new();
+    gravity_mode: GravityMode,
+) -> (Vec<usize>, Vec<usize>, usize) {
+    let mut sorted_lines = lines.to_vec();
+    sorted_lines.sort_by(|a, b| b.cmp(a));
+    sorted_lines.dedup();
 
-    // Bottom line と Non-bottom line を分離
-    for &y in lines {
-        if y == current_board_height - 1 {
+    for &y in &sorted_lines {
+        detonate_bombs(board, y, current_board_height);
+    }
+
+    // Bottom line と Non-bottom line を分離（最下段から連続するラインがBottom line）
+    let mut bottom_lines_cleared = Vec::new();
+    let mut non_bottom_lines = Vec::new();
+    for &y in &sorted_lines {
+        if y + bottom_lines_cleared.len() + 1 == current_board_height {
             bottom_lines_cleared.push(y);
         } else {
-            non_bottom_lines_cleared.push(y);
+            non_bottom_lines.push(y);
         }
     }
 
-    // Bottom lines の標準テトリスクリア処理
-    if !bottom_lines_cleared.is_empty() {
-        let num_cleared = bottom_lines_cleared.len();
-        let mut sorted_lines = bottom_lines_cleared.to_vec();
-        sorted_lines.sort_by(|a, b| b.cmp(a));
-
-        // ライン削除と上からの補充
-        for &line_y in &sorted_lines {
-            board.remove(line_y);
+    if non_bottom_lines.is_empty() && gravity_mode != GravityMode::Naive {
+        // ラインを空にして、上のブロックは重力ルールで落とす
+        for &line_y in &bottom_lines_cleared {
+            board[line_y].fill(Cell::Empty);
         }
-        for _ in 0..num_cleared {
-            board.insert(0, vec![Cell::Empty; BOARD_WIDTH]);
+        if gravity_mode == GravityMode::Sticky {
+            crate::gravity::settle(board, current_board_height);
         }
+        return (bottom_lines_cleared, Vec::new(), 0);
     }
 
+    // Bottom lines の標準テトリスクリア処理（下の行から削除し、上から補充）
+    for &line_y in &bottom_lines_cleared {
+        board.remove(line_y);
+    }
+    for _ in 0..bottom_lines_cleared.len() {
+        board.insert(0, vec![Cell::Empty; BOARD_WIDTH]);
+    }
+
+    // Bottom line除去で盤面が下がった分だけNon-bottom lineの位置をずらす
+    let solid_lines: Vec<usize> = non_bottom_lines
+        .iter()
+        .map(|&y| y + bottom_lines_cleared.len())
+        .collect();
+
+    // 一番上のNon-bottom lineより下にある孤立ブロックを消去（無得点）
+    let isolated_removed = solid_lines.iter().min().map_or(0, |&top_line_y| {
+        crate::board_logic::remove_isolated_blocks(board, top_line_y)
+    });
+
     // Non-bottom lines をSolid化（アニメーション準備）
-    for &y in &non_bottom_lines_cleared {
+    for &y in &solid_lines {
         for x in 0..BOARD_WIDTH {
             board[y][x] = Cell::Solid;
         }
     }
 
-    (bottom_lines_cleared, non_bottom_lines_cleared)
+    (bottom_lines_cleared, solid_lines, isolated_removed)
+}
+
+/// 消去ラインにある爆弾ブロックを爆発させ、周囲3x3のブロックを消去する（CLI版とWASM版共通）
+/// 爆発で消えたブロックは得点にならない。消去したセル数を返す
+pub fn detonate_bombs(
+    board: &mut Vec<Vec<Cell>>,
+    line_y: usize,
+    current_board_height: usize,
+) -> usize {
+    let blast_cells = crate::board_logic::bomb_blast_cells(board, line_y, current_board_height);
+    let mut cleared = 0;
+    for (x, y) in blast_cells {
+        if y != line_y && board[y][x] != Cell::Empty {
+            board[y][x] = Cell::Empty;
+            cleared += 1;
+        }
+    }
+    cleared
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_board() -> Vec<Vec<Cell>> {
+        vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT]
+    }
+
+    fn fill_row(board: &mut [Vec<Cell>], y: usize, cell: Cell) {
+        board[y].fill(cell);
+    }
+
+    fn solid_rows(board: &[Vec<Cell>]) -> Vec<usize> {
+        (0..BOARD_HEIGHT)
+            .filter(|&y| board[y].iter().all(|&cell| cell == Cell::Solid))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_complete_lines_returns_all_lines_from_bottom() {
+        let mut board = empty_board();
+        fill_row(&mut board, 5, Cell::Occupied(GameColor::Cyan));
+        fill_row(&mut board, 12, Cell::Occupied(GameColor::Magenta));
+        fill_row(&mut board, 19, Cell::Garbage);
+
+        assert_eq!(find_complete_lines(&board, BOARD_HEIGHT), vec![19, 12, 5]);
+        // プレイ領域外（Solid領域）の行は対象外
+        assert_eq!(find_complete_lines(&board, 19), vec![12, 5]);
+    }
+
+    #[test]
+    fn test_contiguous_bottom_lines_are_all_cleared() {
+        let mut board = empty_board();
+        fill_row(&mut board, 18, Cell::Occupied(GameColor::Cyan));
+        fill_row(&mut board, 19, Cell::Occupied(GameColor::Yellow));
+        board[17][0] = Cell::Occupied(GameColor::Magenta);
+
+        let (bottom, solid, _) =
+            process_line_clear(&mut board, BOARD_HEIGHT, &[19, 18], GravityMode::Naive);
+
+        assert_eq!(bottom, vec![19, 18]);
+        assert!(solid.is_empty());
+        // 上のブロックは2段分落ちる
+        assert_eq!(board[19][0], Cell::Occupied(GameColor::Magenta));
+        assert!(solid_rows(&board).is_empty());
+    }
+
+    #[test]
+    fn test_mixed_clear_shifts_non_bottom_lines_by_removed_bottom_lines() {
+        let mut board = empty_board();
+        fill_row(&mut board, 10, Cell::Occupied(GameColor::Cyan));
+        fill_row(&mut board, 19, Cell::Occupied(GameColor::Yellow));
+
+        let (bottom, solid, _) =
+            process_line_clear(&mut board, BOARD_HEIGHT, &[19, 10], GravityMode::Naive);
+
+        assert_eq!(bottom, vec![19]);
+        assert_eq!(solid, vec![11]);
+        assert_eq!(solid_rows(&board), vec![11]);
+    }
+
+    #[test]
+    fn test_sticky_gravity_drops_groups_into_holes() {
+        let mut board = empty_board();
+        fill_row(&mut board, 19, Cell::Occupied(GameColor::Yellow));
+        board[18][0] = Cell::Occupied(GameColor::Magenta);
+        board[17][0] = Cell::Occupied(GameColor::Cyan);
+        board[17][1] = Cell::Occupied(GameColor::Cyan);
+
+        let (bottom, solid, _) =
+            process_line_clear(&mut board, BOARD_HEIGHT, &[19], GravityMode::Sticky);
+
+        assert_eq!(bottom, vec![19]);
+        assert!(solid.is_empty());
+        // Cyanの塊は形を保ったままMagentaの上に着地する
+        assert_eq!(board[19][0], Cell::Occupied(GameColor::Magenta));
+        assert_eq!(board[18][0], Cell::Occupied(GameColor::Cyan));
+        assert_eq!(board[18][1], Cell::Occupied(GameColor::Cyan));
+        assert_eq!(board[19][1], Cell::Empty);
+    }
+
+    #[test]
+    fn test_parallel_push_down_keeps_spacing_while_lower_line_moves() {
+        let mut board = empty_board();
+        let mut height = BOARD_HEIGHT;
+        fill_row(&mut board, 10, Cell::Solid);
+        fill_row(&mut board, 14, Cell::Solid);
+        board[12][3] = Cell::Occupied(GameColor::Cyan); // 2本のラインの間のブロック
+        board[15][3] = Cell::Occupied(GameColor::Magenta); // 下のラインの真下のブロック
+
+        let results = process_push_down_steps(&mut board, &mut height, &[10, 14]);
+
+        assert_eq!(
+            results,
+            vec![
+                PushDownStepResult::Moved {
+                    new_solid_line_y: 15
+                },
+                PushDownStepResult::Moved {
+                    new_solid_line_y: 11
+                },
+            ]
+        );
+        // 間隔が保たれ、間のブロックも一緒に運ばれる
+        assert_eq!(solid_rows(&board), vec![11, 15]);
+        assert_eq!(board[13][3], Cell::Occupied(GameColor::Cyan));
+        // 消えるのは下のラインの真下の行だけ
+        assert!(board
+            .iter()
+            .all(|row| !row.contains(&Cell::Occupied(GameColor::Magenta))));
+        assert_eq!(height, BOARD_HEIGHT);
+    }
+
+    #[test]
+    fn test_parallel_push_down_stacks_lines_after_lower_one_lands() {
+        let mut board = empty_board();
+        let mut height = BOARD_HEIGHT;
+        fill_row(&mut board, 8, Cell::Solid);
+        fill_row(&mut board, 16, Cell::Solid);
+        board[12][0] = Cell::Occupied(GameColor::Cyan);
+
+        let mut lines = vec![8, 16];
+        let mut steps = 0;
+        while !lines.is_empty() {
+            let results = process_push_down_steps(&mut board, &mut height, &lines);
+            lines = results
+                .into_iter()
+                .filter_map(|result| match result {
+                    PushDownStepResult::Moved { new_solid_line_y } => Some(new_solid_line_y),
+                    PushDownStepResult::Completed => None,
+                })
+                .collect();
+            steps += 1;
+            assert!(steps < BOARD_HEIGHT * 2, "push-downが終了しない");
+        }
+
+        // 最後には2本とも底に隣接して積み重なり、フィールドが2段浅くなる
+        assert_eq!(solid_rows(&board), vec![18, 19]);
+        assert_eq!(height, BOARD_HEIGHT - 2);
+        // 間にあったブロックは上のラインに消されている
+        assert!(board
+            .iter()
+            .all(|row| row.iter().all(|cell| !matches!(cell, Cell::Occupied(_)))));
+    }
 }
@@ -2,67 +2,97 @@ use std::collections::VecDeque;
 
 use crate::cell::{Board, Cell};
 use crate::config::{BOARD_HEIGHT, BOARD_WIDTH};
+use crate::game_color::GameColor;
 
 pub type Point = (usize, usize);
 
-pub fn find_and_connect_adjacent_blocks(board: &mut Board, lines_to_clear: &[usize]) {
-    let mut cells_to_connect: Vec<(usize, usize)> = Vec::new();
-    let mut visited: Vec<Vec<bool>> = vec![vec![false; BOARD_WIDTH]; BOARD_HEIGHT];
+/// 同色ブロックの連結グループ
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorGroup {
+    pub color: GameColor,
+    /// グループに含まれるセル（参加しているワイルドカードを含む）
+    pub cells: Vec<Point>,
+}
+
+/// `color`の連結グループに参加できるセルかどうか（同色ブロックまたはワイルドカード）
+fn joins_group(cell: Cell, color: GameColor) -> bool {
+    cell == Cell::Wildcard || cell.color() == Some(color)
+}
+
+/// 盤面の連結グループを上下左右の隣接でBFS検出する
+///
+/// - グループの起点は色を持つブロック（Occupied / Connected）のみ
+/// - ワイルドカードは隣接するどの色のグループにも参加し、複数のグループに属することがある
+/// - `height`以上の行と`excluded_rows`に含まれる行は探索しない
+///
+/// 単独ブロックもサイズ1のグループとして返す。
+pub fn find_color_groups(board: &Board, height: usize, excluded_rows: &[usize]) -> Vec<ColorGroup> {
+    let height = height.min(BOARD_HEIGHT);
+    let mut groups = Vec::new();
+    let mut visited = vec![vec![false; BOARD_WIDTH]; BOARD_HEIGHT];
 
-    for y in 0..BOARD_HEIGHT {
+    for y in 0..height {
+        if excluded_rows.contains(&y) {
+            continue;
+        }
         for x in 0..BOARD_WIDTH {
-            if lines_to_clear.contains(&y) {
+            if visited[y][x] {
                 continue;
             }
+            let Some(color) = board[y][x].color() else {
+                continue;
+            };
 
-            if let Cell::Occupied(color) = board[y][x] {
-                if visited[y][x] {
-                    continue;
-                }
+            // ワイルドカードは複数グループに属せるので、グループごとに訪問済みを管理する
+            let mut in_group = vec![vec![false; BOARD_WIDTH]; BOARD_HEIGHT];
+            let mut cells = Vec::new();
+            let mut queue = VecDeque::new();
+
+            visited[y][x] = true;
+            in_group[y][x] = true;
+            queue.push_back((x, y));
+            cells.push((x, y));
 
-                let mut component: Vec<(usize, usize)> = Vec::new();
-                let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
-
-                visited[y][x] = true;
-                queue.push_back((x, y));
-                component.push((x, y));
-
-                while let Some((cx, cy)) = queue.pop_front() {
-                    let neighbors = [
-                        (cx as i8 - 1, cy as i8),
-                        (cx as i8 + 1, cy as i8),
-                        (cx as i8, cy as i8 - 1),
-                        (cx as i8, cy as i8 + 1),
-                    ];
-
-                    for (nx, ny) in neighbors {
-                        if nx >= 0 && nx < BOARD_WIDTH as i8 && ny >= 0 && ny < BOARD_HEIGHT as i8 {
-                            let (nx_usize, ny_usize) = (nx as usize, ny as usize);
-                            if lines_to_clear.contains(&ny_usize) {
-                                continue;
-                            }
-                            if !visited[ny_usize][nx_usize] {
-                                let neighbor_color = match board[ny_usize][nx_usize] {
-                                    Cell::Occupied(c) => Some(c),
-                                    Cell::Connected { color: c, count: _ } => Some(c),
-                                    _ => None,
-                                };
-                                if let Some(neighbor_color) = neighbor_color {
-                                    if neighbor_color == color {
-                                        visited[ny_usize][nx_usize] = true;
-                                        queue.push_back((nx_usize, ny_usize));
-                                        component.push((nx_usize, ny_usize));
-                                    }
-                                }
-                            }
+            while let Some((qx, qy)) = queue.pop_front() {
+                let neighbors = [
+                    (qx as i8 - 1, qy as i8),
+                    (qx as i8 + 1, qy as i8),
+                    (qx as i8, qy as i8 - 1),
+                    (qx as i8, qy as i8 + 1),
+                ];
+
+                for (nx, ny) in neighbors {
+                    if nx < 0 || nx >= BOARD_WIDTH as i8 || ny < 0 || ny >= height as i8 {
+                        continue;
+                    }
+                    let (nx, ny) = (nx as usize, ny as usize);
+                    if excluded_rows.contains(&ny) || in_group[ny][nx] {
+                        continue;
+                    }
+                    if joins_group(board[ny][nx], color) {
+                        in_group[ny][nx] = true;
+                        if board[ny][nx] != Cell::Wildcard {
+                            visited[ny][nx] = true;
                         }
+                        queue.push_back((nx, ny));
+                        cells.push((nx, ny));
                     }
                 }
-
-                if component.len() > 1 {
-                    cells_to_connect.extend(component);
-                }
             }
+
+            groups.push(ColorGroup { color, cells });
+        }
+    }
+
+    groups
+}
+
+pub fn find_and_connect_adjacent_blocks(board: &mut Board, lines_to_clear: &[usize]) {
+    let mut cells_to_connect: Vec<(usize, usize)> = Vec::new();
+
+    for group in find_color_groups(board, BOARD_HEIGHT, lines_to_clear) {
+        if group.cells.len() > 1 {
+            cells_to_connect.extend(group.cells);
         }
     }
 
@@ -73,61 +103,19 @@ pub fn find_and_connect_adjacent_blocks(board: &mut Board, lines_to_clear: &[usi
     }
 }
 
+/// `cleared_line_y`より下の行を含む連結グループについて、各ブロックとそのグループサイズを返す
+/// ワイルドカードは複数のグループに属し得るため、結果には含めない
 pub fn count_connected_blocks(board: &Board, cleared_line_y: usize) -> Vec<(Point, u32)> {
     let mut results = Vec::new();
-    let mut visited = vec![vec![false; BOARD_WIDTH]; BOARD_HEIGHT];
 
-    for y in (cleared_line_y + 1)..BOARD_HEIGHT {
-        for x in 0..BOARD_WIDTH {
-            if let Some(color) = match board[y][x] {
-                Cell::Occupied(c) => Some(c),
-                Cell::Connected { color: c, count: _ } => Some(c),
-                _ => None,
-            } {
-                if visited[y][x] {
-                    continue;
-                }
-
-                let mut component = Vec::new();
-                let mut queue = VecDeque::new();
-
-                visited[y][x] = true;
-                queue.push_back((x, y));
-                component.push((x, y));
-
-                while let Some((qx, qy)) = queue.pop_front() {
-                    let neighbors = [
-                        (qx as i8 - 1, qy as i8),
-                        (qx as i8 + 1, qy as i8),
-                        (qx as i8, qy as i8 - 1),
-                        (qx as i8, qy as i8 + 1),
-                    ];
-
-                    for (nx, ny) in neighbors {
-                        if nx >= 0 && nx < BOARD_WIDTH as i8 && ny >= 0 && ny < BOARD_HEIGHT as i8 {
-                            let (nx_usize, ny_usize) = (nx as usize, ny as usize);
-                            if !visited[ny_usize][nx_usize] {
-                                let neighbor_color = match board[ny_usize][nx_usize] {
-                                    Cell::Occupied(c) => Some(c),
-                                    Cell::Connected { color: c, count: _ } => Some(c),
-                                    _ => None,
-                                };
-                                if let Some(neighbor_color) = neighbor_color {
-                                    if neighbor_color == color {
-                                        visited[ny_usize][nx_usize] = true;
-                                        queue.push_back((nx_usize, ny_usize));
-                                        component.push((nx_usize, ny_usize));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-
-                let component_size = component.len() as u32;
-                for &(px, py) in &component {
-                    results.push(((px, py), component_size));
-                }
+    for group in find_color_groups(board, BOARD_HEIGHT, &[]) {
+        if !group.cells.iter().any(|&(_, y)| y > cleared_line_y) {
+            continue;
+        }
+        let component_size = group.cells.len() as u32;
+        for &(px, py) in &group.cells {
+            if board[py][px] != Cell::Wildcard {
+                results.push(((px, py), component_size));
             }
         }
     }
@@ -135,120 +123,60 @@ pub fn count_connected_blocks(board: &Board, cleared_line_y: usize) -> Vec<(Poin
     results
 }
 
-pub fn remove_isolated_blocks(board: &mut Board, cleared_line_y: usize) {
+/// `cleared_line_y`より下にある、同色ブロックにもワイルドカードにも隣接していないブロックを消去する
+/// 消去したブロック数を返す
+pub fn remove_isolated_blocks(board: &mut Board, cleared_line_y: usize) -> usize {
     let mut blocks_to_remove = Vec::new();
 
-    for y in (cleared_line_y + 1)..BOARD_HEIGHT {
-        for x in 0..BOARD_WIDTH {
-            if let Some(color) = match board[y][x] {
-                Cell::Occupied(c) => Some(c),
-                Cell::Connected { color: c, count: _ } => Some(c),
-                _ => None,
-            } {
-                let mut is_isolated = true;
-                let neighbors = [
-                    (x as i8 - 1, y as i8),
-                    (x as i8 + 1, y as i8),
-                    (x as i8, y as i8 - 1),
-                    (x as i8, y as i8 + 1),
-                ];
-
-                for (nx, ny) in neighbors {
-                    if nx >= 0 && nx < BOARD_WIDTH as i8 && ny >= 0 && ny < BOARD_HEIGHT as i8 {
-                        let neighbor_color = match board[ny as usize][nx as usize] {
-                            Cell::Occupied(c) => Some(c),
-                            Cell::Connected { color: c, count: _ } => Some(c),
-                            _ => None,
-                        };
-                        if let Some(neighbor_color) = neighbor_color {
-                            if neighbor_color == color {
-                                is_isolated = false;
-                                break;
-                            }
-                        }
-                    }
-                }
-
-                if is_isolated {
-                    blocks_to_remove.push((x, y));
-                }
+    for group in find_color_groups(board, BOARD_HEIGHT, &[]) {
+        if let [(x, y)] = group.cells[..] {
+            if y > cleared_line_y {
+                blocks_to_remove.push((x, y));
             }
         }
     }
 
-    for (x, y) in blocks_to_remove {
+    for &(x, y) in &blocks_to_remove {
         board[y][x] = Cell::Empty;
     }
+    blocks_to_remove.len()
 }
 
 /// 盤面全体をスキャンし、10個以上の連結グループを検出して、獲得可能なボーナス段数を計算する
 /// 各グループの連結数から floor(count / 10) 段を計算し、合計を返す
-///
-/// TODO: リファクタリング - BFSロジックの重複を解消
-/// このファイル内の4つの関数（find_and_connect_adjacent_blocks, count_connected_blocks,
-/// remove_isolated_blocks, calculate_chain_bonus）が同じBFSアルゴリズムを重複実装している。
-/// 将来的に共通のBFS関数（find_connected_groups, extract_colorなど）を抽出して、
-/// コードの重複を削減し保守性を向上させる。
+/// ワイルドカードは参加しているすべてのグループの連結数に数えられる
 pub fn calculate_chain_bonus(board: &Board) -> u32 {
-    let mut total_bonus = 0;
-    let mut visited = vec![vec![false; BOARD_WIDTH]; BOARD_HEIGHT];
+    find_color_groups(board, BOARD_HEIGHT, &[])
+        .iter()
+        .map(|group| group.cells.len() as u32)
+        .filter(|&group_size| group_size >= 10)
+        .map(|group_size| group_size / 10)
+        .sum()
+}
 
-    for y in 0..BOARD_HEIGHT {
-        for x in 0..BOARD_WIDTH {
-            if visited[y][x] {
-                continue;
-            }
+/// 爆弾ブロックの爆発で消えるセルの座標を返す
+/// `line_y`の行にある爆弾ごとに周囲3x3（`height`より下とSolidを除く）が対象になる
+/// 巻き込まれた別の爆弾は誘爆しない
+pub fn bomb_blast_cells(board: &Board, line_y: usize, height: usize) -> Vec<Point> {
+    let mut cells = Vec::new();
 
-            if let Some(color) = match board[y][x] {
-                Cell::Occupied(c) => Some(c),
-                Cell::Connected { color: c, count: _ } => Some(c),
-                _ => None,
-            } {
-                // BFSで連結グループを検出
-                let mut component = Vec::new();
-                let mut queue = VecDeque::new();
-
-                visited[y][x] = true;
-                queue.push_back((x, y));
-                component.push((x, y));
-
-                while let Some((qx, qy)) = queue.pop_front() {
-                    let neighbors = [
-                        (qx as i8 - 1, qy as i8),
-                        (qx as i8 + 1, qy as i8),
-                        (qx as i8, qy as i8 - 1),
-                        (qx as i8, qy as i8 + 1),
-                    ];
-
-                    for (nx, ny) in neighbors {
-                        if nx >= 0 && nx < BOARD_WIDTH as i8 && ny >= 0 && ny < BOARD_HEIGHT as i8 {
-                            let (nx_usize, ny_usize) = (nx as usize, ny as usize);
-                            if !visited[ny_usize][nx_usize] {
-                                let neighbor_color = match board[ny_usize][nx_usize] {
-                                    Cell::Occupied(c) => Some(c),
-                                    Cell::Connected { color: c, count: _ } => Some(c),
-                                    _ => None,
-                                };
-                                if let Some(neighbor_color) = neighbor_color {
-                                    if neighbor_color == color {
-                                        visited[ny_usize][nx_usize] = true;
-                                        queue.push_back((nx_usize, ny_usize));
-                                        component.push((nx_usize, ny_usize));
-                                    }
-                                }
-                            }
-                        }
-                    }
+    for (x, &cell) in board[line_y].iter().enumerate() {
+        if cell != Cell::Bomb {
+            continue;
+        }
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (bx, by) = (x as i8 + dx, line_y as i8 + dy);
+                if bx < 0 || bx >= BOARD_WIDTH as i8 || by < 0 || by >= height as i8 {
+                    continue;
                 }
-
-                // 10個以上ならボーナス計算
-                let group_size = component.len() as u32;
-                if group_size >= 10 {
-                    total_bonus += group_size / 10;
+                let (bx, by) = (bx as usize, by as usize);
+                if board[by][bx] != Cell::Solid && !cells.contains(&(bx, by)) {
+                    cells.push((bx, by));
                 }
             }
         }
     }
 
-    total_bonus
+    cells
 }
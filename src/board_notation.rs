@@ -0,0 +1,299 @@
+//! 盤面のテキスト表記
+//!
+//! テスト・パズルファイル・デバッグ出力・バグ報告で共通に使う、人が読み書きできる盤面の表記です。
+//!
+//! ```text
+//! ..........
+//! C.....y3y3y3.
+//! CM*@x.....
+//! ----------
+//! ##########
+//! ```
+//!
+//! - 1行が盤面の1段で、行は下揃えで配置する（上の空き段は省略できる）
+//! - `.` 空き / `C` `M` `Y` `R` `G` `B` 各色のブロック / `#` Solid
+//! - 小文字の色＋数字は連結ブロック（`y3` = 連結数3のYellow。数字を省略すると1）
+//! - `*` ワイルドカード / `@` 爆弾 / `x` ガベージ
+//! - `-` だけの行はフィールドの高さ（`current_board_height`）の境界で、その下の段はプレイ領域外
+//!   （省略した場合は底に連続するSolidラインの数から求める）
+//! - 空白は読み飛ばすので、連結数の桁をそろえるために空白を入れてもよい
+
+use std::fmt;
+
+use crate::cell::{Board, Cell};
+use crate::config::{BOARD_HEIGHT, BOARD_WIDTH};
+use crate::game_color::GameColor;
+
+/// 色を表す文字（大文字）。盤面に置かれる色だけを扱う
+fn color_letter(color: GameColor) -> Option<char> {
+    match color {
+        GameColor::Cyan => Some('C'),
+        GameColor::Magenta => Some('M'),
+        GameColor::Yellow => Some('Y'),
+        GameColor::Red => Some('R'),
+        GameColor::Green => Some('G'),
+        GameColor::Blue => Some('B'),
+        _ => None,
+    }
+}
+
+fn letter_color(letter: char) -> Option<GameColor> {
+    match letter.to_ascii_uppercase() {
+        'C' => Some(GameColor::Cyan),
+        'M' => Some(GameColor::Magenta),
+        'Y' => Some(GameColor::Yellow),
+        'R' => Some(GameColor::Red),
+        'G' => Some(GameColor::Green),
+        'B' => Some(GameColor::Blue),
+        _ => None,
+    }
+}
+
+/// 1セル分の表記（表記できない色は`?`）
+pub fn cell_token(cell: Cell) -> String {
+    match cell {
+        Cell::Empty => ".".to_string(),
+        Cell::Solid => "#".to_string(),
+        Cell::Wildcard => "*".to_string(),
+        Cell::Bomb => "@".to_string(),
+        Cell::Garbage => "x".to_string(),
+        Cell::Occupied(color) => color_letter(color).unwrap_or('?').to_string(),
+        Cell::Connected { color, count } => format!(
+            "{}{}",
+            color_letter(color).map_or('?', |letter| letter.to_ascii_lowercase()),
+            count
+        ),
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&cell_token(*self))
+    }
+}
+
+/// Debug出力も同じ表記にする（表記できない色だけは、どの色かわかるよう色名で書く）
+impl fmt::Debug for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Cell::Occupied(color) if color_letter(color).is_none() => {
+                write!(f, "Occupied({:?})", color)
+            }
+            Cell::Connected { color, count } if color_letter(color).is_none() => {
+                write!(f, "Connected({:?}, {})", color, count)
+            }
+            cell => f.write_str(&cell_token(cell)),
+        }
+    }
+}
+
+/// 1段分の表記を読み取る
+fn parse_row(row: &str) -> Result<Vec<Cell>, String> {
+    let mut cells = Vec::new();
+    let mut chars = row.chars().filter(|c| !c.is_whitespace()).peekable();
+
+    while let Some(letter) = chars.next() {
+        let cell = match letter {
+            '.' => Cell::Empty,
+            '#' => Cell::Solid,
+            '*' => Cell::Wildcard,
+            '@' => Cell::Bomb,
+            'x' => Cell::Garbage,
+            _ => match letter_color(letter) {
+                Some(color) if letter.is_ascii_uppercase() => Cell::Occupied(color),
+                Some(color) => {
+                    let mut digits = String::new();
+                    while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+                        digits.push(digit);
+                    }
+                    let count = if digits.is_empty() {
+                        1
+                    } else {
+                        digits
+                            .parse::<u8>()
+                            .map_err(|_| format!("invalid chain count '{}' in: {}", digits, row))?
+                    };
+                    Cell::Connected { color, count }
+                }
+                None => return Err(format!("unknown board cell '{}' in: {}", letter, row)),
+            },
+        };
+        cells.push(cell);
+    }
+
+    if cells.len() != BOARD_WIDTH {
+        return Err(format!("board row must be {} cells: {}", BOARD_WIDTH, row));
+    }
+    Ok(cells)
+}
+
+fn is_height_boundary(line: &str) -> bool {
+    !line.is_empty() && line.chars().all(|c| c == '-')
+}
+
+/// 底に連続するSolidラインの数から求めたフィールドの高さ
+pub fn height_from_solid_rows(board: &Board) -> usize {
+    let solid_rows = board
+        .iter()
+        .rev()
+        .take_while(|row| row.iter().all(|&cell| cell == Cell::Solid))
+        .count();
+    board.len() - solid_rows
+}
+
+/// 表記から盤面とフィールドの高さを読み取る
+///
+/// 空行は読み飛ばす。高さの境界行がなければ底のSolidライン数から高さを求める
+pub fn parse_board(text: &str) -> Result<(Board, usize), String> {
+    let mut rows = Vec::new();
+    let mut boundary = None;
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if is_height_boundary(line) {
+            if boundary.is_some() {
+                return Err("board has more than one height boundary".to_string());
+            }
+            boundary = Some(rows.len());
+        } else {
+            rows.push(parse_row(line)?);
+        }
+    }
+    if rows.len() > BOARD_HEIGHT {
+        return Err(format!("board has more than {} rows", BOARD_HEIGHT));
+    }
+
+    let top = BOARD_HEIGHT - rows.len();
+    let mut board = vec![vec![Cell::Empty; BOARD_WIDTH]; top];
+    board.extend(rows);
+
+    let height = match boundary {
+        Some(rows_above) => top + rows_above,
+        None => height_from_solid_rows(&board),
+    };
+    Ok((board, height))
+}
+
+/// 盤面を表記に変換する
+///
+/// 上の空き段は省略し、フィールドの高さが盤面より低ければ境界行を入れる。
+/// 連結数のある盤面では、列がそろうようにセルを空白で区切る
+pub fn board_to_text(board: &Board, current_board_height: usize) -> String {
+    let tokens: Vec<Vec<String>> = board
+        .iter()
+        .map(|row| row.iter().map(|&cell| cell_token(cell)).collect())
+        .collect();
+    let width = tokens.iter().flatten().map(String::len).max().unwrap_or(1);
+    let first_row = board
+        .iter()
+        .position(|row| row.iter().any(|&cell| cell != Cell::Empty))
+        .unwrap_or(board.len())
+        .min(current_board_height);
+
+    let mut lines = Vec::new();
+    for (y, row) in tokens.iter().enumerate().skip(first_row) {
+        if y == current_board_height {
+            lines.push("-".repeat(BOARD_WIDTH));
+        }
+        let line = if width == 1 {
+            row.concat()
+        } else {
+            row.iter()
+                .map(|token| format!("{:<width$}", token, width = width))
+                .collect::<Vec<_>>()
+                .join(" ")
+                .trim_end()
+                .to_string()
+        };
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_every_cell_kind() {
+        let (board, height) = parse_board("C M Y R G B . * @ x\nc12 m y3 # # # # # # #").unwrap();
+        assert_eq!(height, BOARD_HEIGHT);
+        let upper = &board[BOARD_HEIGHT - 2];
+        assert_eq!(upper[0], Cell::Occupied(GameColor::Cyan));
+        assert_eq!(upper[5], Cell::Occupied(GameColor::Blue));
+        assert_eq!(upper[6], Cell::Empty);
+        assert_eq!(upper[7], Cell::Wildcard);
+        assert_eq!(upper[8], Cell::Bomb);
+        assert_eq!(upper[9], Cell::Garbage);
+        let bottom = &board[BOARD_HEIGHT - 1];
+        assert_eq!(
+            bottom[0],
+            Cell::Connected {
+                color: GameColor::Cyan,
+                count: 12
+            }
+        );
+        assert_eq!(
+            bottom[1],
+            Cell::Connected {
+                color: GameColor::Magenta,
+                count: 1
+            }
+        );
+        assert_eq!(bottom[9], Cell::Solid);
+        assert!(board[..BOARD_HEIGHT - 2]
+            .iter()
+            .all(|row| row.iter().all(|&cell| cell == Cell::Empty)));
+    }
+
+    #[test]
+    fn test_height_boundary_and_solid_rows() {
+        // 境界行がなければ底のSolidライン数から高さを求める
+        let (_, height) = parse_board("C.........\n##########\n##########").unwrap();
+        assert_eq!(height, BOARD_HEIGHT - 2);
+
+        // 境界行があればそれに従う（Solidラインの上にプレイ領域外の段があってもよい）
+        let (board, height) =
+            parse_board("C.........\n----------\nMMMMMMMMMM\n##########").unwrap();
+        assert_eq!(height, BOARD_HEIGHT - 2);
+        assert_eq!(
+            board[BOARD_HEIGHT - 2][0],
+            Cell::Occupied(GameColor::Magenta)
+        );
+
+        assert!(parse_board("----\n..........\n----").is_err());
+    }
+
+    #[test]
+    fn test_board_round_trips_through_text() {
+        let text = "\
+c2 .  .  .  .  .  .  .  .  .
+c2 y3 y3 y3 *  @  x  .  .  M
+----------
+#  #  #  #  #  #  #  #  #  #";
+        let (board, height) = parse_board(text).unwrap();
+        assert_eq!(height, BOARD_HEIGHT - 1);
+        assert_eq!(board_to_text(&board, height), text);
+
+        // 連結数がなければ1セル1文字で詰めて出力する
+        let (board, height) = parse_board("CMY....YMC").unwrap();
+        assert_eq!(board_to_text(&board, height), "CMY....YMC");
+        assert_eq!(board_to_text(&board, BOARD_HEIGHT), "CMY....YMC");
+    }
+
+    #[test]
+    fn test_invalid_rows_are_rejected() {
+        assert!(parse_board("CMY").is_err());
+        assert!(parse_board("CMY....YMZ").is_err());
+        assert!(parse_board("c999......").is_err());
+        assert!(parse_board(&"..........\n".repeat(BOARD_HEIGHT + 1)).is_err());
+    }
+
+    #[test]
+    fn test_cell_display_uses_notation() {
+        let cell = Cell::Connected {
+            color: GameColor::Yellow,
+            count: 7,
+        };
+        assert_eq!(cell.to_string(), "y7");
+        assert_eq!(Cell::Solid.to_string(), "#");
+    }
+}
@@ -0,0 +1,640 @@
+//! 外部ボットとの対戦
+//!
+//! コミュニティのTetris Bot Protocol（TBP）にならった、1行1メッセージのJSONで子プロセスの
+//! ボットと通信します。毎手、盤面（色・連結数・Solidライン）とピース列を`start`で送り直し、
+//! `suggest`への返答の`suggestion`で配置か入力列を受け取ります。
+//!
+//! 1. ボット → `info`（名前など）
+//! 2. エンジン → `rules`、ボット → `ready`
+//! 3. 毎手: エンジン → `start`・`suggest`、ボット → `suggestion`
+//! 4. 終了時: エンジン → `stop`・`quit`
+//!
+//! 盤面の解決には`simulate_placement`を使うので、結果はゲーム本体と一致します。
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::board_notation::{self, cell_token};
+use crate::cell::{Board, Cell};
+use crate::config::{BOARD_HEIGHT, BOARD_WIDTH};
+use crate::game_input::GameInput;
+use crate::gravity::GravityMode;
+use crate::json::{self, Json};
+use crate::placement;
+use crate::scoring::{CustomScoreSystem, SolidPurgeMode};
+use crate::simulator::{self, SimulationRules};
+use crate::tetromino::{PieceGenerator, SpecialBlockRates, Tetromino};
+
+/// ボットとの対戦を打ち切る理由
+#[derive(Clone, Debug, PartialEq)]
+pub enum BotError {
+    /// 起動や書き込みの失敗
+    Io(String),
+    /// 制限時間内に返答がなかった
+    Timeout(Duration),
+    /// ボットが出力を閉じた（終了した）
+    Closed,
+    /// JSONとして読めない、または必要な項目がないメッセージ
+    InvalidMessage(String),
+    /// 期待と違う種類のメッセージ
+    Unexpected { expected: String, got: String },
+    /// ボットが`error`メッセージを送ってきた
+    Reported(String),
+    /// 置けない配置・動かせない入力
+    IllegalMove(String),
+}
+
+impl fmt::Display for BotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BotError::Io(message) => write!(f, "bot i/o error: {}", message),
+            BotError::Timeout(timeout) => {
+                write!(f, "bot did not respond within {} ms", timeout.as_millis())
+            }
+            BotError::Closed => write!(f, "bot closed its output"),
+            BotError::InvalidMessage(message) => write!(f, "invalid message from bot: {}", message),
+            BotError::Unexpected { expected, got } => {
+                write!(f, "expected '{}' from bot but got '{}'", expected, got)
+            }
+            BotError::Reported(reason) => write!(f, "bot reported an error: {}", reason),
+            BotError::IllegalMove(message) => write!(f, "illegal move: {}", message),
+        }
+    }
+}
+
+/// ボットとの1行1メッセージの通信路
+pub struct BotChannel {
+    writer: Box<dyn Write + Send>,
+    lines: Receiver<io::Result<String>>,
+    timeout: Duration,
+    child: Option<Child>,
+}
+
+impl BotChannel {
+    /// 任意の読み書き先で通信路を作る（読み取りは別スレッドで行い、返答を時間制限つきで待つ)
+    pub fn new(
+        writer: impl Write + Send + 'static,
+        reader: impl Read + Send + 'static,
+        timeout: Duration,
+    ) -> Self {
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            writer: Box::new(writer),
+            lines,
+            timeout,
+            child: None,
+        }
+    }
+
+    /// コマンドライン（空白区切り）でボットを起動し、標準入出力で通信する
+    pub fn spawn(command_line: &str, timeout: Duration) -> Result<Self, BotError> {
+        let mut words = command_line.split_whitespace();
+        let program = words
+            .next()
+            .ok_or_else(|| BotError::Io("empty bot command".to_string()))?;
+        let mut child = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|err| BotError::Io(format!("cannot start '{}': {}", program, err)))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let mut channel = Self::new(stdin, stdout, timeout);
+        channel.child = Some(child);
+        Ok(channel)
+    }
+
+    fn send(&mut self, message: &Json) -> Result<(), BotError> {
+        writeln!(self.writer, "{}", message)
+            .and_then(|_| self.writer.flush())
+            .map_err(|err| BotError::Io(err.to_string()))
+    }
+
+    /// 次のメッセージを受け取る（ボットの`error`はエラーとして返す）
+    fn receive(&mut self) -> Result<Json, BotError> {
+        let line = match self.lines.recv_timeout(self.timeout) {
+            Ok(Ok(line)) => line,
+            Ok(Err(err)) => return Err(BotError::Io(err.to_string())),
+            Err(RecvTimeoutError::Timeout) => return Err(BotError::Timeout(self.timeout)),
+            Err(RecvTimeoutError::Disconnected) => return Err(BotError::Closed),
+        };
+        let message = json::parse(&line)
+            .map_err(|err| BotError::InvalidMessage(format!("{} in {}", err, line)))?;
+        if message_type(&message) == "error" {
+            let reason = message.get("reason").and_then(Json::as_str).unwrap_or("");
+            return Err(BotError::Reported(reason.to_string()));
+        }
+        Ok(message)
+    }
+
+    /// 指定した種類のメッセージを受け取る
+    fn expect(&mut self, expected: &str) -> Result<Json, BotError> {
+        let message = self.receive()?;
+        if message_type(&message) != expected {
+            return Err(BotError::Unexpected {
+                expected: expected.to_string(),
+                got: message_type(&message).to_string(),
+            });
+        }
+        Ok(message)
+    }
+}
+
+impl Drop for BotChannel {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+fn message_type(message: &Json) -> &str {
+    message.get("type").and_then(Json::as_str).unwrap_or("")
+}
+
+const ORIENTATIONS: [&str; 4] = ["north", "east", "south", "west"];
+
+/// 入力列で使う入力名
+const INPUT_NAMES: [(&str, GameInput); 6] = [
+    ("left", GameInput::MoveLeft),
+    ("right", GameInput::MoveRight),
+    ("cw", GameInput::RotateClockwise),
+    ("ccw", GameInput::RotateCounterClockwise),
+    ("soft_drop", GameInput::SoftDrop),
+    ("hard_drop", GameInput::HardDrop),
+];
+
+fn piece_json(piece: &Tetromino) -> Json {
+    Json::object([
+        ("shape", format!("{:?}", piece.shape).into()),
+        (
+            "orientation",
+            ORIENTATIONS[piece.get_rotation_state() as usize].into(),
+        ),
+        ("x", (piece.pos.0 as i64).into()),
+        ("y", (piece.pos.1 as i64).into()),
+        (
+            "cells",
+            Json::Array(
+                piece
+                    .iter_cells()
+                    .map(|(_, cell)| cell_token(cell).into())
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+/// ボットと対戦するゲームの状態（ピースを置くたびに`simulate_placement`で解決する）
+#[derive(Clone, Debug)]
+pub struct BotGame {
+    pub board: Board,
+    pub current_board_height: usize,
+    pub score: CustomScoreSystem,
+    pub lines_cleared: u32,
+    pub pieces_placed: u32,
+    pub rules: SimulationRules,
+    /// 操作中のピース（出現位置に置けずゲームオーバーになったらNone）
+    pub current_piece: Option<Tetromino>,
+    pub next_piece: Tetromino,
+    pieces: PieceGenerator,
+    special_block_rates: SpecialBlockRates,
+}
+
+impl BotGame {
+    pub fn new(
+        rules: SimulationRules,
+        special_block_rates: SpecialBlockRates,
+        pieces: PieceGenerator,
+    ) -> Self {
+        let mut pieces = pieces;
+        let current_piece = pieces.next_piece(&special_block_rates);
+        let next_piece = pieces.next_piece(&special_block_rates);
+        Self {
+            board: vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT],
+            current_board_height: BOARD_HEIGHT,
+            score: CustomScoreSystem::new(),
+            lines_cleared: 0,
+            pieces_placed: 0,
+            rules,
+            current_piece: Some(current_piece),
+            next_piece,
+            pieces,
+            special_block_rates,
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.current_piece.is_none()
+    }
+
+    fn rules_message(&self) -> Json {
+        let gravity = match self.rules.gravity_mode {
+            GravityMode::Naive => "naive",
+            GravityMode::Sticky => "sticky",
+            GravityMode::Cascade => "cascade",
+        };
+        let solid_purge = match self.rules.solid_purge_mode {
+            SolidPurgeMode::Automatic => "automatic",
+            SolidPurgeMode::Manual => "manual",
+        };
+        Json::object([
+            ("type", "rules".into()),
+            ("width", (BOARD_WIDTH as u32).into()),
+            ("height", (BOARD_HEIGHT as u32).into()),
+            ("gravity", gravity.into()),
+            ("solid_purge", solid_purge.into()),
+        ])
+    }
+
+    /// 現在の状態を伝える`start`メッセージ（盤面は上の段から、セルは盤面表記のトークン）
+    fn start_message(&self) -> Json {
+        let board = self
+            .board
+            .iter()
+            .map(|row| Json::Array(row.iter().map(|&cell| cell_token(cell).into()).collect()))
+            .collect();
+        let queue = self
+            .current_piece
+            .iter()
+            .chain([&self.next_piece])
+            .map(piece_json)
+            .collect();
+        let max_chains = &self.score.max_chains;
+        Json::object([
+            ("type", "start".into()),
+            // このゲームにホールドはない
+            ("hold", Json::Null),
+            ("queue", Json::Array(queue)),
+            ("board", Json::Array(board)),
+            (
+                "solid_rows",
+                ((BOARD_HEIGHT - self.current_board_height) as u32).into(),
+            ),
+            ("combo", self.score.combo.into()),
+            ("back_to_back", self.score.back_to_back.into()),
+            ("chain_bonus", self.score.chain_bonus.into()),
+            (
+                "max_chains",
+                Json::object([
+                    ("cyan", max_chains.cyan.into()),
+                    ("magenta", max_chains.magenta.into()),
+                    ("yellow", max_chains.yellow.into()),
+                ]),
+            ),
+            ("score", self.score.score.total().into()),
+            ("lines", self.lines_cleared.into()),
+        ])
+    }
+
+    /// `suggestion`の手を検証して置く
+    ///
+    /// 手は`location`（ピースの位置`x`・`y`と向き`orientation`）か`inputs`（入力名の列。
+    /// 最後に接地するまで落としてから固定する）のどちらか
+    pub fn play(&mut self, step: &Json) -> Result<(), BotError> {
+        let Some(piece) = &self.current_piece else {
+            return Err(BotError::IllegalMove("the game is over".to_string()));
+        };
+        let landed = if let Some(location) = step.get("location") {
+            self.resolve_location(piece, location)?
+        } else if let Some(inputs) = step.get("inputs").and_then(Json::as_array) {
+            self.resolve_inputs(piece, inputs)?
+        } else {
+            return Err(BotError::InvalidMessage(format!(
+                "move needs 'location' or 'inputs': {}",
+                step
+            )));
+        };
+
+        let outcome = simulator::simulate_placement(
+            &self.board,
+            self.current_board_height,
+            &self.score,
+            &landed,
+            self.rules,
+        );
+        self.board = outcome.board;
+        self.current_board_height = outcome.current_board_height;
+        self.score = outcome.score;
+        self.lines_cleared += outcome.lines_cleared;
+        self.pieces_placed += 1;
+
+        // 次のピースを出す（出現位置に置けなければゲームオーバー）
+        let next = std::mem::replace(
+            &mut self.next_piece,
+            self.pieces.next_piece(&self.special_block_rates),
+        );
+        self.current_piece =
+            placement::is_valid_position(&self.board, self.current_board_height, &next)
+                .then_some(next);
+        Ok(())
+    }
+
+    fn resolve_location(&self, piece: &Tetromino, location: &Json) -> Result<Tetromino, BotError> {
+        let field = |key: &str| location.get(key).and_then(Json::as_i64);
+        let orientation = location.get("orientation").and_then(Json::as_str);
+        let (Some(x), Some(y), Some(orientation)) = (field("x"), field("y"), orientation) else {
+            return Err(BotError::InvalidMessage(format!(
+                "location needs x, y and orientation: {}",
+                location
+            )));
+        };
+        let rotation = ORIENTATIONS
+            .iter()
+            .position(|&name| name == orientation)
+            .ok_or_else(|| {
+                BotError::IllegalMove(format!("unknown orientation '{}'", orientation))
+            })?;
+
+        let mut target = piece.clone();
+        while target.get_rotation_state() as usize != rotation {
+            target = target.rotated();
+        }
+        // 盤面の座標はi8なので、収まらない値はそのまま切り詰めずに置けない配置として扱う
+        let (Ok(target_x), Ok(target_y)) = (i8::try_from(x), i8::try_from(y)) else {
+            return Err(BotError::IllegalMove(format!(
+                "location x={} y={} is outside the board",
+                x, y
+            )));
+        };
+        target.pos = (target_x, target_y);
+        placement::path_to(&self.board, self.current_board_height, piece, &target)
+            .map(|_| target)
+            .ok_or_else(|| {
+                BotError::IllegalMove(format!(
+                    "{:?} piece cannot reach a resting place at x={} y={} {}",
+                    piece.shape, x, y, orientation
+                ))
+            })
+    }
+
+    fn resolve_inputs(&self, piece: &Tetromino, inputs: &[Json]) -> Result<Tetromino, BotError> {
+        let mut moved = piece.clone();
+        for (index, input) in inputs.iter().enumerate() {
+            let name = input.as_str().unwrap_or("");
+            let game_input = INPUT_NAMES
+                .iter()
+                .find(|(input_name, _)| *input_name == name)
+                .map(|&(_, game_input)| game_input)
+                .ok_or_else(|| BotError::IllegalMove(format!("unknown input '{}'", input)))?;
+            moved =
+                placement::apply_input(&self.board, self.current_board_height, &moved, game_input)
+                    .ok_or_else(|| {
+                        BotError::IllegalMove(format!(
+                            "input {} ('{}') is blocked",
+                            index + 1,
+                            name
+                        ))
+                    })?;
+        }
+        Ok(placement::drop_to_floor(
+            &self.board,
+            self.current_board_height,
+            &moved,
+        ))
+    }
+
+    /// 終了時の結果（標準出力に表示する）
+    pub fn summary(&self) -> String {
+        format!(
+            "pieces: {}\nlines: {}\nscore: {}\n{}",
+            self.pieces_placed,
+            self.lines_cleared,
+            self.score.score.total(),
+            board_notation::board_to_text(&self.board, self.current_board_height)
+        )
+    }
+}
+
+/// ボットと`piece_limit`個まで（またはゲームオーバーまで）対戦し、ボットの名前を返す
+pub fn run_bot_game(
+    channel: &mut BotChannel,
+    game: &mut BotGame,
+    piece_limit: u32,
+) -> Result<String, BotError> {
+    let info = channel.expect("info")?;
+    let name = info
+        .get("name")
+        .and_then(Json::as_str)
+        .unwrap_or("unnamed bot")
+        .to_string();
+    channel.send(&game.rules_message())?;
+    channel.expect("ready")?;
+
+    while !game.is_over() && game.pieces_placed < piece_limit {
+        channel.send(&game.start_message())?;
+        channel.send(&Json::object([("type", "suggest".into())]))?;
+        let suggestion = channel.expect("suggestion")?;
+        let step = suggestion
+            .get("moves")
+            .and_then(Json::as_array)
+            .and_then(|moves| moves.first())
+            .ok_or_else(|| {
+                BotError::InvalidMessage(format!("suggestion has no moves: {}", suggestion))
+            })?;
+        game.play(step)?;
+    }
+
+    channel.send(&Json::object([("type", "stop".into())]))?;
+    channel.send(&Json::object([("type", "quit".into())]))?;
+    Ok(name)
+}
+
+/// 参照用のボット（`--echo-bot`）: 受け取ったピースをそのままハードドロップで置く
+pub fn run_echo_bot(input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let info = Json::object([
+        ("type", "info".into()),
+        ("name", "echo-bot".into()),
+        ("version", env!("CARGO_PKG_VERSION").into()),
+        ("author", "thud-and-tile".into()),
+        ("features", Json::Array(Vec::new())),
+    ]);
+    writeln!(output, "{}", info)?;
+    output.flush()?;
+
+    for line in input.lines() {
+        let line = line?;
+        let Ok(message) = json::parse(&line) else {
+            continue;
+        };
+        let reply = match message_type(&message) {
+            "rules" => Json::object([("type", "ready".into())]),
+            "suggest" => Json::object([
+                ("type", "suggestion".into()),
+                (
+                    "moves",
+                    Json::Array(vec![Json::object([(
+                        "inputs",
+                        Json::Array(vec!["hard_drop".into()]),
+                    )])]),
+                ),
+            ]),
+            "quit" => break,
+            _ => continue,
+        };
+        writeln!(output, "{}", reply)?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_game() -> BotGame {
+        BotGame::new(
+            SimulationRules::default(),
+            SpecialBlockRates::default(),
+            PieceGenerator::new(7),
+        )
+    }
+
+    /// 返答を決め打ちしたボット（エンジンからのメッセージは読み捨てる）
+    fn scripted_bot(replies: &[&str], timeout: Duration) -> BotChannel {
+        let (engine_reader, mut bot_writer) = io::pipe().unwrap();
+        let (bot_reader, engine_writer) = io::pipe().unwrap();
+        let replies: Vec<String> = replies.iter().map(|reply| reply.to_string()).collect();
+        thread::spawn(move || {
+            for reply in replies {
+                writeln!(bot_writer, "{}", reply).unwrap();
+            }
+            // エンジンが通信路を閉じるまで出力を開いたままにする
+            io::copy(&mut BufReader::new(bot_reader), &mut io::sink()).unwrap();
+        });
+        BotChannel::new(engine_writer, engine_reader, timeout)
+    }
+
+    #[test]
+    fn test_echo_bot_plays_until_the_piece_limit() {
+        let (engine_reader, bot_writer) = io::pipe().unwrap();
+        let (bot_reader, engine_writer) = io::pipe().unwrap();
+        let bot = thread::spawn(move || run_echo_bot(BufReader::new(bot_reader), bot_writer));
+
+        let mut channel = BotChannel::new(engine_writer, engine_reader, Duration::from_secs(5));
+        let mut game = new_game();
+        let name = run_bot_game(&mut channel, &mut game, 5).unwrap();
+
+        assert_eq!(name, "echo-bot");
+        assert_eq!(game.pieces_placed, 5);
+        assert!(game.board.iter().flatten().any(|&cell| cell != Cell::Empty));
+        drop(channel);
+        bot.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_start_message_describes_board_and_queue() {
+        let mut game = new_game();
+        game.board[BOARD_HEIGHT - 1][0] = Cell::Connected {
+            color: crate::game_color::GameColor::Cyan,
+            count: 3,
+        };
+        game.board[BOARD_HEIGHT - 1][1] = Cell::Solid;
+        let message = game.start_message();
+
+        let board = message.get("board").and_then(Json::as_array).unwrap();
+        let bottom = board[BOARD_HEIGHT - 1].as_array().unwrap();
+        assert_eq!(bottom[0], Json::from("c3"));
+        assert_eq!(bottom[1], Json::from("#"));
+        assert_eq!(message.get("hold"), Some(&Json::Null));
+        let queue = message.get("queue").and_then(Json::as_array).unwrap();
+        assert_eq!(queue.len(), 2);
+        assert_eq!(
+            queue[0].get("orientation").and_then(Json::as_str),
+            Some("north")
+        );
+    }
+
+    #[test]
+    fn test_location_must_be_reachable_and_resting() {
+        let mut game = new_game();
+        let piece = game.current_piece.clone().unwrap();
+        let floating = format!(
+            r#"{{"location":{{"x":{},"y":{},"orientation":"north"}}}}"#,
+            piece.pos.0, piece.pos.1
+        );
+        let error = game.play(&json::parse(&floating).unwrap()).unwrap_err();
+        assert!(matches!(error, BotError::IllegalMove(_)), "{:?}", error);
+        assert_eq!(game.pieces_placed, 0);
+
+        // 真下に落とした位置なら置ける
+        let landed = placement::drop_to_floor(&game.board, game.current_board_height, &piece);
+        let resting = format!(
+            r#"{{"location":{{"x":{},"y":{},"orientation":"north"}}}}"#,
+            landed.pos.0, landed.pos.1
+        );
+        game.play(&json::parse(&resting).unwrap()).unwrap();
+        assert_eq!(game.pieces_placed, 1);
+    }
+
+    #[test]
+    fn test_location_outside_i8_is_rejected_instead_of_wrapping() {
+        let mut game = new_game();
+        let piece = game.current_piece.clone().unwrap();
+        let landed = placement::drop_to_floor(&game.board, game.current_board_height, &piece);
+        // 256を足すとi8に切り詰めたときに着地位置と同じxになる
+        let wrapped = format!(
+            r#"{{"location":{{"x":{},"y":{},"orientation":"north"}}}}"#,
+            landed.pos.0 as i64 + 256,
+            landed.pos.1
+        );
+        let error = game.play(&json::parse(&wrapped).unwrap()).unwrap_err();
+        assert!(matches!(error, BotError::IllegalMove(_)), "{:?}", error);
+        assert_eq!(game.pieces_placed, 0);
+    }
+
+    #[test]
+    fn test_blocked_input_is_rejected_with_its_position() {
+        let mut game = new_game();
+        let moves =
+            json::parse(&format!(r#"{{"inputs":[{}]}}"#, ["\"left\""; 8].join(","))).unwrap();
+        let error = game.play(&moves).unwrap_err();
+        assert!(error.to_string().contains("is blocked"), "{}", error);
+
+        let unknown = json::parse(r#"{"inputs":["jump"]}"#).unwrap();
+        assert_eq!(
+            game.play(&unknown).unwrap_err(),
+            BotError::IllegalMove("unknown input '\"jump\"'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_silent_bot_times_out() {
+        let mut channel = scripted_bot(&[], Duration::from_millis(50));
+        let error = run_bot_game(&mut channel, &mut new_game(), 1).unwrap_err();
+        assert_eq!(error, BotError::Timeout(Duration::from_millis(50)));
+    }
+
+    #[test]
+    fn test_bot_errors_and_unexpected_messages_end_the_game() {
+        let mut channel = scripted_bot(
+            &[
+                r#"{"type":"info","name":"broken"}"#,
+                r#"{"type":"error","reason":"unsupported rules"}"#,
+            ],
+            Duration::from_secs(5),
+        );
+        let error = run_bot_game(&mut channel, &mut new_game(), 1).unwrap_err();
+        assert_eq!(error, BotError::Reported("unsupported rules".to_string()));
+
+        let mut channel = scripted_bot(&[r#"{"type":"ready"}"#], Duration::from_secs(5));
+        let error = run_bot_game(&mut channel, &mut new_game(), 1).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected 'info' from bot but got 'ready'"
+        );
+    }
+}
@@ -5,16 +5,52 @@ pub enum Cell {
     Empty,
     Occupied(GameColor),
     Solid,
-    Connected { color: GameColor, count: u8 },
+    Connected {
+        color: GameColor,
+        count: u8,
+    },
+    /// どの色の連結グループにも参加できる特殊ブロック
+    Wildcard,
+    /// 自身の行が消去されると周囲3x3を巻き込んで消える特殊ブロック
+    Bomb,
+    /// どのブロックとも連結しない灰色のブロック
+    Garbage,
 }
 
-impl std::fmt::Debug for Cell {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Cell {
+    /// 連結判定に使う色（色を持たないセルはNone）
+    pub fn color(&self) -> Option<GameColor> {
         match self {
-            Cell::Empty => write!(f, "Empty"),
-            Cell::Occupied(color) => write!(f, "Occupied({:?})", color),
-            Cell::Solid => write!(f, "Solid"),
-            Cell::Connected { color, count } => write!(f, "Connected({:?}, {})", color, count),
+            Cell::Occupied(color) => Some(*color),
+            Cell::Connected { color, count: _ } => Some(*color),
+            _ => None,
+        }
+    }
+
+    /// ライン判定でブロックとして数えるセルかどうか（SolidとEmpty以外）
+    pub fn is_block(&self) -> bool {
+        !matches!(self, Cell::Empty | Cell::Solid)
+    }
+}
+
+/// ピースを構成する各ブロックの種類
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlockKind {
+    #[default]
+    Normal,
+    Wildcard,
+    Bomb,
+    Garbage,
+}
+
+impl BlockKind {
+    /// 固定時に盤面へ置かれるセル
+    pub fn to_cell(self, color: GameColor) -> Cell {
+        match self {
+            BlockKind::Normal => Cell::Occupied(color),
+            BlockKind::Wildcard => Cell::Wildcard,
+            BlockKind::Bomb => Cell::Bomb,
+            BlockKind::Garbage => Cell::Garbage,
         }
     }
 }
@@ -32,8 +68,27 @@ mod tests {
             color: GameColor::Red,
             count: 5,
         };
-        // This test will fail initially because the default Debug output is different.
-        // It will pass once a custom Debug impl is added.
-        assert_eq!(format!("{:?}", cell), "Connected(Red, 5)");
+        // Debug出力は盤面表記（src/board_notation.rs）と同じ
+        assert_eq!(format!("{:?}", cell), "r5");
+        assert_eq!(format!("{:?}", vec![Cell::Empty, Cell::Solid]), "[., #]");
+        // 表記できない色は色名で書く
+        assert_eq!(
+            format!("{:?}", Cell::Occupied(GameColor::Grey)),
+            "Occupied(Grey)"
+        );
+    }
+
+    #[test]
+    fn test_special_cells_fill_lines_but_have_no_color() {
+        for cell in [Cell::Wildcard, Cell::Bomb, Cell::Garbage] {
+            assert!(cell.is_block());
+            assert_eq!(cell.color(), None);
+        }
+        assert!(!Cell::Solid.is_block());
+        assert_eq!(
+            BlockKind::Normal.to_cell(GameColor::Cyan),
+            Cell::Occupied(GameColor::Cyan)
+        );
+        assert_eq!(BlockKind::Bomb.to_cell(GameColor::Cyan), Cell::Bomb);
     }
 }
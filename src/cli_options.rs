@@ -0,0 +1,335 @@
+//! CLI版のコマンドライン引数
+//!
+//! ゲームルールの切り替えなど、起動時に指定できるオプションを解析します。
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::cell::Board;
+use crate::config::{BOT_PIECE_LIMIT, BOT_TIMEOUT};
+use crate::fumen;
+use crate::gravity::GravityMode;
+use crate::layout::BoardStyle;
+use crate::scoring::SolidPurgeMode;
+use crate::tetromino::SpecialBlockRates;
+use crate::theme::Charset;
+
+/// 起動時オプション
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameOptions {
+    pub solid_purge_mode: SolidPurgeMode,
+    pub special_block_rates: SpecialBlockRates,
+    pub gravity_mode: GravityMode,
+    /// 組み込みパズルの代わりに読み込むパズルパック
+    pub puzzle_pack: Option<PathBuf>,
+    /// `--board`で指定したPRACTICEモードの開始盤面とフィールドの高さ（ほかのモードでは使わない）
+    pub start_board: Option<(Board, usize)>,
+    /// `--bot`で起動する外部ボットのコマンドライン（指定時は画面を使わずに対戦する）
+    pub bot_command: Option<String>,
+    /// ボット対戦で置くピース数の上限
+    pub bot_pieces: u32,
+    /// ボットの返答を待つ時間
+    pub bot_timeout: Duration,
+    /// `--echo-bot`: 参照用ボットとして標準入出力で応答する
+    pub echo_bot: bool,
+    /// 盤面のマスの描き方（`--half-block`で上下2マスを1文字にまとめる）
+    pub board_style: BoardStyle,
+    /// `--theme`で指定した組み込みテーマの名前かテーマファイルのパス
+    pub theme: Option<String>,
+    /// `--glyphs`: 色ごとに形の違う記号で描く（テーマの`glyphs`と同じ）
+    pub glyphs: bool,
+    /// `--charset`で指定した文字セット（Noneならロケールから判定する）
+    pub charset: Option<Charset>,
+    /// `--narrate`: 画面を描かずに出来事を1行ずつ書き出す（スクリーンリーダー向け）
+    pub narrate: bool,
+    /// `--report`: ゲーム終了時に統計レポートを1行のJSONで追記するファイル
+    pub report: Option<PathBuf>,
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        Self {
+            solid_purge_mode: SolidPurgeMode::Automatic,
+            special_block_rates: SpecialBlockRates::default(),
+            gravity_mode: GravityMode::default(),
+            puzzle_pack: None,
+            start_board: None,
+            bot_command: None,
+            bot_pieces: BOT_PIECE_LIMIT,
+            bot_timeout: BOT_TIMEOUT,
+            echo_bot: false,
+            board_style: BoardStyle::Blocks,
+            theme: None,
+            glyphs: false,
+            charset: None,
+            narrate: false,
+            report: None,
+        }
+    }
+}
+
+/// `--xxx-rate <PERCENT>` 形式の値を読み取る
+fn parse_percent(option: &str, value: Option<impl AsRef<str>>) -> Result<u32, String> {
+    let value = value.ok_or_else(|| format!("missing value for {}", option))?;
+    match value.as_ref().parse::<u32>() {
+        Ok(percent) if percent <= 100 => Ok(percent),
+        _ => Err(format!(
+            "invalid value for {}: {} (expected 0-100)",
+            option,
+            value.as_ref()
+        )),
+    }
+}
+
+/// `--gravity <MODE>` の値を読み取る
+fn parse_gravity(value: Option<impl AsRef<str>>) -> Result<GravityMode, String> {
+    let value = value.ok_or_else(|| "missing value for --gravity".to_string())?;
+    match value.as_ref() {
+        "naive" => Ok(GravityMode::Naive),
+        "sticky" => Ok(GravityMode::Sticky),
+        "cascade" => Ok(GravityMode::Cascade),
+        other => Err(format!(
+            "invalid value for --gravity: {} (expected naive, sticky or cascade)",
+            other
+        )),
+    }
+}
+
+/// `--charset <auto|ascii|unicode>` の値を読み取る
+fn parse_charset(value: Option<impl AsRef<str>>) -> Result<Option<Charset>, String> {
+    let value = value.ok_or_else(|| "missing value for --charset".to_string())?;
+    match value.as_ref() {
+        "auto" => Ok(None),
+        "ascii" => Ok(Some(Charset::Ascii)),
+        "unicode" => Ok(Some(Charset::Unicode)),
+        other => Err(format!(
+            "invalid value for --charset: {} (expected auto, ascii or unicode)",
+            other
+        )),
+    }
+}
+
+/// `--bot-pieces <N>` / `--bot-timeout <MS>` の正の整数値を読み取る
+fn parse_positive(option: &str, value: Option<impl AsRef<str>>) -> Result<u32, String> {
+    let value = value.ok_or_else(|| format!("missing value for {}", option))?;
+    match value.as_ref().parse::<u32>() {
+        Ok(number) if number > 0 => Ok(number),
+        _ => Err(format!(
+            "invalid value for {}: {} (expected a positive integer)",
+            option,
+            value.as_ref()
+        )),
+    }
+}
+
+/// `--board <FUMEN>` の値を読み取る（複数ページの場合は最初のページの盤面を使う）
+fn parse_board(value: Option<impl AsRef<str>>) -> Result<(Board, usize), String> {
+    let value = value.ok_or_else(|| "missing value for --board".to_string())?;
+    let page = fumen::decode(value.as_ref())
+        .map_err(|message| format!("invalid value for --board: {}", message))?
+        .remove(0);
+    Ok((page.board, page.board_height))
+}
+
+impl GameOptions {
+    /// コマンドライン引数（プログラム名を除く）からオプションを作成
+    pub fn from_args<I, S>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let rates = &mut options.special_block_rates;
+            match arg.as_ref() {
+                "--manual-purge" => options.solid_purge_mode = SolidPurgeMode::Manual,
+                option @ "--wildcard-rate" => {
+                    rates.wildcard_percent = parse_percent(option, args.next())?
+                }
+                option @ "--bomb-rate" => rates.bomb_percent = parse_percent(option, args.next())?,
+                option @ "--garbage-rate" => {
+                    rates.garbage_percent = parse_percent(option, args.next())?
+                }
+                "--gravity" => options.gravity_mode = parse_gravity(args.next())?,
+                "--puzzles" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| "missing value for --puzzles".to_string())?;
+                    options.puzzle_pack = Some(PathBuf::from(path.as_ref()));
+                }
+                "--board" => options.start_board = Some(parse_board(args.next())?),
+                "--bot" => {
+                    let command = args
+                        .next()
+                        .ok_or_else(|| "missing value for --bot".to_string())?;
+                    options.bot_command = Some(command.as_ref().to_string());
+                }
+                option @ "--bot-pieces" => {
+                    options.bot_pieces = parse_positive(option, args.next())?
+                }
+                option @ "--bot-timeout" => {
+                    let millis = parse_positive(option, args.next())?;
+                    options.bot_timeout = Duration::from_millis(millis as u64);
+                }
+                "--echo-bot" => options.echo_bot = true,
+                "--half-block" => options.board_style = BoardStyle::HalfBlock,
+                "--glyphs" => options.glyphs = true,
+                "--charset" => options.charset = parse_charset(args.next())?,
+                "--narrate" => options.narrate = true,
+                "--report" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| "missing value for --report".to_string())?;
+                    options.report = Some(PathBuf::from(path.as_ref()));
+                }
+                "--theme" => {
+                    let theme = args
+                        .next()
+                        .ok_or_else(|| "missing value for --theme".to_string())?;
+                    options.theme = Some(theme.as_ref().to_string());
+                }
+                other => return Err(format!("unknown option: {}", other)),
+            }
+        }
+
+        if options.special_block_rates.total_percent() > 100 {
+            return Err("special block rates must add up to 100 or less".to_string());
+        }
+
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_options_without_args() {
+        let options = GameOptions::from_args(Vec::<String>::new()).unwrap();
+        assert_eq!(options, GameOptions::default());
+    }
+
+    #[test]
+    fn test_manual_purge_flag() {
+        let options = GameOptions::from_args(["--manual-purge"]).unwrap();
+        assert_eq!(options.solid_purge_mode, SolidPurgeMode::Manual);
+    }
+
+    #[test]
+    fn test_special_block_rates() {
+        let options =
+            GameOptions::from_args(["--wildcard-rate", "10", "--bomb-rate", "5"]).unwrap();
+        assert_eq!(options.special_block_rates.wildcard_percent, 10);
+        assert_eq!(options.special_block_rates.bomb_percent, 5);
+        assert_eq!(options.special_block_rates.garbage_percent, 0);
+
+        assert!(GameOptions::from_args(["--garbage-rate"]).is_err());
+        assert!(GameOptions::from_args(["--bomb-rate", "101"]).is_err());
+        assert!(GameOptions::from_args(["--bomb-rate", "60", "--garbage-rate", "60"]).is_err());
+    }
+
+    #[test]
+    fn test_gravity_mode() {
+        let options = GameOptions::from_args(["--gravity", "cascade"]).unwrap();
+        assert_eq!(options.gravity_mode, GravityMode::Cascade);
+
+        assert!(GameOptions::from_args(["--gravity"]).is_err());
+        assert!(GameOptions::from_args(["--gravity", "heavy"]).is_err());
+    }
+
+    #[test]
+    fn test_puzzle_pack_path() {
+        let options = GameOptions::from_args(["--puzzles", "my_pack.txt"]).unwrap();
+        assert_eq!(options.puzzle_pack, Some(PathBuf::from("my_pack.txt")));
+
+        assert!(GameOptions::from_args(["--puzzles"]).is_err());
+    }
+
+    #[test]
+    fn test_start_board_from_fumen() {
+        let options = GameOptions::from_args(["--board", "v115@vhAAgH"]).unwrap();
+        let (board, height) = options.start_board.unwrap();
+        assert_eq!(height, crate::config::BOARD_HEIGHT);
+        assert!(board
+            .iter()
+            .flatten()
+            .all(|&cell| cell == crate::cell::Cell::Empty));
+
+        assert!(GameOptions::from_args(["--board"]).is_err());
+        assert!(GameOptions::from_args(["--board", "not-a-fumen"]).is_err());
+    }
+
+    #[test]
+    fn test_bot_options() {
+        let options = GameOptions::from_args([
+            "--bot",
+            "./my-bot --fast",
+            "--bot-pieces",
+            "20",
+            "--bot-timeout",
+            "250",
+        ])
+        .unwrap();
+        assert_eq!(options.bot_command.as_deref(), Some("./my-bot --fast"));
+        assert_eq!(options.bot_pieces, 20);
+        assert_eq!(options.bot_timeout, Duration::from_millis(250));
+        assert!(GameOptions::from_args(["--echo-bot"]).unwrap().echo_bot);
+
+        assert!(GameOptions::from_args(["--bot"]).is_err());
+        assert!(GameOptions::from_args(["--bot-pieces", "0"]).is_err());
+        assert!(GameOptions::from_args(["--bot-timeout", "soon"]).is_err());
+    }
+
+    #[test]
+    fn test_half_block_flag() {
+        let options = GameOptions::from_args(["--half-block"]).unwrap();
+        assert_eq!(options.board_style, BoardStyle::HalfBlock);
+        assert_eq!(GameOptions::default().board_style, BoardStyle::Blocks);
+    }
+
+    #[test]
+    fn test_theme_option() {
+        let options = GameOptions::from_args(["--theme", "solarized"]).unwrap();
+        assert_eq!(options.theme.as_deref(), Some("solarized"));
+
+        assert!(GameOptions::from_args(["--theme"]).is_err());
+        assert!(GameOptions::from_args(["--glyphs"]).unwrap().glyphs);
+    }
+
+    #[test]
+    fn test_charset_option() {
+        let options = GameOptions::from_args(["--charset", "ascii"]).unwrap();
+        assert_eq!(options.charset, Some(Charset::Ascii));
+        assert_eq!(
+            GameOptions::from_args(["--charset", "auto"])
+                .unwrap()
+                .charset,
+            None
+        );
+
+        assert!(GameOptions::from_args(["--charset"]).is_err());
+        assert!(GameOptions::from_args(["--charset", "latin1"]).is_err());
+    }
+
+    #[test]
+    fn test_narrate_flag() {
+        assert!(GameOptions::from_args(["--narrate"]).unwrap().narrate);
+        assert!(!GameOptions::default().narrate);
+    }
+
+    #[test]
+    fn test_report_path() {
+        let options = GameOptions::from_args(["--report", "stats.json"]).unwrap();
+        assert_eq!(options.report, Some(PathBuf::from("stats.json")));
+
+        assert!(GameOptions::from_args(["--report"]).is_err());
+    }
+
+    #[test]
+    fn test_unknown_option_is_rejected() {
+        assert!(GameOptions::from_args(["--bogus"]).is_err());
+    }
+}
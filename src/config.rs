@@ -4,8 +4,33 @@ use crate::game_color::GameColor;
 pub const BOARD_WIDTH: usize = 10;
 pub const BOARD_HEIGHT: usize = 20;
 pub const FALL_SPEED_START: std::time::Duration = std::time::Duration::from_millis(800);
+pub const FALL_SPEED_MIN: std::time::Duration = std::time::Duration::from_millis(100);
 
 pub const COLOR_PALETTE: [GameColor; 3] = [GameColor::Cyan, GameColor::Magenta, GameColor::Yellow];
 pub const BLINK_ANIMATION_STEP: std::time::Duration = std::time::Duration::from_millis(120);
 pub const BLINK_COUNT_MAX: usize = 6; // 3 blinks: on-off-on-off-on-off
 pub const PUSH_DOWN_STEP_DURATION: std::time::Duration = std::time::Duration::from_millis(100);
+pub const GRAVITY_STEP_DURATION: std::time::Duration = std::time::Duration::from_millis(80); // Cascade重力の1段落下
+
+// --- コンボ・Back-to-Back ---
+pub const COMBO_STEP_PERCENT: u32 = 50; // コンボ1段ごとのボーナス倍率（%）
+pub const BACK_TO_BACK_PERCENT: u32 = 50; // Back-to-Back成立時のボーナス倍率（%）
+pub const DIFFICULT_CLEAR_LINES: u32 = 2; // 1ロックでこの本数以上消すと「難しい消去」
+
+// --- ゲームモード ---
+pub const SPRINT_LINES: u32 = 40; // Sprintの目標ライン数
+pub const MARATHON_LEVEL_CAP: u32 = 15; // Marathonの上限レベル
+pub const LINES_PER_LEVEL: u32 = 10; // 1レベル上がるのに必要なライン数
+pub const FALL_SPEED_STEP_PER_LEVEL: std::time::Duration = std::time::Duration::from_millis(50); // 1レベルごとに短くなる落下間隔
+pub const SURVIVAL_RISE_INTERVAL_START: std::time::Duration = std::time::Duration::from_secs(10); // サバイバルの最初のせり上がり間隔
+pub const SURVIVAL_RISE_INTERVAL_STEP: std::time::Duration = std::time::Duration::from_millis(250); // せり上がるごとに短くなる間隔
+pub const SURVIVAL_RISE_INTERVAL_MIN: std::time::Duration = std::time::Duration::from_secs(3); // せり上がり間隔の下限
+pub const SURVIVAL_GARBAGE_EVERY: u32 = 3; // この回数に1回はSolidではなくガベージ行がせり上がる
+pub const PRACTICE_HISTORY_LIMIT: usize = 200; // 練習モードで巻き戻せるピース数の上限
+pub const BOT_PIECE_LIMIT: u32 = 100; // ボット対戦で置くピース数の上限（--bot-piecesの既定値）
+pub const BOT_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(1000); // ボットの返答を待つ時間（--bot-timeoutの既定値）
+
+// --- 特殊ブロック（ピース内の各ブロックが特殊ブロックになる確率、%） ---
+pub const WILDCARD_SPAWN_PERCENT: u32 = 0;
+pub const BOMB_SPAWN_PERCENT: u32 = 0;
+pub const GARBAGE_SPAWN_PERCENT: u32 = 0;
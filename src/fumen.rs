@@ -0,0 +1,753 @@
+//! fumen（テト譜）形式の読み書き
+//!
+//! オンラインのテトリス盤面エディタで共有に使われる fumen v115 文字列と、盤面・ピースを相互に変換します。
+//! 1ページが1手（置く前の盤面と、そこに置いたピース）に対応し、複数ページでリプレイを表します。
+//!
+//! fumenにはCMYの区別や特殊ブロックがないため、色は次のように対応させます。
+//!
+//! | Thud & Tile                       | fumen     |
+//! |-----------------------------------|-----------|
+//! | Cyan / Magenta / Yellow           | I / T / O |
+//! | Red / Green / Blue                | Z / S / J |
+//! | Solid・ガベージ・ワイルドカード・爆弾 | グレー    |
+//!
+//! 読み込み時は I・J → Cyan、T・Z → Magenta、O・L・S → Yellow、グレー → ガベージ
+//! （底に連続する全面グレーの行はSolid）として扱います。
+//!
+//! この対応で失われる情報（連結数・特殊ブロック・フィールドの高さ）は、ページのコメントに
+//! `thud:`に続けて盤面表記（[`crate::board_notation`]、行区切りは`/`）を入れる拡張で保存します。
+//! 読み込み時は、拡張の盤面をfumenの色に変換した結果がそのページのフィールドと一致する場合にだけ使います。
+
+use crate::board_notation;
+use crate::cell::{Board, Cell};
+use crate::config::{BOARD_HEIGHT, BOARD_WIDTH};
+use crate::game_color::GameColor;
+use crate::tetromino::{Tetromino, TetrominoShape};
+
+const ENCODE_TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const COMMENT_TABLE: &[u8] =
+    b" !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+const COMMENT_BASE: u32 = COMMENT_TABLE.len() as u32 + 1;
+const MAX_COMMENT_LENGTH: usize = 4095;
+
+const VERSION_PREFIXES: [&str; 3] = ["v115@", "m115@", "d115@"];
+const EXTENSION_PREFIX: &str = "thud:";
+
+/// fumenのフィールドの幅と可視段数（最下段の下に、せり上がり用の1行が続く）
+const FIELD_WIDTH: usize = 10;
+const FIELD_TOP: usize = 23;
+const PLAYFIELD_BLOCKS: usize = FIELD_TOP * FIELD_WIDTH;
+const FIELD_BLOCKS: usize = PLAYFIELD_BLOCKS + FIELD_WIDTH;
+
+const _: () = assert!(BOARD_WIDTH == FIELD_WIDTH && BOARD_HEIGHT <= FIELD_TOP);
+
+/// fumenのブロック種別（0: 空き, 1-7: I L O Z T J S, 8: グレー）
+const EMPTY: u8 = 0;
+const GRAY: u8 = 8;
+
+/// 上の段から順に並べたfumenのフィールド
+type Field = [u8; FIELD_BLOCKS];
+
+/// ピースの4ブロックの座標
+type Cells = [(i32, i32); 4];
+
+/// ページに置かれたピース（Thud & Tileの回転状態と位置）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FumenPiece {
+    pub shape: TetrominoShape,
+    /// SRSの回転状態（0: 出現時, 1: 右回転, 2: 180度, 3: 左回転）
+    pub rotation: u8,
+    /// `Tetromino::pos`と同じ、4x4の枠の左上の位置
+    pub pos: (i8, i8),
+}
+
+/// fumenの1ページ
+#[derive(Clone, Debug, PartialEq)]
+pub struct FumenPage {
+    pub board: Board,
+    pub board_height: usize,
+    pub piece: Option<FumenPiece>,
+}
+
+impl FumenPiece {
+    pub fn from_tetromino(piece: &Tetromino) -> Self {
+        Self {
+            shape: piece.shape,
+            rotation: piece.get_rotation_state(),
+            pos: piece.pos,
+        }
+    }
+
+    /// 各ブロックの色を指定してピースを作る
+    pub fn to_tetromino(self, colors: [GameColor; 4]) -> Tetromino {
+        let mut piece = Tetromino::from_shape(self.shape, colors);
+        for _ in 0..self.rotation % 4 {
+            piece = piece.rotated();
+        }
+        piece.pos = self.pos;
+        piece
+    }
+
+    /// 盤面上のブロックの座標 (x, y)
+    fn cells(&self) -> Cells {
+        let coordinates =
+            Tetromino::get_shape_coordinates(self.shape as usize, self.rotation as usize)
+                .unwrap_or_default();
+        coordinates.map(|(x, y)| ((self.pos.0 + x) as i32, (self.pos.1 + y) as i32))
+    }
+}
+
+fn piece_code(shape: TetrominoShape) -> u8 {
+    match shape {
+        TetrominoShape::I => 1,
+        TetrominoShape::L => 2,
+        TetrominoShape::O => 3,
+        TetrominoShape::Z => 4,
+        TetrominoShape::T => 5,
+        TetrominoShape::J => 6,
+        TetrominoShape::S => 7,
+    }
+}
+
+fn code_shape(code: u32) -> Option<TetrominoShape> {
+    match code {
+        1 => Some(TetrominoShape::I),
+        2 => Some(TetrominoShape::L),
+        3 => Some(TetrominoShape::O),
+        4 => Some(TetrominoShape::Z),
+        5 => Some(TetrominoShape::T),
+        6 => Some(TetrominoShape::J),
+        7 => Some(TetrominoShape::S),
+        _ => None,
+    }
+}
+
+/// SRSの回転状態とfumenの回転コード（0: 180度, 1: 右, 2: 出現時, 3: 左）は同じ表で相互に変換できる
+const ROTATION_CODES: [u32; 4] = [2, 1, 0, 3];
+
+/// fumenのピース中心から見た各ブロックの位置（上向きが+y）
+fn center_offsets(shape: TetrominoShape, rotation: u8) -> Cells {
+    let spawn = match shape {
+        TetrominoShape::I => [(0, 0), (-1, 0), (1, 0), (2, 0)],
+        TetrominoShape::T => [(0, 0), (-1, 0), (1, 0), (0, 1)],
+        TetrominoShape::O => [(0, 0), (1, 0), (0, 1), (1, 1)],
+        TetrominoShape::L => [(0, 0), (-1, 0), (1, 0), (1, 1)],
+        TetrominoShape::J => [(0, 0), (-1, 0), (1, 0), (-1, 1)],
+        TetrominoShape::S => [(0, 0), (-1, 0), (0, 1), (1, 1)],
+        TetrominoShape::Z => [(0, 0), (1, 0), (0, 1), (-1, 1)],
+    };
+    spawn.map(|(x, y)| match rotation % 4 {
+        0 => (x, y),
+        1 => (y, -x),
+        2 => (-x, -y),
+        _ => (-y, x),
+    })
+}
+
+/// 旧来のfumenが記録する位置と、SRSの回転中心とのずれ
+fn legacy_offset(shape: TetrominoShape, rotation: u8) -> (i32, i32) {
+    match (shape, rotation % 4) {
+        (TetrominoShape::O, 0) => (0, 1),
+        (TetrominoShape::O, 2) => (-1, 0),
+        (TetrominoShape::O, 3) => (-1, 1),
+        (TetrominoShape::I, 2) => (-1, 0),
+        (TetrominoShape::I, 3) => (0, 1),
+        (TetrominoShape::S, 0) => (0, 1),
+        (TetrominoShape::S, 1) => (1, 0),
+        (TetrominoShape::Z, 0) => (0, 1),
+        (TetrominoShape::Z, 3) => (-1, 0),
+        _ => (0, 0),
+    }
+}
+
+/// 盤面の段（上から数えた行番号）を、fumenの下から数えた段に変換する（逆変換も同じ式）
+fn flip_row(y: i32) -> i32 {
+    BOARD_HEIGHT as i32 - 1 - y
+}
+
+/// fumenの段 (x, 下からの段) のフィールド上の位置
+fn field_index(x: i32, y: i32) -> Option<usize> {
+    if (0..FIELD_WIDTH as i32).contains(&x) && (0..FIELD_TOP as i32).contains(&y) {
+        Some((FIELD_TOP - 1 - y as usize) * FIELD_WIDTH + x as usize)
+    } else {
+        None
+    }
+}
+
+fn encode_piece(piece: &FumenPiece) -> Result<(u32, u32, u32), String> {
+    let cells = piece.cells().map(|(x, y)| (x, flip_row(y)));
+    let offsets = center_offsets(piece.shape, piece.rotation);
+    let min = |points: &Cells| {
+        (
+            points.iter().map(|p| p.0).min().unwrap_or(0),
+            points.iter().map(|p| p.1).min().unwrap_or(0),
+        )
+    };
+    let (cells_x, cells_y) = min(&cells);
+    let (offsets_x, offsets_y) = min(&offsets);
+    let (legacy_x, legacy_y) = legacy_offset(piece.shape, piece.rotation);
+    let position = field_index(
+        cells_x - offsets_x + legacy_x,
+        cells_y - offsets_y + legacy_y,
+    )
+    .ok_or_else(|| format!("piece is outside the fumen field: {:?}", piece))?;
+
+    Ok((
+        piece_code(piece.shape) as u32,
+        ROTATION_CODES[(piece.rotation % 4) as usize],
+        position as u32,
+    ))
+}
+
+/// fumenのピース指定から、fumenの段 (x, 下からの段) でのブロック位置を求める
+fn fumen_cells(
+    code: u32,
+    rotation_code: u32,
+    position: u32,
+) -> Option<(TetrominoShape, u8, Cells)> {
+    let shape = code_shape(code)?;
+    let rotation = ROTATION_CODES[rotation_code as usize % 4] as u8;
+    let (legacy_x, legacy_y) = legacy_offset(shape, rotation);
+    let center_x = (position as usize % FIELD_WIDTH) as i32 - legacy_x;
+    let center_y = FIELD_TOP as i32 - 1 - (position as usize / FIELD_WIDTH) as i32 - legacy_y;
+    let cells = center_offsets(shape, rotation).map(|(x, y)| (center_x + x, center_y + y));
+    Some((shape, rotation, cells))
+}
+
+fn decode_piece(code: u32, rotation_code: u32, position: u32) -> Option<FumenPiece> {
+    let (shape, rotation, cells) = fumen_cells(code, rotation_code, position)?;
+
+    // 同じ回転状態のTetrominoの枠内座標と比べて、枠の左上の位置を求める
+    let cells = cells.map(|(x, y)| (x, flip_row(y)));
+    let coordinates = Tetromino::get_shape_coordinates(shape as usize, rotation as usize)?;
+    let pos_x = cells.iter().map(|c| c.0).min()? - coordinates.iter().map(|c| c.0 as i32).min()?;
+    let pos_y = cells.iter().map(|c| c.1).min()? - coordinates.iter().map(|c| c.1 as i32).min()?;
+    Some(FumenPiece {
+        shape,
+        rotation,
+        pos: (i8::try_from(pos_x).ok()?, i8::try_from(pos_y).ok()?),
+    })
+}
+
+fn cell_code(cell: Cell) -> u8 {
+    match cell {
+        Cell::Empty => EMPTY,
+        _ => match cell.color() {
+            Some(GameColor::Cyan) => 1,
+            Some(GameColor::Magenta) => 5,
+            Some(GameColor::Yellow) => 3,
+            Some(GameColor::Red) => 4,
+            Some(GameColor::Green) => 7,
+            Some(GameColor::Blue) => 6,
+            _ => GRAY,
+        },
+    }
+}
+
+fn code_cell(code: u8) -> Cell {
+    match code {
+        EMPTY => Cell::Empty,
+        1 | 6 => Cell::Occupied(GameColor::Cyan),
+        4 | 5 => Cell::Occupied(GameColor::Magenta),
+        2 | 3 | 7 => Cell::Occupied(GameColor::Yellow),
+        _ => Cell::Garbage,
+    }
+}
+
+fn board_to_field(board: &Board) -> Field {
+    let mut field = [EMPTY; FIELD_BLOCKS];
+    for (y, row) in board.iter().enumerate() {
+        for (x, &cell) in row.iter().enumerate() {
+            if let Some(index) = field_index(x as i32, flip_row(y as i32)) {
+                field[index] = cell_code(cell);
+            }
+        }
+    }
+    field
+}
+
+fn field_to_board(field: &Field) -> Result<(Board, usize), String> {
+    let mut board = vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT];
+    for (index, &code) in field[..PLAYFIELD_BLOCKS].iter().enumerate() {
+        let x = (index % FIELD_WIDTH) as i32;
+        let y = flip_row((FIELD_TOP - 1 - index / FIELD_WIDTH) as i32);
+        if code == EMPTY {
+            continue;
+        }
+        if y < 0 {
+            return Err(format!("fumen field has blocks above row {}", BOARD_HEIGHT));
+        }
+        board[y as usize][x as usize] = code_cell(code);
+    }
+
+    // 底に連続する全面グレーの行はSolidライン
+    for row in board.iter_mut().rev() {
+        if !row.iter().all(|&cell| cell == Cell::Garbage) {
+            break;
+        }
+        row.fill(Cell::Solid);
+    }
+    let height = board_notation::height_from_solid_rows(&board);
+    Ok((board, height))
+}
+
+/// 色の対応だけでは復元できない盤面なら、拡張コメントを返す
+fn extension_comment(page: &FumenPage) -> String {
+    let plain = field_to_board(&board_to_field(&page.board));
+    if plain == Ok((page.board.clone(), page.board_height)) {
+        return String::new();
+    }
+    let text = board_notation::board_to_text(&page.board, page.board_height);
+    format!("{}{}", EXTENSION_PREFIX, text.replace('\n', "/"))
+}
+
+/// 拡張コメントの盤面が、fumenのフィールドと矛盾しなければ返す
+fn extension_board(comment: &str, field: &Field) -> Option<(Board, usize)> {
+    let text = comment.strip_prefix(EXTENSION_PREFIX)?;
+    let (board, height) = board_notation::parse_board(&text.replace('/', "\n")).ok()?;
+    let matches = board_to_field(&board)[..PLAYFIELD_BLOCKS] == field[..PLAYFIELD_BLOCKS];
+    matches.then_some((board, height))
+}
+
+/// JavaScriptの`escape()`と同じ変換（fumenのコメントはこの形で保存される）
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for unit in text.encode_utf16() {
+        match char::from_u32(unit as u32) {
+            Some(c) if c.is_ascii_alphanumeric() || "@*_+-./".contains(c) => escaped.push(c),
+            _ if unit < 0x100 => escaped.push_str(&format!("%{:02X}", unit)),
+            _ => escaped.push_str(&format!("%u{:04X}", unit)),
+        }
+    }
+    escaped
+}
+
+/// JavaScriptの`unescape()`と同じ変換
+fn unescape(text: &str) -> String {
+    let mut units = Vec::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let hex = |digits: &str| u16::from_str_radix(digits, 16).ok();
+        let decoded = if c != '%' {
+            None
+        } else if let Some(unit) = rest
+            .get(2..6)
+            .filter(|_| rest[1..].starts_with('u'))
+            .and_then(hex)
+        {
+            Some((unit, 6))
+        } else {
+            rest.get(1..3).and_then(hex).map(|unit| (unit, 3))
+        };
+        match decoded {
+            Some((unit, length)) => {
+                units.push(unit);
+                rest = &rest[length..];
+            }
+            None => {
+                let mut buffer = [0; 2];
+                units.extend_from_slice(c.encode_utf16(&mut buffer));
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    String::from_utf16_lossy(&units)
+}
+
+/// 64進数の値の列（下位の桁から並ぶ）
+struct Values {
+    digits: Vec<u32>,
+    index: usize,
+}
+
+impl Values {
+    fn push(digits: &mut Vec<u32>, mut value: u32, count: usize) {
+        for _ in 0..count {
+            digits.push(value % 64);
+            value /= 64;
+        }
+    }
+
+    fn poll(&mut self, count: usize) -> Result<u32, String> {
+        let digits = self
+            .digits
+            .get(self.index..self.index + count)
+            .ok_or("fumen data ended unexpectedly")?;
+        self.index += count;
+        Ok(digits
+            .iter()
+            .rev()
+            .fold(0, |value, &digit| value * 64 + digit))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.index >= self.digits.len()
+    }
+}
+
+/// 前のページとのフィールドの差分を連長圧縮する。変化がなければfalseを返す
+fn encode_field(prev: &Field, current: &Field, digits: &mut Vec<u32>) -> bool {
+    let diffs: Vec<u32> = prev
+        .iter()
+        .zip(current)
+        .map(|(&before, &after)| (after as i32 - before as i32 + 8) as u32)
+        .collect();
+    let mut runs: Vec<(u32, u32)> = Vec::new();
+    for &diff in &diffs {
+        match runs.last_mut() {
+            Some((last, length)) if *last == diff => *length += 1,
+            _ => runs.push((diff, 1)),
+        }
+    }
+    for &(diff, length) in &runs {
+        Values::push(digits, diff * FIELD_BLOCKS as u32 + length - 1, 2);
+    }
+    !(runs.len() == 1 && runs[0].0 == 8)
+}
+
+/// ページの列をfumen文字列に変換する
+///
+/// 次のページのフィールドは、fumenのルールでピースを固定した結果との差分として記録する
+/// （Thud & Tileのライン消去とは結果が異なっても、差分で正しい盤面になる）
+pub fn encode(pages: &[FumenPage]) -> Result<String, String> {
+    let mut digits = Vec::new();
+    let mut prev_field = [EMPTY; FIELD_BLOCKS];
+    let mut prev_comment = String::new();
+    let mut repeat_index: Option<usize> = None;
+
+    for (page_index, page) in pages.iter().enumerate() {
+        let field = board_to_field(&page.board);
+        let mut field_digits = Vec::new();
+        if encode_field(&prev_field, &field, &mut field_digits) {
+            digits.extend(field_digits);
+            repeat_index = None;
+        } else {
+            // 変化のないページが続く場合は、直前の繰り返し回数を増やす
+            match repeat_index {
+                Some(index) if digits[index] < 63 => digits[index] += 1,
+                _ => {
+                    digits.extend(field_digits);
+                    digits.push(0);
+                    repeat_index = Some(digits.len() - 1);
+                }
+            }
+        }
+
+        let (piece_type, rotation, position) = match &page.piece {
+            Some(piece) => encode_piece(piece)?,
+            None => (0, 0, 0),
+        };
+        let comment = extension_comment(page);
+        let comment_changed = comment != prev_comment;
+        let colorize = page_index == 0;
+        let mut action = 0; // ピースを固定する
+        action = action * 2 + comment_changed as u32;
+        action = action * 2 + colorize as u32;
+        action *= 2; // 左右反転なし
+        action *= 2; // せり上がりなし
+        action = action * FIELD_BLOCKS as u32 + position;
+        action = action * 4 + rotation;
+        action = action * 8 + piece_type;
+        Values::push(&mut digits, action, 3);
+
+        if comment_changed {
+            let escaped: Vec<u8> = escape(&comment).into_bytes();
+            let length = escaped.len().min(MAX_COMMENT_LENGTH);
+            Values::push(&mut digits, length as u32, 2);
+            for chunk in escaped[..length].chunks(4) {
+                let value = chunk.iter().rev().fold(0, |value, &byte| {
+                    let index = COMMENT_TABLE.iter().position(|&c| c == byte).unwrap_or(0);
+                    value * COMMENT_BASE + index as u32
+                });
+                Values::push(&mut digits, value, 5);
+            }
+        }
+
+        prev_field = field;
+        apply_lock(
+            &mut prev_field,
+            (piece_type, rotation, position),
+            false,
+            false,
+        );
+        prev_comment = comment;
+    }
+
+    // 先頭42文字の後は47文字ごとに`?`を挟む
+    let data: String = digits
+        .iter()
+        .map(|&digit| ENCODE_TABLE[digit as usize] as char)
+        .collect();
+    let mut chunks = vec![&data[..data.len().min(42)]];
+    if data.len() > 42 {
+        chunks.extend(
+            data.as_bytes()[42..]
+                .chunks(47)
+                .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default()),
+        );
+    }
+    Ok(format!("{}{}", VERSION_PREFIXES[0], chunks.join("?")))
+}
+
+/// 盤面と操作中のピースを1ページのfumen文字列に変換する
+pub fn encode_board(
+    board: &Board,
+    current_board_height: usize,
+    piece: Option<&Tetromino>,
+) -> Result<String, String> {
+    encode(&[FumenPage {
+        board: board.clone(),
+        board_height: current_board_height,
+        piece: piece.map(FumenPiece::from_tetromino),
+    }])
+}
+
+/// ピースを固定した後の、fumenのルールでのフィールド（次のページの差分の基準になる）
+fn apply_lock(field: &mut Field, piece: (u32, u32, u32), rise: bool, mirror: bool) {
+    let (code, rotation, position) = piece;
+    if let Some((_, _, cells)) = fumen_cells(code, rotation, position) {
+        for (x, y) in cells {
+            if let Some(index) = field_index(x, y) {
+                field[index] = code as u8;
+            }
+        }
+    }
+
+    // 揃った段を消して上の段を詰める
+    let remaining: Vec<u8> = field[..PLAYFIELD_BLOCKS]
+        .chunks(FIELD_WIDTH)
+        .filter(|row| row.contains(&EMPTY))
+        .flatten()
+        .copied()
+        .collect();
+    let cleared = PLAYFIELD_BLOCKS - remaining.len();
+    field[..cleared].fill(EMPTY);
+    field[cleared..PLAYFIELD_BLOCKS].copy_from_slice(&remaining);
+
+    if rise {
+        field.copy_within(FIELD_WIDTH.., 0);
+        field[PLAYFIELD_BLOCKS..].fill(EMPTY);
+    }
+    if mirror {
+        for row in field[..PLAYFIELD_BLOCKS].chunks_mut(FIELD_WIDTH) {
+            row.reverse();
+        }
+    }
+}
+
+/// fumen文字列をページの列に変換する
+pub fn decode(text: &str) -> Result<Vec<FumenPage>, String> {
+    let text = text.trim();
+    let data = VERSION_PREFIXES
+        .iter()
+        .find_map(|prefix| text.strip_prefix(prefix))
+        .ok_or("unsupported fumen (expected v115@...)")?;
+    let digits = data
+        .chars()
+        .filter(|&c| c != '?' && !c.is_whitespace())
+        .map(|c| {
+            ENCODE_TABLE
+                .iter()
+                .position(|&e| e as char == c)
+                .map(|digit| digit as u32)
+                .ok_or_else(|| format!("invalid fumen character '{}'", c))
+        })
+        .collect::<Result<_, _>>()?;
+    let mut values = Values { digits, index: 0 };
+
+    let mut pages = Vec::new();
+    let mut prev_field = [EMPTY; FIELD_BLOCKS];
+    let mut comment = String::new();
+    let mut repeat = 0;
+
+    while !values.is_empty() {
+        let mut field = prev_field;
+        if repeat > 0 {
+            repeat -= 1;
+        } else {
+            let mut index = 0;
+            let mut changed = true;
+            while index < FIELD_BLOCKS {
+                let value = values.poll(2)?;
+                let diff = (value / FIELD_BLOCKS as u32) as i32 - 8;
+                let length = (value % FIELD_BLOCKS as u32) as usize + 1;
+                if diff == 0 && length == FIELD_BLOCKS {
+                    changed = false;
+                }
+                let cells = field
+                    .get_mut(index..index + length)
+                    .ok_or("fumen field data overflows the field")?;
+                for cell in cells {
+                    *cell = u8::try_from(*cell as i32 + diff)
+                        .ok()
+                        .filter(|&code| code <= GRAY)
+                        .ok_or("invalid fumen block")?;
+                }
+                index += length;
+            }
+            if !changed {
+                repeat = values.poll(1)?;
+            }
+        }
+
+        let mut action = values.poll(3)?;
+        let piece_type = action % 8;
+        action /= 8;
+        let rotation = action % 4;
+        action /= 4;
+        let position = action % FIELD_BLOCKS as u32;
+        action /= FIELD_BLOCKS as u32;
+        let rise = action & 1 == 1;
+        let mirror = action >> 1 & 1 == 1;
+        let has_comment = action >> 3 & 1 == 1;
+        let lock = action >> 4 & 1 == 0;
+
+        if has_comment {
+            let length = values.poll(2)? as usize;
+            let mut escaped = String::new();
+            for _ in 0..length.div_ceil(4) {
+                let mut value = values.poll(5)?;
+                for _ in 0..4 {
+                    let index = (value % COMMENT_BASE) as usize;
+                    escaped.push(*COMMENT_TABLE.get(index).ok_or("invalid fumen comment")? as char);
+                    value /= COMMENT_BASE;
+                }
+            }
+            escaped.truncate(length);
+            comment = unescape(&escaped);
+        }
+
+        let (board, board_height) = match extension_board(&comment, &field) {
+            Some(extended) => extended,
+            None => field_to_board(&field)?,
+        };
+        let piece = decode_piece(piece_type, rotation, position);
+        pages.push(FumenPage {
+            board,
+            board_height,
+            piece,
+        });
+
+        if lock {
+            apply_lock(&mut field, (piece_type, rotation, position), rise, mirror);
+        }
+        prev_field = field;
+    }
+
+    if pages.is_empty() {
+        return Err("fumen has no pages".to_string());
+    }
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(text: &str, piece: Option<FumenPiece>) -> FumenPage {
+        let (board, board_height) = board_notation::parse_board(text).unwrap();
+        FumenPage {
+            board,
+            board_height,
+            piece,
+        }
+    }
+
+    #[test]
+    fn test_empty_field_matches_standard_fumen() {
+        let pages = vec![page("", None)];
+        assert_eq!(encode(&pages).unwrap(), "v115@vhAAgH");
+        assert_eq!(decode("v115@vhAAgH").unwrap(), pages);
+    }
+
+    #[test]
+    fn test_plain_colors_round_trip_without_extension() {
+        let pages = vec![page("C..MMY....\nCCYYYMMC..\n##########", None)];
+        assert_eq!(extension_comment(&pages[0]), "");
+        assert_eq!(decode(&encode(&pages).unwrap()).unwrap(), pages);
+    }
+
+    #[test]
+    fn test_special_blocks_and_counts_use_extension_comment() {
+        let pages = vec![page(
+            "
+            c2 *  @  .  .  .  .  .  .  .
+            c2 x  m  m  y  .  .  .  .  .
+            ----------
+            B  B  B  B  B  B  B  B  B  B",
+            None,
+        )];
+        assert!(extension_comment(&pages[0]).starts_with(EXTENSION_PREFIX));
+        assert_eq!(decode(&encode(&pages).unwrap()).unwrap(), pages);
+    }
+
+    #[test]
+    fn test_pieces_of_every_shape_and_rotation_round_trip() {
+        for shape in TetrominoShape::all_shapes() {
+            for rotation in 0..4 {
+                let piece = FumenPiece {
+                    shape,
+                    rotation,
+                    pos: (3, 14),
+                };
+                let pages = vec![page("C.........", Some(piece))];
+                let decoded = decode(&encode(&pages).unwrap()).unwrap();
+                assert_eq!(decoded, pages, "{:?} rotation {}", shape, rotation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pages_map_to_successive_placements() {
+        // 1手目: Iを底に横置き、2手目: その上にT
+        let i_piece = FumenPiece {
+            shape: TetrominoShape::I,
+            rotation: 0,
+            pos: (0, 18),
+        };
+        let t_piece = FumenPiece {
+            shape: TetrominoShape::T,
+            rotation: 0,
+            pos: (0, 17),
+        };
+        let pages = vec![
+            page("....MMMMMM", Some(i_piece)),
+            page("CCCCMMMMMM", Some(t_piece)),
+            page(".M........\nMMM.......\nCCCCMMMMMM", None),
+        ];
+        let decoded = decode(&encode(&pages).unwrap()).unwrap();
+        assert_eq!(decoded, pages);
+
+        // fumenのピースは盤面上の同じ位置のTetrominoになる
+        let tetromino = decoded[0].piece.unwrap().to_tetromino([GameColor::Cyan; 4]);
+        let cells: Vec<(i8, i8)> = tetromino.iter_blocks().map(|(pos, _)| pos).collect();
+        assert_eq!(cells, vec![(0, 19), (1, 19), (2, 19), (3, 19)]);
+        assert_eq!(FumenPiece::from_tetromino(&tetromino), i_piece);
+    }
+
+    #[test]
+    fn test_plain_fumen_colors_map_to_cmy() {
+        // I L O Z T J S グレー（左から）を1段に並べたフィールド
+        let mut field = [EMPTY; FIELD_BLOCKS];
+        for (x, code) in [1, 2, 3, 4, 5, 6, 7, 8].into_iter().enumerate() {
+            field[field_index(x as i32, 0).unwrap()] = code;
+        }
+        let (board, height) = field_to_board(&field).unwrap();
+        assert_eq!(height, BOARD_HEIGHT);
+        assert_eq!(board_notation::board_to_text(&board, height), "CYYMMCYx..");
+    }
+
+    #[test]
+    fn test_comment_escape_round_trip() {
+        let comment = "thud:c12 #/ñ日本";
+        assert_eq!(unescape(&escape(comment)), comment);
+        assert_eq!(escape("a b#"), "a%20b%23");
+    }
+
+    #[test]
+    fn test_invalid_fumen_is_rejected() {
+        assert!(decode("v110@vhAAgH").is_err());
+        assert!(decode("v115@vh").is_err());
+        assert!(decode("v115@!!!").is_err());
+    }
+}
@@ -20,6 +20,31 @@ pub enum GameInput {
     Restart, // Enter: ゲーム開始/再開
     Pause,   // 'p': 一時停止（将来用）
 
+    // CHAIN-BONUS操作
+    SolidPurge,       // 'x': CHAIN-BONUSを消費してSolidラインを消去
+    CyclePurgeAmount, // 'c': Solid purgeで消去する段数を切り替え
+
+    // 練習モード
+    Undo, // 'z': 1ピース分巻き戻す
+    Redo, // 'y': 巻き戻したピースをやり直す
+
+    // ヒント
+    Hint, // 'h': おすすめの配置を表示
+
+    // 表示
+    ToggleHalfBlock, // 'v': ハーフブロック表示の切り替え
+    ToggleCounts,    // 'n': ハーフブロック表示で連結数を表示
+    ToggleGroups,    // 'g': 連結グループの輪郭とボーナスまでの残りを表示
+    ToggleStats,     // 't': サイドバーにプレイの統計を表示
+
+    // 読み上げモード
+    ReadColumn(u8), // '1'〜'9', '0': 列（0始まり）の高さを読み上げる
+    ReadBoard,      // 'b': 盤面を1段ずつ読み上げる
+    ReadStatus,     // 's': スコアとCHAIN-BONUSを読み上げる
+
+    // 端末
+    Resize(u16, u16), // 端末の大きさが変わった（幅, 高さ）
+
     // その他
     Unknown, // 未対応キー
 }
@@ -81,11 +106,28 @@ impl InputProvider for CrosstermInputProvider {
                     KeyCode::Char('q') | KeyCode::Char('Q') => GameInput::Quit,
                     KeyCode::Enter => GameInput::Restart,
                     KeyCode::Char('p') | KeyCode::Char('P') => GameInput::Pause,
+                    KeyCode::Char('x') | KeyCode::Char('X') => GameInput::SolidPurge,
+                    KeyCode::Char('c') | KeyCode::Char('C') => GameInput::CyclePurgeAmount,
+                    KeyCode::Char('z') | KeyCode::Char('Z') => GameInput::Undo,
+                    KeyCode::Char('y') | KeyCode::Char('Y') => GameInput::Redo,
+                    KeyCode::Char('h') | KeyCode::Char('H') => GameInput::Hint,
+                    KeyCode::Char('v') | KeyCode::Char('V') => GameInput::ToggleHalfBlock,
+                    KeyCode::Char('n') | KeyCode::Char('N') => GameInput::ToggleCounts,
+                    KeyCode::Char('g') | KeyCode::Char('G') => GameInput::ToggleGroups,
+                    KeyCode::Char('t') | KeyCode::Char('T') => GameInput::ToggleStats,
+                    KeyCode::Char(digit @ '0'..='9') => {
+                        // '1'が左端の列、'0'が右端（10列目）
+                        let column = (digit as u8 - b'0' + 9) % 10;
+                        GameInput::ReadColumn(column)
+                    }
+                    KeyCode::Char('b') | KeyCode::Char('B') => GameInput::ReadBoard,
+                    KeyCode::Char('s') | KeyCode::Char('S') => GameInput::ReadStatus,
                     _ => GameInput::Unknown,
                 };
 
                 Ok(Some(input))
             }
+            Event::Resize(width, height) => Ok(Some(GameInput::Resize(width, height))),
             _ => Ok(Some(GameInput::Unknown)), // マウスイベント等は無視
         }
     }
@@ -181,6 +223,19 @@ impl GameInput {
             GameInput::Quit => "Quit Game",
             GameInput::Restart => "Restart/Start Game",
             GameInput::Pause => "Pause Game",
+            GameInput::SolidPurge => "Solid Purge",
+            GameInput::CyclePurgeAmount => "Cycle Purge Amount",
+            GameInput::Undo => "Undo Piece",
+            GameInput::Redo => "Redo Piece",
+            GameInput::Hint => "Show Hint",
+            GameInput::ToggleHalfBlock => "Toggle Half-Block View",
+            GameInput::ToggleCounts => "Toggle Connected Counts",
+            GameInput::ToggleGroups => "Toggle Group Outlines",
+            GameInput::ToggleStats => "Toggle Statistics",
+            GameInput::ReadColumn(_) => "Read Column Height",
+            GameInput::ReadBoard => "Read Board",
+            GameInput::ReadStatus => "Read Status",
+            GameInput::Resize(..) => "Resize Terminal",
             GameInput::Unknown => "Unknown Input",
         }
     }
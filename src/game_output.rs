@@ -0,0 +1,173 @@
+//! ゲームループの出力先（CLI版）
+//!
+//! 通常は画面に描き（`ScreenOutput`）、`--narrate`では出来事を読み上げます（`Narrator`）。
+//! タイトル・パズル選択・プレイ・ゲームオーバーの進め方は`run_game`で共通です。
+
+use std::io::{self, Write};
+
+use crate::narration::{self, Narrator};
+use crate::puzzle::{Puzzle, PuzzleProgress};
+use crate::render::{self, CrosstermRenderer};
+use crate::{GameMode, GameState};
+
+/// ゲームループが状態の変化を伝える先
+pub trait GameOutput {
+    /// タイトル画面（選んでいるモード）を示す
+    fn show_title(&mut self, selected_mode: usize) -> io::Result<()>;
+
+    /// パズル選択画面（選んでいるパズル）を示す
+    fn show_puzzle_select(
+        &mut self,
+        puzzles: &[Puzzle],
+        selected: usize,
+        progress: &PuzzleProgress,
+    ) -> io::Result<()>;
+
+    /// ループの1周ごとに、ゲームの状態と前の周からの出来事を示す
+    fn show_state(&mut self, state: &mut GameState) -> io::Result<()>;
+
+    /// 端末の大きさが変わった（タイトルなどの画面を描き直すならtrue）
+    fn resize(&mut self, width: u16, height: u16) -> bool;
+
+    /// 読み上げコマンドの結果や巻き戻しなど、1行の知らせ（画面には出さない）
+    fn say(&mut self, line: &str) -> io::Result<()>;
+
+    /// 保存の失敗など、ゲームを続けたまま伝えるエラー
+    fn warn(&mut self, message: String) -> io::Result<()>;
+}
+
+/// 代替画面に描く出力（エラーは代替画面を抜けてから表示するためにためておく）
+pub struct ScreenOutput {
+    pub renderer: CrosstermRenderer,
+    pub save_errors: Vec<String>,
+}
+
+impl GameOutput for ScreenOutput {
+    fn show_title(&mut self, selected_mode: usize) -> io::Result<()> {
+        render::draw_title_screen(&mut self.renderer, selected_mode)
+    }
+
+    fn show_puzzle_select(
+        &mut self,
+        puzzles: &[Puzzle],
+        selected: usize,
+        progress: &PuzzleProgress,
+    ) -> io::Result<()> {
+        render::draw_puzzle_select_screen(&mut self.renderer, puzzles, selected, progress)
+    }
+
+    fn show_state(&mut self, state: &mut GameState) -> io::Result<()> {
+        if state.mode != GameMode::Title {
+            render::draw(&mut self.renderer, state)?;
+        }
+        // 出来事は読み上げモードでだけ使う
+        state.events.clear();
+        Ok(())
+    }
+
+    fn resize(&mut self, width: u16, height: u16) -> bool {
+        self.renderer.resize(width, height);
+        true
+    }
+
+    fn say(&mut self, _line: &str) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn warn(&mut self, message: String) -> io::Result<()> {
+        self.save_errors.push(message);
+        Ok(())
+    }
+}
+
+impl<W: Write> GameOutput for Narrator<W> {
+    fn show_title(&mut self, selected_mode: usize) -> io::Result<()> {
+        self.say(&narration::describe_title(selected_mode))
+    }
+
+    fn show_puzzle_select(
+        &mut self,
+        puzzles: &[Puzzle],
+        selected: usize,
+        progress: &PuzzleProgress,
+    ) -> io::Result<()> {
+        self.say(&narration::describe_puzzle(puzzles, selected, progress))
+    }
+
+    fn show_state(&mut self, state: &mut GameState) -> io::Result<()> {
+        for event in state.events.drain() {
+            self.say(&narration::describe(&event))?;
+        }
+        Ok(())
+    }
+
+    fn resize(&mut self, _width: u16, _height: u16) -> bool {
+        false
+    }
+
+    fn say(&mut self, line: &str) -> io::Result<()> {
+        Narrator::say(self, line)
+    }
+
+    fn warn(&mut self, message: String) -> io::Result<()> {
+        Narrator::say(self, &message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli_options::GameOptions;
+    use crate::game_input::{GameInput, InputProvider};
+    use std::collections::VecDeque;
+
+    /// 決まった入力を1つずつ返す入力
+    struct ScriptedInput(VecDeque<GameInput>);
+
+    impl InputProvider for ScriptedInput {
+        fn poll_input(&mut self, _timeout_ms: u64) -> io::Result<bool> {
+            Ok(!self.0.is_empty())
+        }
+
+        fn read_input(&mut self) -> io::Result<Option<GameInput>> {
+            Ok(self.0.pop_front())
+        }
+
+        fn read_all_pending(&mut self) -> io::Result<Vec<GameInput>> {
+            Ok(self.0.pop_front().into_iter().collect())
+        }
+    }
+
+    #[test]
+    fn test_narrated_game_runs_through_the_shared_loop() {
+        let mut input = ScriptedInput(VecDeque::from([
+            GameInput::Restart,
+            GameInput::ReadStatus,
+            GameInput::Quit,
+            GameInput::Quit,
+        ]));
+        let mut out = Vec::new();
+        crate::run_game(
+            &mut GameOptions::default(),
+            &crate::puzzle::builtin_puzzles(),
+            &mut input,
+            &mut Narrator::new(&mut out),
+        )
+        .unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.split("\r\n").collect();
+        assert_eq!(lines[0], narration::describe_title(0));
+        assert!(
+            lines.iter().any(|line| line.starts_with("Score 0")),
+            "{}",
+            text
+        );
+        assert!(
+            lines.iter().any(|line| line.starts_with("Game over")),
+            "{}",
+            text
+        );
+        assert!(input.0.is_empty());
+    }
+}
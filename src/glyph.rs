@@ -0,0 +1,80 @@
+//! 色に頼らない記号表示（アクセシビリティモード）
+//!
+//! Cyan・Magenta・Yellowの見分けに色相を使わずに済むよう、色ごとに形の違う記号を割り当てます。
+//! CLI版は2文字の記号で描き、Web版は同じ番号の模様をブロックに重ねて描きます。
+
+use crate::game_color::GameColor;
+
+/// 色ごとの記号（Web版では模様の番号）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    /// 記号を割り当てない色（`[]`のまま、模様なし）
+    Plain = 0,
+    /// Cyan: `[]`（Web版は枠線）
+    Square = 1,
+    /// Magenta: `{}`（Web版は横縞）
+    Brace = 2,
+    /// Yellow: `/\`（Web版は斜線）
+    Slash = 3,
+}
+
+/// Web版の模様の番号に足す、連結ブロックの印
+pub const CONNECTED_PATTERN_FLAG: u8 = 4;
+
+/// 記号表示でのSolidブロック（Cyanの`[]`と区別する）
+pub const SOLID_GLYPH: &str = "==";
+
+impl Pattern {
+    pub fn for_color(color: GameColor) -> Pattern {
+        match color {
+            GameColor::Cyan => Pattern::Square,
+            GameColor::Magenta => Pattern::Brace,
+            GameColor::Yellow => Pattern::Slash,
+            _ => Pattern::Plain,
+        }
+    }
+
+    /// ブロックの2文字
+    pub fn block(self) -> &'static str {
+        match self {
+            Pattern::Plain | Pattern::Square => "[]",
+            Pattern::Brace => "{}",
+            Pattern::Slash => "/\\",
+        }
+    }
+
+    /// ゴーストの2文字（ゴーストの`:`に色の記号の閉じ側を添える）
+    pub fn ghost(self) -> &'static str {
+        match self {
+            Pattern::Plain => "::",
+            Pattern::Square => ":]",
+            Pattern::Brace => ":}",
+            Pattern::Slash => ":\\",
+        }
+    }
+
+    /// 連結ブロックの連結数の前に付ける1文字
+    pub fn connected_mark(self) -> char {
+        self.block().chars().next().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_each_piece_color_has_distinct_glyphs() {
+        let patterns =
+            [GameColor::Cyan, GameColor::Magenta, GameColor::Yellow].map(Pattern::for_color);
+        for (index, pattern) in patterns.iter().enumerate() {
+            for other in &patterns[index + 1..] {
+                assert_ne!(pattern.block(), other.block());
+                assert_ne!(pattern.ghost(), other.ghost());
+                assert_ne!(pattern.connected_mark(), other.connected_mark());
+            }
+            assert_ne!(pattern.block(), SOLID_GLYPH);
+        }
+        assert_eq!(Pattern::for_color(GameColor::Grey), Pattern::Plain);
+    }
+}
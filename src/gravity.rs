@@ -0,0 +1,203 @@
+//! ライン消去後の重力処理
+//! CLI版とWASM版で共有される、最下段ライン消去後のブロック落下ルール
+
+use crate::board_logic::{find_color_groups, Point};
+use crate::cell::{Board, Cell};
+use crate::config::BOARD_WIDTH;
+
+/// 最下段ライン消去後の重力ルール
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GravityMode {
+    /// 消去した行より上の行をそのまま1段ずつ下げる（従来の挙動）
+    #[default]
+    Naive,
+    /// 同色の連結グループが1つの塊として穴に落ちる（即座に着地）
+    Sticky,
+    /// 支えのないすべての塊が1段ずつ落下するアニメーションを行い、
+    /// 着地後に新しい連結やライン消去を引き起こす
+    Cascade,
+}
+
+/// 落下単位を検出する
+///
+/// 同色の連結グループが1つの単位になり、ワイルドカードを共有するグループは1つにまとめる。
+/// どのグループにも属さない色なしブロック（ワイルドカード・爆弾・ガベージ）は単独で1つの単位になる。
+/// Solidは動かない土台として扱い、単位には含めない。
+fn find_fall_units(board: &Board, height: usize) -> Vec<Vec<Point>> {
+    let mut unit_of: Vec<Vec<Option<usize>>> = vec![vec![None; BOARD_WIDTH]; board.len()];
+    let mut units: Vec<Vec<Point>> = Vec::new();
+
+    for group in find_color_groups(board, height, &[]) {
+        // ワイルドカード経由で既存の単位とつながっていれば合流する
+        let mut merged: Option<usize> = None;
+        for &(x, y) in &group.cells {
+            if let Some(existing) = unit_of[y][x] {
+                merged = Some(merged.map_or(existing, |unit| unit.min(existing)));
+            }
+        }
+        let unit_index = merged.unwrap_or_else(|| {
+            units.push(Vec::new());
+            units.len() - 1
+        });
+
+        for &(x, y) in &group.cells {
+            match unit_of[y][x] {
+                Some(existing) if existing != unit_index => {
+                    // 別の単位をまるごと取り込む
+                    let cells = std::mem::take(&mut units[existing]);
+                    for &(cx, cy) in &cells {
+                        unit_of[cy][cx] = Some(unit_index);
+                    }
+                    units[unit_index].extend(cells);
+                }
+                Some(_) => {}
+                None => {
+                    unit_of[y][x] = Some(unit_index);
+                    units[unit_index].push((x, y));
+                }
+            }
+        }
+    }
+
+    for y in 0..height {
+        for x in 0..BOARD_WIDTH {
+            if board[y][x].is_block() && unit_of[y][x].is_none() {
+                unit_of[y][x] = Some(units.len());
+                units.push(vec![(x, y)]);
+            }
+        }
+    }
+
+    units.retain(|unit| !unit.is_empty());
+    units
+}
+
+/// 支えのない落下単位をすべて1段下げる。1つでも動いたらtrueを返す
+pub fn step_gravity(board: &mut Board, height: usize) -> bool {
+    let units = find_fall_units(board, height);
+    let mut unit_of: Vec<Vec<Option<usize>>> = vec![vec![None; BOARD_WIDTH]; board.len()];
+    for (index, unit) in units.iter().enumerate() {
+        for &(x, y) in unit {
+            unit_of[y][x] = Some(index);
+        }
+    }
+
+    // 床・Solid・止まっている単位の上に乗っている単位を、変化がなくなるまで「停止」に加える
+    let mut stuck = vec![false; units.len()];
+    loop {
+        let mut changed = false;
+        for (index, unit) in units.iter().enumerate() {
+            if stuck[index] {
+                continue;
+            }
+            let supported = unit.iter().any(|&(x, y)| {
+                let below = y + 1;
+                if below >= height {
+                    return true;
+                }
+                match unit_of[below][x] {
+                    Some(other) => other != index && stuck[other],
+                    None => board[below][x] != Cell::Empty,
+                }
+            });
+            if supported {
+                stuck[index] = true;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let falling: Vec<usize> = (0..units.len()).filter(|&index| !stuck[index]).collect();
+    if falling.is_empty() {
+        return false;
+    }
+
+    // 落下する単位をいったん取り除いてから1段下に置き直す
+    let mut moved_cells = Vec::new();
+    for &index in &falling {
+        for &(x, y) in &units[index] {
+            moved_cells.push((x, y + 1, board[y][x]));
+            board[y][x] = Cell::Empty;
+        }
+    }
+    for (x, y, cell) in moved_cells {
+        board[y][x] = cell;
+    }
+
+    true
+}
+
+/// 落下がなくなるまで重力を適用し、落下したステップ数を返す（Stickyモード用）
+pub fn settle(board: &mut Board, height: usize) -> usize {
+    let mut steps = 0;
+    while step_gravity(board, height) {
+        steps += 1;
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BOARD_HEIGHT;
+    use crate::game_color::GameColor;
+
+    fn empty_board() -> Board {
+        vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT]
+    }
+
+    #[test]
+    fn test_connected_group_falls_as_a_unit() {
+        let mut board = empty_board();
+        let cyan = Cell::Occupied(GameColor::Cyan);
+        // L字のCyanグループ。右端だけ下のMagentaに支えられている
+        board[15][0] = cyan;
+        board[16][0] = cyan;
+        board[16][1] = cyan;
+        board[17][1] = Cell::Occupied(GameColor::Magenta);
+        board[19][1] = Cell::Occupied(GameColor::Yellow);
+
+        settle(&mut board, BOARD_HEIGHT);
+
+        // Magentaは1段落ちてYellowの上に乗り、CyanのL字も形を保ったまま1段落ちる
+        assert_eq!(board[18][1], Cell::Occupied(GameColor::Magenta));
+        assert_eq!(board[16][0], cyan);
+        assert_eq!(board[17][0], cyan);
+        assert_eq!(board[17][1], cyan);
+        assert_eq!(board[15][0], Cell::Empty);
+    }
+
+    #[test]
+    fn test_solid_lines_and_floor_support_blocks() {
+        let mut board = empty_board();
+        board[17].fill(Cell::Solid);
+        board[15][4] = Cell::Garbage;
+        board[19][4] = Cell::Occupied(GameColor::Cyan);
+
+        assert_eq!(settle(&mut board, BOARD_HEIGHT), 1);
+        assert_eq!(board[16][4], Cell::Garbage, "Solidの上で止まる");
+        assert_eq!(board[19][4], Cell::Occupied(GameColor::Cyan));
+        assert!(!step_gravity(&mut board, BOARD_HEIGHT));
+    }
+
+    #[test]
+    fn test_groups_sharing_a_wildcard_fall_together() {
+        let mut board = empty_board();
+        board[10][0] = Cell::Occupied(GameColor::Cyan);
+        board[10][1] = Cell::Wildcard;
+        board[10][2] = Cell::Occupied(GameColor::Magenta);
+        board[11][2] = Cell::Occupied(GameColor::Magenta);
+        board[19][2] = Cell::Occupied(GameColor::Yellow);
+
+        settle(&mut board, BOARD_HEIGHT);
+
+        // 3つが1つの塊としてYellowの上に着地する
+        assert_eq!(board[17][0], Cell::Occupied(GameColor::Cyan));
+        assert_eq!(board[17][1], Cell::Wildcard);
+        assert_eq!(board[17][2], Cell::Occupied(GameColor::Magenta));
+        assert_eq!(board[18][2], Cell::Occupied(GameColor::Magenta));
+    }
+}
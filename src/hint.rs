@@ -0,0 +1,233 @@
+//! 配置のヒント
+//!
+//! 到達できる配置をすべて`simulate_placement`で解決して評価し、最も良い配置を理由つきで返します。
+//! 色の連結を育てる戦略が見えるように、MAX-CHAINの伸び・10連結の完成・既存グループとの連結を
+//! 重く見て、穴と積み上がりの高さを減点します。
+
+use crate::board_logic;
+use crate::cell::{Board, Cell};
+use crate::config::BOARD_WIDTH;
+use crate::game_color::GameColor;
+use crate::placement::{self, Placement};
+use crate::scoring::CustomScoreSystem;
+use crate::simulator::{self, SimulationOutcome, SimulationRules};
+use crate::tetromino::Tetromino;
+
+/// MAX-CHAINが1伸びるごとの評価値
+const CHAIN_GAIN_VALUE: i64 = 30;
+/// 10連結が1つ増えるごとの評価値
+const TEN_GROUP_VALUE: i64 = 300;
+/// ピースが既存の同色ブロック1個と連結するごとの評価値
+const JOINED_BLOCK_VALUE: i64 = 8;
+/// 穴1つごとの減点
+const HOLE_PENALTY: i64 = 60;
+/// 積み上がりの高さ1段ごとの減点
+const HEIGHT_PENALTY: i64 = 10;
+/// サイドバーに表示する理由の最大数
+pub const MAX_HINT_REASONS: usize = 3;
+
+/// おすすめの配置と、その理由
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hint {
+    pub placement: Placement,
+    /// 理由の短い説明（例: "+3 CYAN CHAIN", "CREATES 10-GROUP"）。重要な順
+    pub reasons: Vec<String>,
+}
+
+impl Hint {
+    /// 理由を1行にまとめた説明
+    pub fn explanation(&self) -> String {
+        self.reasons.join(", ")
+    }
+}
+
+/// 到達できる配置のうち評価値が最も高いもの（同点なら入力数の少ないもの）
+///
+/// ピースが今の位置に収まっていなければNone
+pub fn best_placement(
+    board: &Board,
+    current_board_height: usize,
+    score: &CustomScoreSystem,
+    piece: &Tetromino,
+    rules: SimulationRules,
+) -> Option<Hint> {
+    let mut best: Option<(i64, Hint)> = None;
+    for placement in placement::enumerate_placements(board, current_board_height, piece) {
+        let (value, reasons) = evaluate(board, current_board_height, score, &placement, rules);
+        if best
+            .as_ref()
+            .is_none_or(|(best_value, _)| value > *best_value)
+        {
+            best = Some((value, Hint { placement, reasons }));
+        }
+    }
+    best.map(|(_, hint)| hint)
+}
+
+/// 配置の評価値と理由
+fn evaluate(
+    board: &Board,
+    current_board_height: usize,
+    score: &CustomScoreSystem,
+    placement: &Placement,
+    rules: SimulationRules,
+) -> (i64, Vec<String>) {
+    let outcome =
+        simulator::simulate_placement(board, current_board_height, score, &placement.piece, rules);
+    let mut value = 0;
+    let mut reasons = Vec::new();
+
+    let points = outcome.score.score.total() - score.score.total();
+    value += points as i64;
+    if points > 0 {
+        reasons.push(format!("+{} PTS", points));
+    }
+
+    let before = &score.max_chains;
+    let after = &outcome.score.max_chains;
+    for (color, gain) in [
+        (GameColor::Cyan, after.cyan - before.cyan),
+        (GameColor::Magenta, after.magenta - before.magenta),
+        (GameColor::Yellow, after.yellow - before.yellow),
+    ] {
+        if gain > 0 {
+            value += gain as i64 * CHAIN_GAIN_VALUE;
+            reasons.push(format!("+{} {} CHAIN", gain, color_name(color)));
+        }
+    }
+
+    // 連結の評価は、ライン消去で崩れる前の「置いた直後」の盤面で行う
+    let mut placed = board.clone();
+    for ((x, y), cell) in placement.piece.iter_cells() {
+        if y >= 0 {
+            placed[y as usize][x as usize] = cell;
+        }
+    }
+    let ten_groups = board_logic::calculate_chain_bonus(&placed)
+        .saturating_sub(board_logic::calculate_chain_bonus(board));
+    if ten_groups > 0 {
+        value += ten_groups as i64 * TEN_GROUP_VALUE;
+        reasons.push("CREATES 10-GROUP".to_string());
+    }
+
+    let piece_cells = placement.cells();
+    let touched: Vec<_> = board_logic::find_color_groups(&placed, current_board_height, &[])
+        .into_iter()
+        .filter(|group| {
+            group
+                .cells
+                .iter()
+                .any(|&(x, y)| piece_cells.contains(&(x as i8, y as i8)))
+        })
+        .collect();
+    let joined: usize = touched
+        .iter()
+        .map(|group| {
+            group
+                .cells
+                .iter()
+                .filter(|&&(x, y)| !piece_cells.contains(&(x as i8, y as i8)))
+                .count()
+        })
+        .sum();
+    value += joined as i64 * JOINED_BLOCK_VALUE;
+    if let Some(largest) = touched.iter().max_by_key(|group| group.cells.len()) {
+        if joined > 0 && ten_groups == 0 && largest.cells.len() > 2 {
+            reasons.push(format!(
+                "{} GROUP: {}",
+                color_name(largest.color),
+                largest.cells.len()
+            ));
+        }
+    }
+
+    let holes = count_holes(&outcome);
+    value -= holes as i64 * HOLE_PENALTY + stack_height(&outcome) as i64 * HEIGHT_PENALTY;
+    if holes <= count_holes_in(board, current_board_height) {
+        reasons.push("NO NEW HOLES".to_string());
+    }
+    if reasons.is_empty() {
+        // 得点も連結も穴なしの置き場もなければ、残りは高さと穴の少なさで選んでいる
+        reasons.push("KEEPS STACK LOW".to_string());
+    }
+
+    reasons.truncate(MAX_HINT_REASONS);
+    (value, reasons)
+}
+
+fn color_name(color: GameColor) -> String {
+    format!("{:?}", color).to_uppercase()
+}
+
+fn count_holes(outcome: &SimulationOutcome) -> usize {
+    count_holes_in(&outcome.board, outcome.current_board_height)
+}
+
+/// 上にブロックがある空きマスの数
+fn count_holes_in(board: &Board, current_board_height: usize) -> usize {
+    (0..BOARD_WIDTH)
+        .map(|x| {
+            (0..current_board_height)
+                .map(|y| board[y][x])
+                .skip_while(|&cell| cell == Cell::Empty)
+                .filter(|&cell| cell == Cell::Empty)
+                .count()
+        })
+        .sum()
+}
+
+/// フィールドの底から最も高いブロックまでの段数
+fn stack_height(outcome: &SimulationOutcome) -> usize {
+    let top = (0..outcome.current_board_height)
+        .find(|&y| outcome.board[y].iter().any(|&cell| cell != Cell::Empty))
+        .unwrap_or(outcome.current_board_height);
+    outcome.current_board_height - top
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board_notation::parse_board;
+    use crate::config::BOARD_HEIGHT;
+    use crate::tetromino::TetrominoShape;
+
+    #[test]
+    fn test_hint_grows_the_color_chain() {
+        // 底の空きに寝かせると、低いままCyanの塊とつながって12個のグループになる
+        let (board, height) = parse_board(
+            "\
+CCCC......
+CCCC......",
+        )
+        .unwrap();
+        let piece = Tetromino::from_shape(TetrominoShape::I, [GameColor::Cyan; 4]);
+        let score = CustomScoreSystem::new();
+        let hint = best_placement(&board, height, &score, &piece, SimulationRules::default())
+            .expect("I piece should have a placement");
+
+        let row = (BOARD_HEIGHT - 1) as i8;
+        assert_eq!(
+            hint.placement.cells(),
+            [(4, row), (5, row), (6, row), (7, row)]
+        );
+        assert_eq!(hint.reasons[0], "+12 CYAN CHAIN");
+        assert_eq!(
+            hint.explanation(),
+            "+12 CYAN CHAIN, CREATES 10-GROUP, NO NEW HOLES"
+        );
+    }
+
+    #[test]
+    fn test_no_hint_when_piece_does_not_fit() {
+        let board = vec![vec![Cell::Solid; BOARD_WIDTH]; BOARD_HEIGHT];
+        let piece = Tetromino::from_shape(TetrominoShape::T, [GameColor::Cyan; 4]);
+        assert!(best_placement(
+            &board,
+            BOARD_HEIGHT,
+            &CustomScoreSystem::new(),
+            &piece,
+            SimulationRules::default()
+        )
+        .is_none());
+    }
+}
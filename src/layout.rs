@@ -0,0 +1,243 @@
+//! 端末の大きさに合わせた画面配置
+//!
+//! 盤面とサイドバーを端末の中央に置きます。幅が足りなければサイドバーを盤面の下に回し、
+//! 高さが足りなければサイドバーを詰めた表示にします。どの配置も収まらなければNoneを返し、
+//! 描画側は「端末が小さすぎる」ことを表示します。盤面の大きさはマスの描き方（`BoardStyle`）で変わります。
+
+use crate::config::{BOARD_HEIGHT, BOARD_WIDTH};
+use crate::render::UI_LINE_WIDTH;
+
+/// 盤面とサイドバーの間隔
+const SIDEBAR_GAP: u16 = 2;
+const SIDEBAR_WIDTH: u16 = UI_LINE_WIDTH as u16;
+/// サイドバーの行数（Fullは項目ごとに見出しと内訳を、Condensedは1項目1行で表示する）
+const FULL_SIDEBAR_HEIGHT: u16 = 26;
+const CONDENSED_SIDEBAR_HEIGHT: u16 = 14;
+
+/// 盤面のマスの描き方
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoardStyle {
+    /// 1マスを2文字（`[]`、連結ブロックは連結数）で描く
+    Blocks,
+    /// 上下2マスを1文字（`▀`/`▄`の前景色と背景色）に詰めて描く
+    HalfBlock,
+    /// 1マスを1文字で描き、連結ブロックは連結数を表示する（ハーフブロック表示で連結数を見るとき）
+    Counts,
+}
+
+impl BoardStyle {
+    /// 1マスの文字数
+    pub fn cell_width(self) -> u16 {
+        match self {
+            BoardStyle::Blocks => 2,
+            BoardStyle::HalfBlock | BoardStyle::Counts => 1,
+        }
+    }
+
+    /// 盤面の内側の行数
+    pub fn board_rows(self) -> u16 {
+        match self {
+            BoardStyle::HalfBlock => BOARD_HEIGHT.div_ceil(2) as u16,
+            BoardStyle::Blocks | BoardStyle::Counts => BOARD_HEIGHT as u16,
+        }
+    }
+
+    /// 盤面の枠を含む大きさ
+    pub fn frame_size(self) -> (u16, u16) {
+        (
+            BOARD_WIDTH as u16 * self.cell_width() + 2,
+            self.board_rows() + 2,
+        )
+    }
+}
+
+/// サイドバーの表示形式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SidebarStyle {
+    Full,
+    Condensed,
+}
+
+/// サイドバーの置き場所
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SidebarPlacement {
+    Right,
+    Below,
+}
+
+/// 候補の配置（優先順）
+const ARRANGEMENTS: [(SidebarPlacement, SidebarStyle); 3] = [
+    (SidebarPlacement::Right, SidebarStyle::Full),
+    (SidebarPlacement::Right, SidebarStyle::Condensed),
+    (SidebarPlacement::Below, SidebarStyle::Condensed),
+];
+
+/// 画面上の配置（座標はすべて端末の左上からの文字単位）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Layout {
+    pub screen: (u16, u16),
+    /// 盤面の枠の左上
+    pub board: (u16, u16),
+    /// サイドバーの左上
+    pub sidebar: (u16, u16),
+    pub sidebar_style: SidebarStyle,
+    pub sidebar_placement: SidebarPlacement,
+    pub board_style: BoardStyle,
+}
+
+impl Layout {
+    /// 端末に収まる最初の配置（どれも収まらなければNone）
+    pub fn for_screen(width: u16, height: u16, board_style: BoardStyle) -> Option<Layout> {
+        let (frame_width, frame_height) = board_style.frame_size();
+        ARRANGEMENTS.iter().find_map(|&(placement, style)| {
+            let (needed_width, needed_height) = required_size(board_style, placement, style);
+            if width < needed_width || height < needed_height {
+                return None;
+            }
+            let left = (width - needed_width) / 2;
+            let top = (height - needed_height) / 2;
+            let sidebar = match placement {
+                SidebarPlacement::Right => (left + frame_width + SIDEBAR_GAP, top),
+                SidebarPlacement::Below => (left + 1, top + frame_height),
+            };
+            Some(Layout {
+                screen: (width, height),
+                board: (left, top),
+                sidebar,
+                sidebar_style: style,
+                sidebar_placement: placement,
+                board_style,
+            })
+        })
+    }
+
+    /// 盤面のマス(x, y)の左端の画面座標（ハーフブロックでは2段で同じ行になる）
+    pub fn cell_position(&self, x: usize, y: usize) -> (u16, u16) {
+        let row = match self.board_style {
+            BoardStyle::HalfBlock => y as u16 / 2,
+            BoardStyle::Blocks | BoardStyle::Counts => y as u16,
+        };
+        (
+            self.board.0 + x as u16 * self.board_style.cell_width() + 1,
+            self.board.1 + row + 1,
+        )
+    }
+
+    /// 盤面の枠の内側で中央寄せしたx座標
+    pub fn board_centered_x(&self, text: &str) -> u16 {
+        let (frame_width, _) = self.board_style.frame_size();
+        self.board.0 + (frame_width + 1).saturating_sub(text.chars().count() as u16) / 2
+    }
+}
+
+/// 幅`width`の中で中央寄せしたx座標
+pub fn centered_x(width: u16, text: &str) -> u16 {
+    width.saturating_sub(text.chars().count() as u16) / 2
+}
+
+/// 配置に必要な端末の大きさ
+pub fn required_size(
+    board_style: BoardStyle,
+    placement: SidebarPlacement,
+    style: SidebarStyle,
+) -> (u16, u16) {
+    let (frame_width, frame_height) = board_style.frame_size();
+    let sidebar_height = match style {
+        SidebarStyle::Full => FULL_SIDEBAR_HEIGHT,
+        SidebarStyle::Condensed => CONDENSED_SIDEBAR_HEIGHT,
+    };
+    match placement {
+        SidebarPlacement::Right => (
+            frame_width + SIDEBAR_GAP + SIDEBAR_WIDTH,
+            frame_height.max(sidebar_height),
+        ),
+        SidebarPlacement::Below => (
+            frame_width.max(SIDEBAR_WIDTH + 1),
+            frame_height + sidebar_height,
+        ),
+    }
+}
+
+/// 「端末が小さすぎる」ときに表示する、必要な大きさの一覧（例: "44x22 OR 22x36"）
+pub fn minimum_sizes_text(board_style: BoardStyle) -> String {
+    let mut sizes: Vec<(u16, u16)> = Vec::new();
+    for &(placement, style) in &ARRANGEMENTS {
+        let size = required_size(board_style, placement, style);
+        // 他の配置より幅も高さも大きい配置は、最小の候補にならない
+        sizes.retain(|&(w, h)| !(w >= size.0 && h >= size.1));
+        if !sizes.iter().any(|&(w, h)| w <= size.0 && h <= size.1) {
+            sizes.push(size);
+        }
+    }
+    sizes
+        .iter()
+        .map(|(w, h)| format!("{}x{}", w, h))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_is_centered_and_falls_back_when_small() {
+        // 標準の大きさちょうどなら、従来どおり左上から並べる
+        let exact = Layout::for_screen(44, 26, BoardStyle::Blocks).unwrap();
+        assert_eq!(exact.board, (0, 0));
+        assert_eq!(exact.sidebar, (24, 0));
+        assert_eq!(exact.sidebar_style, SidebarStyle::Full);
+        assert_eq!(exact.cell_position(0, 0), (1, 1));
+
+        let wide = Layout::for_screen(100, 40, BoardStyle::Blocks).unwrap();
+        assert_eq!(wide.board, (28, 7));
+        assert_eq!(wide.sidebar, (52, 7));
+
+        // 80x24では高さが足りないので、サイドバーを詰める
+        let short = Layout::for_screen(80, 24, BoardStyle::Blocks).unwrap();
+        assert_eq!(short.sidebar_style, SidebarStyle::Condensed);
+        assert_eq!(short.sidebar_placement, SidebarPlacement::Right);
+        assert_eq!(short.board, (18, 1));
+
+        // 幅が足りなければ、サイドバーを盤面の下に回す
+        let narrow = Layout::for_screen(30, 40, BoardStyle::Blocks).unwrap();
+        assert_eq!(narrow.sidebar_placement, SidebarPlacement::Below);
+        assert_eq!(narrow.board, (4, 2));
+        assert_eq!(narrow.sidebar, (5, 24));
+
+        assert_eq!(Layout::for_screen(30, 30, BoardStyle::Blocks), None);
+        assert_eq!(
+            Layout::for_screen(43, 40, BoardStyle::Blocks)
+                .unwrap()
+                .sidebar_placement,
+            SidebarPlacement::Below
+        );
+        assert_eq!(minimum_sizes_text(BoardStyle::Blocks), "44x22 OR 22x36");
+    }
+
+    #[test]
+    fn test_half_block_layout_fits_small_terminals() {
+        // 盤面は10x10文字になり、80x24の標準端末ならサイドバーをすべて表示できる
+        assert_eq!(BoardStyle::HalfBlock.frame_size(), (12, 12));
+        let standard = Layout::for_screen(80, 24, BoardStyle::HalfBlock).unwrap();
+        assert_eq!(standard.sidebar_style, SidebarStyle::Condensed);
+        let tall = Layout::for_screen(80, 30, BoardStyle::HalfBlock).unwrap();
+        assert_eq!(tall.sidebar_style, SidebarStyle::Full);
+        assert_eq!(tall.board, (23, 2));
+
+        // 2段が1行にまとまる
+        let pane = Layout::for_screen(34, 14, BoardStyle::HalfBlock).unwrap();
+        assert_eq!(pane.sidebar_placement, SidebarPlacement::Right);
+        assert_eq!(pane.sidebar, (14, 0));
+        assert_eq!(pane.cell_position(3, 4), (4, 3));
+        assert_eq!(pane.cell_position(3, 5), (4, 3));
+
+        assert_eq!(minimum_sizes_text(BoardStyle::HalfBlock), "34x14 OR 21x26");
+        assert_eq!(
+            Layout::for_screen(34, 22, BoardStyle::Counts)
+                .unwrap()
+                .cell_position(3, 5),
+            (4, 6)
+        );
+    }
+}
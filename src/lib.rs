@@ -110,17 +110,25 @@ impl TimeProvider for MockTimeProvider {
 
 // モジュールのインポート
 mod board_logic;
+mod board_notation;
 mod cell;
 mod config;
 mod game_color;
 mod game_input;
+mod glyph;
+mod fumen;
+mod gravity;
+mod hint;
+mod placement;
+mod puzzle;
 mod random;
 mod scheduler;
 mod scoring;
+mod simulator;
 mod tetromino;
 
 use animation::{calculate_line_clear_score, process_line_clear, Animation};
-use cell::Cell;
+use cell::{BlockKind, Cell};
 use config::*;
 use game_color::GameColor;
 use game_input::GameInput;
@@ -228,6 +236,35 @@ impl WasmCustomScoreSystem {
     pub fn get_chain_bonus(&self) -> u32 {
         self.inner.chain_bonus
     }
+
+    /// 現在のコンボ数を取得
+    #[wasm_bindgen]
+    pub fn get_combo(&self) -> u32 {
+        self.inner.combo
+    }
+
+    /// Back-to-Back継続中かどうかを取得
+    #[wasm_bindgen]
+    pub fn get_back_to_back(&self) -> bool {
+        self.inner.back_to_back
+    }
+
+    /// 直近のライン消去ロックの内訳を取得
+    /// [lines_cleared, base_points, combo, combo_bonus, back_to_back(0/1), back_to_back_bonus]
+    #[wasm_bindgen]
+    pub fn get_last_lock_breakdown(&self) -> Vec<u32> {
+        match &self.inner.last_lock {
+            Some(breakdown) => vec![
+                breakdown.lines_cleared,
+                breakdown.base_points,
+                breakdown.combo,
+                breakdown.combo_bonus,
+                breakdown.back_to_back as u32,
+                breakdown.back_to_back_bonus,
+            ],
+            None => vec![],
+        }
+    }
 }
 
 /// Web版用のTetrominoShape列挙型
@@ -286,6 +323,7 @@ pub struct SimpleTetromino {
     pub y: usize,
     pub rotation: u8,
     pub colors: Vec<GameColor>, // 各ブロックの色（4要素固定）
+    pub kinds: Vec<BlockKind>,  // 各ブロックの種類（colorsと同じ並び）
     pub shape: u8,              // 0=I, 1=O, 2=T, 3=L, 4=J, 5=S, 6=Z
 }
 
@@ -342,6 +380,7 @@ impl SimpleTetromino {
             y: 0,
             rotation: 0,
             colors: colors.to_vec(),
+            kinds: vec![BlockKind::Normal; 4],
             shape,
         }
     }
@@ -400,6 +439,19 @@ impl SimpleTetromino {
         }
     }
 
+    /// 同じ位置・向き・配色の共通Tetrominoに変換する（配置の探索・評価用）
+    pub fn to_tetromino(&self) -> Tetromino {
+        let shape = tetromino::TetrominoShape::all_shapes()[self.shape as usize % 7];
+        let colors = [0, 1, 2, 3].map(|index| self.colors[index % self.colors.len()]);
+        let kinds = [0, 1, 2, 3].map(|index| self.kinds[index % self.kinds.len()]);
+        let mut piece = Tetromino::from_shape(shape, colors).with_block_kinds(kinds);
+        for _ in 0..self.rotation % 4 {
+            piece = piece.rotated();
+        }
+        piece.pos = (self.x as i8, self.y as i8);
+        piece
+    }
+
     /// 現在の回転状態でのブロック座標と色のペアを返す
     pub fn iter_blocks_with_colors(&self) -> Vec<((i8, i8), GameColor)> {
         let blocks = self.get_blocks_at_rotation(self.rotation);
@@ -439,74 +491,86 @@ pub struct WasmGameState {
     current_board_height: usize,    // 動的ボード高さ（CLI版と同じ）
     // アニメーション関連（CLI版と同等）
     animation: Vec<Animation>, // CLI版と同じVec<Animation>管理
+    solid_purge_mode: scoring::SolidPurgeMode,
+    special_block_rates: tetromino::SpecialBlockRates,
+    gravity_mode: gravity::GravityMode,
+    // パズルモード関連
+    puzzles: Vec<puzzle::Puzzle>,
+    puzzle_index: Option<usize>,
+    piece_queue: Option<VecDeque<SimpleTetromino>>, // パズルの固定ピース列（通常モードではNone）
+    puzzle_status: u8,                              // 0: プレイ中, 1: クリア, 2: 失敗
+    puzzle_progress: puzzle::PuzzleProgress,
+    lines_cleared: u32,
+    hint: Option<hint::Hint>, // 操作中のピースについて求めたヒント（次のピースが出ると消える）
 }
 
-/// 盤面から揃っているラインを1本だけ検出（下から上に探索）
-#[cfg(target_arch = "wasm32")]
-fn find_first_complete_line_wasm(board: &Vec<Vec<Cell>>, current_board_height: usize) -> Option<usize> {
-    // 下から上に探索（Push Downで下から処理するため）
-    for y in (0..current_board_height).rev() {
-        let is_complete = board[y].iter().all(|&cell| {
-            matches!(cell, Cell::Occupied(_)) || matches!(cell, Cell::Connected { color: _, count: _ })
-        });
-        if is_complete {
-            return Some(y);
-        }
-    }
-    None
-}
-
-/// 次の揃ったラインを検出してLineBlink アニメーションを開始（WASM版）
+/// 揃ったラインをすべて検出してLineBlink アニメーションを開始（WASM版）
 #[cfg(target_arch = "wasm32")]
 fn check_and_start_next_line_animation_wasm(state: &mut WasmGameState) {
     let current_time = state.time_provider.now();
-    
-    if let Some(line_y) = find_first_complete_line_wasm(&state.board, state.current_board_height) {
-        // 揃ったラインが1本見つかった
-        // 隣接ブロック接続処理
-        crate::board_logic::find_and_connect_adjacent_blocks(&mut state.board, &[line_y]);
-        
+    let lines = animation::find_complete_lines(&state.board, state.current_board_height);
+
+    if !lines.is_empty() {
+        state.lines_cleared += lines.len() as u32;
+
+        // 隣接ブロック接続処理（消去ラインは除外）
+        crate::board_logic::find_and_connect_adjacent_blocks(&mut state.board, &lines);
+
         state.update_connected_block_counts();
         state.update_max_chains();
-        
+
         // 盤面全体の連結グループを評価し、CHAIN-BONUS獲得量を更新
         let total_chain_bonus = crate::board_logic::calculate_chain_bonus(&state.board);
-        state.custom_score_system
+        state
+            .custom_score_system
             .inner
             .set_chain_bonus_from_total(total_chain_bonus);
-        
-        // スコア計算
-        let scores = animation::calculate_line_clear_score(
-            &state.board,
-            line_y,
-            &state.custom_score_system.inner.max_chains,
-        );
-        for (_, points) in scores {
-            state.custom_score_system.inner.add_score(points);
+
+        // スコア計算（ラインごとに加算）
+        for &line_y in &lines {
+            let scores = animation::calculate_line_clear_score(
+                &state.board,
+                line_y,
+                &state.custom_score_system.inner.max_chains,
+            );
+            let line_points = scores.iter().map(|(_, points)| points).sum();
+            state
+                .custom_score_system
+                .inner
+                .add_line_clear_points(line_points);
         }
-        
-        // LineBlink アニメーション開始
+
+        console_log!("ライン {:?} のLineBlink開始", lines);
+
+        // 揃ったすべてのラインで1つのLineBlink アニメーションを開始
         state.animation.push(animation::Animation::LineBlink {
-            lines: vec![line_y],
+            lines,
             count: 0,
             start_time: current_time,
         });
-        
-        console_log!("段階的検出: ライン {} のLineBlink開始", line_y);
     } else {
         // 揃ったラインがなくても、全体の隣接ブロック接続処理を実行
         crate::board_logic::find_and_connect_adjacent_blocks(&mut state.board, &[]);
         state.update_connected_block_counts();
         state.update_max_chains();
-        
+
         let total_chain_bonus = crate::board_logic::calculate_chain_bonus(&state.board);
-        state.custom_score_system
+        state
+            .custom_score_system
             .inner
             .set_chain_bonus_from_total(total_chain_bonus);
-        
+
+        // ロックに伴うライン消去が終わったのでコンボ・B2Bボーナスを確定
+        state.custom_score_system.inner.finish_lock();
+
+        // パズルのクリア条件を満たしていれば終了
+        if state.check_puzzle_goal() {
+            return;
+        }
+
         // 揃ったラインがなければ新ピース生成
         state.spawn_piece();
-        console_log!("段階的検出: 揃ったラインなし、新ピース生成");
+        console_log!("揃ったラインなし、新ピース生成");
     }
 }
 
@@ -532,6 +596,16 @@ impl WasmGameState {
             tetromino_bag: WebTetrominoBag::new(),
             current_board_height: BOARD_HEIGHT, // CLI版と同じ初期値
             animation: Vec::new(),              // CLI版と同じ初期状態
+            solid_purge_mode: scoring::SolidPurgeMode::Automatic,
+            special_block_rates: tetromino::SpecialBlockRates::default(),
+            gravity_mode: gravity::GravityMode::default(),
+            puzzles: puzzle::builtin_puzzles(),
+            puzzle_index: None,
+            piece_queue: None,
+            puzzle_status: 0,
+            puzzle_progress: puzzle::PuzzleProgress::default(),
+            lines_cleared: 0,
+            hint: None,
         }
     }
 
@@ -548,6 +622,10 @@ impl WasmGameState {
         self.current_board_height = BOARD_HEIGHT; // ボード高さもリセット
                                                   // アニメーション状態もリセット
         self.animation.clear();
+        self.lines_cleared = 0;
+        self.puzzle_index = None;
+        self.piece_queue = None;
+        self.puzzle_status = 0;
 
         // CLI版と同じピース初期化ロジック
         // 1. 最初にnext_pieceのみを生成（CLI版のnew()と同じ）
@@ -564,7 +642,60 @@ impl WasmGameState {
     /// 7-bagを使った新しいピース生成
     fn new_random_piece(&mut self) -> SimpleTetromino {
         let shape = self.tetromino_bag.next();
-        SimpleTetromino::from_shape(shape)
+        let mut piece = SimpleTetromino::from_shape(shape);
+        let mut provider = create_default_random_provider();
+        for kind in piece.kinds.iter_mut() {
+            *kind = self.special_block_rates.roll(&mut provider);
+        }
+        piece
+    }
+
+    /// 特殊ブロックの出現確率（%）を設定。合計が100を超える場合は設定せずfalseを返す
+    #[wasm_bindgen]
+    pub fn set_special_block_rates(&mut self, wildcard: u32, bomb: u32, garbage: u32) -> bool {
+        let rates = tetromino::SpecialBlockRates {
+            wildcard_percent: wildcard,
+            bomb_percent: bomb,
+            garbage_percent: garbage,
+        };
+        if rates.total_percent() > 100 {
+            return false;
+        }
+        self.special_block_rates = rates;
+        true
+    }
+
+    /// 最下段ライン消去後の重力ルールを設定 [0: Naive, 1: Sticky, 2: Cascade]
+    /// 不明な値の場合は設定せずfalseを返す
+    #[wasm_bindgen]
+    pub fn set_gravity_mode(&mut self, mode: u8) -> bool {
+        self.gravity_mode = match mode {
+            0 => gravity::GravityMode::Naive,
+            1 => gravity::GravityMode::Sticky,
+            2 => gravity::GravityMode::Cascade,
+            _ => return false,
+        };
+        true
+    }
+
+    /// 現在のピースの各ブロックの種類を取得 [0: 通常, 1: ワイルドカード, 2: 爆弾, 3: ガベージ]
+    #[wasm_bindgen]
+    pub fn get_current_piece_block_kinds(&self) -> Vec<u8> {
+        self.current_piece
+            .as_ref()
+            .map(|piece| {
+                piece
+                    .kinds
+                    .iter()
+                    .map(|kind| match kind {
+                        BlockKind::Normal => 0,
+                        BlockKind::Wildcard => 1,
+                        BlockKind::Bomb => 2,
+                        BlockKind::Garbage => 3,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
     }
 
     /// 新しいピースをスポーン（CLI版と同じロジック）
@@ -573,8 +704,20 @@ impl WasmGameState {
 
         // next_pieceをcurrent_pieceにする
         self.current_piece = self.next_piece.take();
-        // 新しいnext_pieceを生成する（7-bag使用）
-        self.next_piece = Some(self.new_random_piece());
+        // 新しいnext_pieceを生成する（7-bag使用、パズルでは固定ピース列から取り出す）
+        self.next_piece = match &mut self.piece_queue {
+            Some(queue) => queue.pop_front(),
+            None => Some(self.new_random_piece()),
+        };
+        self.hint = None;
+
+        // パズルのピースを使い切ったら失敗
+        if self.current_piece.is_none() && self.piece_queue.is_some() {
+            self.game_mode = 2; // GameOver
+            self.puzzle_status = 2;
+            console_log!("Puzzle failed: out of pieces");
+            return;
+        }
 
         // current_pieceが有効な位置にあるかチェック
         if let Some(piece) = &self.current_piece {
@@ -587,6 +730,204 @@ impl WasmGameState {
         console_log!("spawn_piece completed: next → current, new next generated");
     }
 
+    /// 操作中のピースのおすすめ配置を求める（見つからなければfalse）
+    #[wasm_bindgen]
+    pub fn request_hint(&mut self) -> bool {
+        let rules = simulator::SimulationRules {
+            gravity_mode: self.gravity_mode,
+            solid_purge_mode: self.solid_purge_mode,
+        };
+        self.hint = self.current_piece.as_ref().and_then(|piece| {
+            hint::best_placement(
+                &self.board,
+                self.current_board_height,
+                &self.custom_score_system.inner,
+                &piece.to_tetromino(),
+                rules,
+            )
+        });
+        self.hint.is_some()
+    }
+
+    /// ヒントの配置で固定されるセルの座標 [x0, y0, x1, y1, ...]（ヒントがなければ空）
+    #[wasm_bindgen]
+    pub fn get_hint_cells(&self) -> Vec<i8> {
+        self.hint
+            .as_ref()
+            .map(|hint| {
+                hint.placement
+                    .cells()
+                    .iter()
+                    .flat_map(|&(x, y)| [x, y])
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// ヒントの理由（例: "+3 CYAN CHAIN, CREATES 10-GROUP"）。ヒントがなければ空文字列
+    #[wasm_bindgen]
+    pub fn get_hint_explanation(&self) -> String {
+        self.hint
+            .as_ref()
+            .map(|hint| hint.explanation())
+            .unwrap_or_default()
+    }
+
+    /// 読み込まれているパズルの数
+    #[wasm_bindgen]
+    pub fn get_puzzle_count(&self) -> usize {
+        self.puzzles.len()
+    }
+
+    /// パズル名を取得（範囲外なら空文字列）
+    #[wasm_bindgen]
+    pub fn get_puzzle_name(&self, index: usize) -> String {
+        self.puzzles
+            .get(index)
+            .map(|puzzle| puzzle.name.clone())
+            .unwrap_or_default()
+    }
+
+    /// パズルのクリア条件の説明を取得（範囲外なら空文字列）
+    #[wasm_bindgen]
+    pub fn get_puzzle_goal(&self, index: usize) -> String {
+        self.puzzles
+            .get(index)
+            .map(|puzzle| puzzle.goal.describe())
+            .unwrap_or_default()
+    }
+
+    /// 現在のパズルのクリア条件の進捗 [現在値, 目標値]（パズル以外では空）
+    #[wasm_bindgen]
+    pub fn get_puzzle_goal_progress(&self) -> Vec<u32> {
+        self.puzzle_index
+            .and_then(|index| self.puzzles.get(index))
+            .map(|puzzle| {
+                vec![
+                    puzzle
+                        .goal
+                        .progress(&self.custom_score_system.inner, self.lines_cleared),
+                    puzzle.goal.target(),
+                ]
+            })
+            .unwrap_or_default()
+    }
+
+    /// パズルパックのテキストを読み込んで差し替える。解析に失敗した場合はfalseを返す
+    #[wasm_bindgen]
+    pub fn load_puzzle_pack(&mut self, text: &str) -> bool {
+        match puzzle::parse_pack(text) {
+            Ok(puzzles) => {
+                self.puzzles = puzzles;
+                true
+            }
+            Err(message) => {
+                console_log!("Puzzle pack error: {}", message);
+                false
+            }
+        }
+    }
+
+    /// 指定したパズルを開始。範囲外の場合はfalseを返す
+    #[wasm_bindgen]
+    pub fn start_puzzle(&mut self, index: usize) -> bool {
+        let Some(puzzle) = self.puzzles.get(index).cloned() else {
+            return false;
+        };
+        console_log!("Starting puzzle: {}", puzzle.name);
+        self.start_game();
+
+        self.board = puzzle.board;
+        self.current_board_height = puzzle.board_height;
+        let mut queue: VecDeque<SimpleTetromino> = puzzle
+            .pieces
+            .iter()
+            .map(|piece| SimpleTetromino::from_shape_with_colors(piece.shape as u8, piece.colors))
+            .collect();
+        self.current_piece = None;
+        self.next_piece = queue.pop_front();
+        self.piece_queue = Some(queue);
+        self.puzzle_index = Some(index);
+
+        // 盤面に置かれた連結ブロックの連結数を数え直す
+        self.update_connected_block_counts();
+        self.update_max_chains();
+        self.spawn_piece();
+        true
+    }
+
+    /// パズルの状態を取得 [0: プレイ中・パズル以外, 1: クリア, 2: 失敗]
+    #[wasm_bindgen]
+    pub fn get_puzzle_status(&self) -> u8 {
+        self.puzzle_status
+    }
+
+    /// クリア済みパズル名の一覧（1行1パズル、localStorage保存用）
+    #[wasm_bindgen]
+    pub fn get_completed_puzzles(&self) -> String {
+        self.puzzle_progress.to_text()
+    }
+
+    /// 保存しておいたクリア済みパズル名の一覧を復元
+    #[wasm_bindgen]
+    pub fn set_completed_puzzles(&mut self, text: &str) {
+        self.puzzle_progress = puzzle::PuzzleProgress::from_text(text);
+    }
+
+    /// パズルがクリア済みかどうか
+    #[wasm_bindgen]
+    pub fn is_puzzle_completed(&self, index: usize) -> bool {
+        self.puzzles
+            .get(index)
+            .is_some_and(|puzzle| self.puzzle_progress.is_completed(&puzzle.name))
+    }
+
+    /// 現在の盤面と操作中のピースをfumen文字列で取得（変換できない場合は空文字列）
+    #[wasm_bindgen]
+    pub fn get_board_fumen(&self) -> String {
+        let piece = self.current_piece.as_ref().and_then(|piece| {
+            let shape = tetromino::TetrominoShape::all_shapes()
+                .get(piece.shape as usize)
+                .copied()?;
+            Some(fumen::FumenPiece {
+                shape,
+                rotation: piece.rotation,
+                pos: (piece.x as i8, piece.y as i8),
+            })
+        });
+        fumen::encode(&[fumen::FumenPage {
+            board: self.board.clone(),
+            board_height: self.current_board_height,
+            piece,
+        }])
+        .unwrap_or_default()
+    }
+
+    /// fumen文字列の最初のページの盤面を読み込む（練習用、start_gameの後に呼ぶ）
+    /// 読み込めない場合は盤面を変えずにfalseを返す
+    #[wasm_bindgen]
+    pub fn load_board_fumen(&mut self, text: &str) -> bool {
+        match fumen::decode(text) {
+            Ok(mut pages) => {
+                let page = pages.remove(0);
+                self.board = page.board;
+                self.current_board_height = page.board_height;
+                // 連結数と、そこから決まるMAX-CHAIN・CHAIN-BONUSを数え直す
+                self.update_connected_block_counts();
+                self.update_max_chains();
+                let total_chain_bonus = crate::board_logic::calculate_chain_bonus(&self.board);
+                self.custom_score_system
+                    .inner
+                    .set_chain_bonus_from_total(total_chain_bonus);
+                true
+            }
+            Err(message) => {
+                console_log!("Fumen error: {}", message);
+                false
+            }
+        }
+    }
+
     /// 現在の合計スコアを取得
     #[wasm_bindgen]
     pub fn get_score(&self) -> u32 {
@@ -612,6 +953,24 @@ impl WasmGameState {
         self.custom_score_system.get_score_details()
     }
 
+    /// 現在のコンボ数を取得
+    #[wasm_bindgen]
+    pub fn get_combo(&self) -> u32 {
+        self.custom_score_system.get_combo()
+    }
+
+    /// Back-to-Back継続中かどうかを取得
+    #[wasm_bindgen]
+    pub fn get_back_to_back(&self) -> bool {
+        self.custom_score_system.get_back_to_back()
+    }
+
+    /// 直近のライン消去ロックの内訳を取得
+    #[wasm_bindgen]
+    pub fn get_last_lock_breakdown(&self) -> Vec<u32> {
+        self.custom_score_system.get_last_lock_breakdown()
+    }
+
     /// スコア表示用文字列を取得
     #[wasm_bindgen]
     pub fn get_score_display(&self) -> String {
@@ -656,12 +1015,45 @@ impl WasmGameState {
                         result.push(color_id);
                     }
                     Cell::Solid => result.push(21),
+                    Cell::Garbage => result.push(20), // ガベージはグレーとして表示
+                    Cell::Wildcard => result.push(22),
+                    Cell::Bomb => result.push(23),
                 }
             }
         }
         result
     }
 
+    /// 記号表示（色覚に頼らない表示）で盤面に重ねる模様（`get_board_state`と同じ並び）
+    ///
+    /// 0: なし / 1: 枠線（Cyan） / 2: 横縞（Magenta） / 3: 斜線（Yellow）。連結ブロックは4を足す
+    #[wasm_bindgen]
+    pub fn get_board_patterns(&self) -> Vec<u8> {
+        self.board
+            .iter()
+            .flatten()
+            .map(|&cell| match cell {
+                Cell::Occupied(color) => glyph::Pattern::for_color(color) as u8,
+                Cell::Connected { color, .. } => {
+                    glyph::Pattern::for_color(color) as u8 + glyph::CONNECTED_PATTERN_FLAG
+                }
+                _ => 0,
+            })
+            .collect()
+    }
+
+    /// ピース・ゴースト・NEXTのブロックの色番号（`get_current_piece_blocks`などの3番目の値）の模様
+    #[wasm_bindgen]
+    pub fn get_color_pattern(color: u8) -> u8 {
+        let color = match color {
+            0 => GameColor::Cyan,
+            1 => GameColor::Magenta,
+            2 => GameColor::Yellow,
+            _ => return glyph::Pattern::Plain as u8,
+        };
+        glyph::Pattern::for_color(color) as u8
+    }
+
     /// 入力を処理
     #[wasm_bindgen]
     pub fn handle_input(&mut self, input_code: u8) -> bool {
@@ -674,6 +1066,8 @@ impl WasmGameState {
             5 => GameInput::HardDrop,
             6 => GameInput::Restart,
             7 => GameInput::Quit,
+            8 => GameInput::SolidPurge,
+            9 => GameInput::Hint,
             _ => GameInput::Unknown,
         };
 
@@ -730,6 +1124,14 @@ impl WasmGameState {
                     false
                 }
             }
+            GameInput::SolidPurge => {
+                if self.game_mode == 1 {
+                    self.purge_solid_lines(u32::MAX) > 0
+                } else {
+                    false
+                }
+            }
+            GameInput::Hint => self.game_mode == 1 && self.request_hint(),
             _ => false,
         }
     }
@@ -839,9 +1241,11 @@ impl WasmGameState {
                     && board_y >= 0
                     && board_y < BOARD_HEIGHT as i8
                 {
-                    // 各ブロックに個別の色を使用
+                    // 各ブロックに個別の色と種類を使用
                     let block_color = piece.colors[block_index % piece.colors.len()];
-                    self.board[board_y as usize][board_x as usize] = Cell::Occupied(block_color);
+                    let block_kind = piece.kinds[block_index % piece.kinds.len()];
+                    self.board[board_y as usize][board_x as usize] =
+                        block_kind.to_cell(block_color);
                     console_log!(
                         "Placing block {} at ({}, {}) with color {:?}",
                         block_index,
@@ -1128,82 +1532,87 @@ impl WasmGameState {
         // 共通アニメーション処理モジュールを使用
         let result = animation::update_animations(&mut self.animation, current_time);
 
-        // LineBlink完了処理（段階的：1本ずつ）
+        // LineBlink完了処理（揃ったラインをまとめて処理）
         for completed_lines in result.completed_line_blinks.clone() {
-            // 段階的検出では1本ずつ処理されるので、completed_linesには1本しか入っていない
-            assert_eq!(completed_lines.len(), 1, "段階的検出では1本ずつ処理する");
-            let line_y = completed_lines[0];
-            
-            // Bottom line かどうかを判定
-            let is_bottom_line = line_y == self.current_board_height - 1;
-
-            if is_bottom_line {
-                // Handle bottom line (standard Tetris clear)
-                self.board.remove(line_y);
-                self.board.insert(0, vec![Cell::Empty; BOARD_WIDTH]);
-
-                // Update connected block counts after bottom line clear
-                self.update_all_connected_block_counts();
-                
-                console_log!("Bottom line {} cleared", line_y);
-                
-                // Bottom line消去後、次のラインを検出
+            let (bottom_lines, solid_lines, _) = animation::process_line_clear(
+                &mut self.board,
+                self.current_board_height,
+                &completed_lines,
+                self.gravity_mode,
+            );
+
+            // Update connected block counts after line clear
+            self.update_all_connected_block_counts();
+            console_log!(
+                "Bottom lines {:?} cleared, lines {:?} converted to Solid",
+                bottom_lines,
+                solid_lines
+            );
+
+            if solid_lines.is_empty() && self.gravity_mode == gravity::GravityMode::Cascade {
+                // Cascade重力: 空いた行へ向かってブロックを1段ずつ落とす
+                self.animation.push(animation::Animation::GravityFall {
+                    start_time: current_time,
+                });
+            } else if solid_lines.is_empty() {
+                // Bottom lineのみ消去した場合は、次のラインを検出
                 check_and_start_next_line_animation_wasm(self);
             } else {
-                // Handle non-bottom line (custom clear with Solid conversion)
-                // Remove isolated blocks
-                crate::board_logic::remove_isolated_blocks(&mut self.board, line_y);
+                self.consume_chain_bonus_for_solid_lines();
 
-                // Turn line to Solid
-                for x in 0..BOARD_WIDTH {
-                    self.board[line_y][x] = Cell::Solid;
+                // Solid化したすべてのラインで並行してpush-downアニメーションを開始
+                for solid_line_y in solid_lines {
+                    self.animation.push(animation::Animation::PushDown {
+                        solid_line_y,
+                        start_time: current_time,
+                    });
                 }
-
-                // Update connected blocks after line clear
-                self.update_all_connected_block_counts();
-                self.consume_chain_bonus_for_solid_lines();
-                
-                console_log!("Non-bottom line {} converted to Solid", line_y);
-                
-                // Trigger push-down animation
-                self.animation.push(animation::Animation::PushDown {
-                    solid_line_y: line_y,
-                    start_time: current_time,
-                });
             }
         }
 
         // 継続するアニメーションを設定
         self.animation.extend(result.continuing_animations);
 
-        // Push Down完了処理
-        for solid_line_y in result.completed_push_downs {
-            match animation::process_push_down_step(
+        // Push Down完了処理（同時に下がっているラインをまとめて1ステップ進める）
+        if !result.completed_push_downs.is_empty() {
+            let step_results = animation::process_push_down_steps(
                 &mut self.board,
                 &mut self.current_board_height,
-                solid_line_y,
-            ) {
-                animation::PushDownStepResult::Completed => {
-                    // Push Down完了後にconnected block countsを更新
-                    self.update_all_connected_block_counts();
-                    console_log!("PushDown animation completed for line {}", solid_line_y);
-                    
-                    // Push Down完了後、次のラインを検出
-                    if self.animation.is_empty() {
-                        check_and_start_next_line_animation_wasm(self);
-                    }
-                }
-                animation::PushDownStepResult::Moved { new_solid_line_y } => {
+                &result.completed_push_downs,
+            );
+
+            // Board structure changed - update connected blocks
+            self.update_all_connected_block_counts();
+
+            for step_result in step_results {
+                if let animation::PushDownStepResult::Moved { new_solid_line_y } = step_result {
                     // 新しい位置でPush Downアニメーションを継続
                     self.animation.push(animation::Animation::PushDown {
                         solid_line_y: new_solid_line_y,
                         start_time: current_time,
                     });
-                    // Board structure changed - update connected blocks
-                    self.update_all_connected_block_counts();
-                    console_log!("PushDown animation moved line to {}", new_solid_line_y);
                 }
             }
+
+            // すべてのPush Down完了後、次のラインを検出
+            if self.animation.is_empty() {
+                check_and_start_next_line_animation_wasm(self);
+            }
+        }
+
+        // GravityFall完了処理（支えのない塊を1段落とす）
+        if result.completed_gravity_falls > 0 {
+            let moved = gravity::step_gravity(&mut self.board, self.current_board_height);
+            self.update_all_connected_block_counts();
+
+            if moved {
+                self.animation.push(animation::Animation::GravityFall {
+                    start_time: current_time,
+                });
+            } else {
+                // 落下が止まったら、新しい連結や揃ったラインを検出
+                check_and_start_next_line_animation_wasm(self);
+            }
         }
     }
 
@@ -1232,7 +1641,8 @@ impl WasmGameState {
         // self.update_all_connected_block_counts();
     }
 
-    fn consume_chain_bonus_for_solid_lines(&mut self) {
+    /// 盤面の底に積み上がっているSolidラインの段数
+    fn count_bottom_solid_lines(&self) -> usize {
         let mut solid_lines = 0usize;
         let mut y = self.current_board_height;
 
@@ -1241,9 +1651,22 @@ impl WasmGameState {
             y += 1;
         }
 
-        if solid_lines == 0 {
+        solid_lines
+    }
+
+    fn consume_chain_bonus_for_solid_lines(&mut self) {
+        if self.solid_purge_mode == scoring::SolidPurgeMode::Manual {
             return;
         }
+        let solid_lines = self.count_bottom_solid_lines();
+        self.remove_bottom_solid_lines(solid_lines);
+    }
+
+    /// CHAIN-BONUSを消費して底のSolidラインを最大`solid_lines`段消去し、消去した段数を返す
+    fn remove_bottom_solid_lines(&mut self, solid_lines: usize) -> usize {
+        if solid_lines == 0 {
+            return 0;
+        }
 
         let removable = self
             .custom_score_system
@@ -1251,7 +1674,7 @@ impl WasmGameState {
             .consume_chain_bonus(solid_lines as u32) as usize;
 
         if removable == 0 {
-            return;
+            return 0;
         }
 
         for _ in 0..removable {
@@ -1265,8 +1688,44 @@ impl WasmGameState {
         for _ in 0..removable {
             self.board.insert(0, vec![Cell::Empty; BOARD_WIDTH]);
         }
+        // ヒントの配置はずれる前の盤面のものなので消す
+        self.hint = None;
 
         self.current_board_height = (self.current_board_height + removable).min(BOARD_HEIGHT);
+        removable
+    }
+
+    /// Solid purgeを手動で行うかどうかを設定（falseなら自動消費）
+    #[wasm_bindgen]
+    pub fn set_manual_solid_purge(&mut self, manual: bool) {
+        self.solid_purge_mode = if manual {
+            scoring::SolidPurgeMode::Manual
+        } else {
+            scoring::SolidPurgeMode::Automatic
+        };
+    }
+
+    /// CHAIN-BONUSの残量で消去できるSolidラインの段数を取得
+    #[wasm_bindgen]
+    pub fn get_purgeable_solid_lines(&self) -> u32 {
+        self.count_bottom_solid_lines()
+            .min(self.custom_score_system.get_chain_bonus() as usize) as u32
+    }
+
+    /// 底のSolidラインを最大`rows`段消去し、消去した段数を返す（Manualモード専用）
+    #[wasm_bindgen]
+    pub fn purge_solid_lines(&mut self, rows: u32) -> u32 {
+        if self.solid_purge_mode != scoring::SolidPurgeMode::Manual || !self.animation.is_empty() {
+            return 0;
+        }
+        let rows = (rows as usize).min(self.count_bottom_solid_lines());
+        let removed = self.remove_bottom_solid_lines(rows);
+
+        // 盤面全体が下にずれるので、操作中のピースも同じだけ下げる
+        if let Some(ref mut piece) = self.current_piece {
+            piece.y += removed;
+        }
+        removed as u32
     }
 
     /// アニメーション情報を取得（JavaScript用）
@@ -1305,6 +1764,11 @@ impl WasmGameState {
                     result.push(elapsed_ms);
                     result.push(*solid_line_y as i32);
                 }
+                Animation::GravityFall { start_time } => {
+                    result.push(3); // GravityFall type id
+                    let elapsed_ms = (current_time - *start_time).as_millis() as i32;
+                    result.push(elapsed_ms);
+                }
             }
         }
 
@@ -1312,69 +1776,20 @@ impl WasmGameState {
     }
 
     /// CLI版のcount_connected_blocks相当の実装（内部実装のみ）
-    /// cleared_line_y より下の行の連結ブロックを BFS で検出してカウント
+    /// cleared_line_y より下の行を含む連結グループを検出してカウント
     fn count_connected_blocks(&self, cleared_line_y: usize) -> Vec<((usize, usize), u32)> {
         let mut results = Vec::new();
-        let mut visited = vec![vec![false; BOARD_WIDTH]; BOARD_HEIGHT];
+        let groups =
+            crate::board_logic::find_color_groups(&self.board, self.current_board_height, &[]);
 
-        for y in (cleared_line_y + 1)..self.current_board_height {
-            for x in 0..BOARD_WIDTH {
-                let color = match self.board[y][x] {
-                    Cell::Occupied(c) => Some(c),
-                    Cell::Connected { color: c, count: _ } => Some(c),
-                    _ => None,
-                };
-
-                if let Some(color) = color {
-                    if visited[y][x] {
-                        continue;
-                    }
-
-                    let mut component = Vec::new();
-                    let mut queue = VecDeque::new();
-
-                    visited[y][x] = true;
-                    queue.push_back((x, y));
-                    component.push((x, y));
-
-                    // BFS で連結コンポーネント検出
-                    while let Some((qx, qy)) = queue.pop_front() {
-                        let neighbors = [
-                            (qx as i8 - 1, qy as i8),
-                            (qx as i8 + 1, qy as i8),
-                            (qx as i8, qy as i8 - 1),
-                            (qx as i8, qy as i8 + 1),
-                        ];
-
-                        for (nx, ny) in neighbors {
-                            if nx >= 0
-                                && nx < BOARD_WIDTH as i8
-                                && ny >= 0
-                                && (ny as usize) < self.current_board_height
-                            {
-                                let (nx_usize, ny_usize) = (nx as usize, ny as usize);
-                                if !visited[ny_usize][nx_usize] {
-                                    let neighbor_color = match self.board[ny_usize][nx_usize] {
-                                        Cell::Occupied(c) => Some(c),
-                                        Cell::Connected { color: c, count: _ } => Some(c),
-                                        _ => None,
-                                    };
-                                    if let Some(neighbor_color) = neighbor_color {
-                                        if neighbor_color == color {
-                                            visited[ny_usize][nx_usize] = true;
-                                            queue.push_back((nx_usize, ny_usize));
-                                            component.push((nx_usize, ny_usize));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-
-                    let component_size = component.len() as u32;
-                    for &(px, py) in &component {
-                        results.push(((px, py), component_size));
-                    }
+        for group in groups {
+            if !group.cells.iter().any(|&(_, y)| y > cleared_line_y) {
+                continue;
+            }
+            let component_size = group.cells.len() as u32;
+            for &(px, py) in &group.cells {
+                if self.board[py][px] != Cell::Wildcard {
+                    results.push(((px, py), component_size));
                 }
             }
         }
@@ -1393,69 +1808,46 @@ impl WasmGameState {
             }
         }
 
-        // 各セルについて連結コンポーネントサイズを再計算
-        let mut visited = vec![vec![false; BOARD_WIDTH]; BOARD_HEIGHT];
+        // 各連結グループのサイズを再計算（ワイルドカードも連結に数える）
+        let groups =
+            crate::board_logic::find_color_groups(&self.board, self.current_board_height, &[]);
 
-        for y in 0..self.current_board_height {
-            for x in 0..BOARD_WIDTH {
-                if !visited[y][x] {
-                    if let Cell::Occupied(color) = self.board[y][x] {
-                        // BFSで連結コンポーネントを検出
-                        let mut component = Vec::new();
-                        let mut queue = VecDeque::new();
-
-                        visited[y][x] = true;
-                        queue.push_back((x, y));
-                        component.push((x, y));
-
-                        while let Some((qx, qy)) = queue.pop_front() {
-                            let neighbors = [
-                                (qx as i8 - 1, qy as i8),
-                                (qx as i8 + 1, qy as i8),
-                                (qx as i8, qy as i8 - 1),
-                                (qx as i8, qy as i8 + 1),
-                            ];
-
-                            for (nx, ny) in neighbors {
-                                if nx >= 0
-                                    && nx < BOARD_WIDTH as i8
-                                    && ny >= 0
-                                    && (ny as usize) < self.current_board_height
-                                {
-                                    let (nx_usize, ny_usize) = (nx as usize, ny as usize);
-                                    if !visited[ny_usize][nx_usize] {
-                                        if let Cell::Occupied(neighbor_color) =
-                                            self.board[ny_usize][nx_usize]
-                                        {
-                                            if neighbor_color == color {
-                                                visited[ny_usize][nx_usize] = true;
-                                                queue.push_back((nx_usize, ny_usize));
-                                                component.push((nx_usize, ny_usize));
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
-                        // 連結コンポーネントサイズを各セルに設定
-                        let component_size = component.len() as u8; // u8にキャスト
-                        for &(cx, cy) in &component {
-                            if component_size > 1 {
-                                self.board[cy][cx] = Cell::Connected {
-                                    color,
-                                    count: component_size,
-                                };
-                            } else {
-                                self.board[cy][cx] = Cell::Occupied(color); // 単独ブロックはOccupiedのまま
-                            }
-                        }
-                    }
+        for group in groups {
+            // 連結コンポーネントサイズを各セルに設定（単独ブロックはOccupiedのまま）
+            let component_size = group.cells.len() as u8;
+            if component_size <= 1 {
+                continue;
+            }
+            for (cx, cy) in group.cells {
+                if let Cell::Occupied(color) = self.board[cy][cx] {
+                    self.board[cy][cx] = Cell::Connected {
+                        color,
+                        count: component_size,
+                    };
                 }
             }
         }
     }
 
+    /// パズルのクリア条件を満たしていればクリア済みとして記録し、ゲームを終了する
+    fn check_puzzle_goal(&mut self) -> bool {
+        let Some(puzzle) = self.puzzle_index.and_then(|index| self.puzzles.get(index)) else {
+            return false;
+        };
+        if !puzzle
+            .goal
+            .is_met(&self.custom_score_system.inner, self.lines_cleared)
+        {
+            return false;
+        }
+        let name = puzzle.name.clone();
+        self.puzzle_progress.mark_completed(&name);
+        self.game_mode = 2; // GameOver
+        self.puzzle_status = 1;
+        console_log!("Puzzle solved: {}", name);
+        true
+    }
+
     /// CLI版のupdate_connected_block_counts相当の実装
     /// ピースロック後に連結ブロック数を再計算・更新
     fn update_connected_block_counts(&mut self) {
@@ -1633,3 +2025,23 @@ fn wasm_shared_animation_module() {
         animations.len()
     );
 }
+
+#[cfg(all(target_arch = "wasm32", test))]
+#[wasm_bindgen_test]
+fn wasm_load_board_fumen_refreshes_chains() {
+    // 10個のCyanグループ（連結数つき）の盤面をfumenにして読み込む
+    let (board, board_height) =
+        board_notation::parse_board("ccccc.....\nccccc.....\n##########").unwrap();
+    let text = fumen::encode(&[fumen::FumenPage {
+        board,
+        board_height,
+        piece: None,
+    }])
+    .unwrap();
+
+    let mut game_state = WasmGameState::new();
+    game_state.start_game();
+    assert!(game_state.load_board_fumen(&text));
+    assert_eq!(game_state.get_max_chains(), vec![10, 0, 0]);
+    assert_eq!(game_state.get_chain_bonus(), 1);
+}
@@ -9,16 +9,18 @@ use std::io::{self};
 use std::time::{Duration, Instant};
 
 mod animation; // 共通アニメーション処理モジュール
+mod cli_options;
 mod config;
 mod game_color;
 mod game_input;
+mod glyph;
 mod random;
 mod scheduler;
+use cli_options::GameOptions;
 use config::*;
 use game_input::{CrosstermInputProvider, GameInput, InputProvider};
 use scheduler::{create_default_scheduler, Scheduler};
 
-#[cfg(test)]
 use game_color::GameColor;
 
 mod render;
@@ -83,18 +85,56 @@ mod cell;
 use cell::{Board, Cell};
 
 mod scoring;
-use scoring::CustomScoreSystem;
+use scoring::{CustomScoreSystem, SolidPurgeMode};
 
 mod tetromino;
-use tetromino::Tetromino;
+use tetromino::{PieceGenerator, SpecialBlockRates, Tetromino};
 
 mod board_logic;
-
-use animation::{process_push_down_step, update_animations, Animation, PushDownStepResult}; // 共通アニメーション関数
+mod board_notation;
+
+mod fumen;
+mod gravity;
+mod hint;
+use hint::Hint;
+mod placement;
+use gravity::GravityMode;
+
+mod play_mode;
+use play_mode::{ModeOutcome, PlayMode};
+
+mod survival;
+
+mod simulator;
+use simulator::SimulationRules;
+
+mod puzzle;
+use puzzle::{Puzzle, PuzzleProgress};
+use std::collections::VecDeque;
+
+mod practice;
+use practice::UndoHistory;
+
+mod bot;
+mod json;
+mod layout;
+use layout::BoardStyle;
+mod narration;
+use narration::{EventQueue, GameEvent};
+mod stats;
+use stats::GameStats;
+mod theme;
+use theme::Theme;
+mod game_output;
+use game_output::{GameOutput, ScreenOutput};
+mod virtual_terminal;
+
+use animation::{process_push_down_steps, update_animations, Animation, PushDownStepResult}; // 共通アニメーション関数
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum GameMode {
     Title,
+    PuzzleSelect,
     Playing,
     GameOver,
 }
@@ -105,77 +145,310 @@ struct GameState {
     board: Board,
     current_piece: Option<Tetromino>,
     next_piece: Option<Tetromino>,
+    // 出現した直後の操作中ピース（練習モードで巻き戻したときにこの位置から操作し直す）
+    spawned_piece: Option<Tetromino>,
+    // ピースの生成器（スナップショットに含めて、巻き戻し後も同じピース列にする）
+    pieces: PieceGenerator,
     animation: Vec<Animation>,
     lines_cleared: u32,
     fall_speed: Duration,
     current_board_height: usize,
     custom_score_system: CustomScoreSystem,
+    solid_purge_mode: SolidPurgeMode,
+    special_block_rates: SpecialBlockRates,
+    gravity_mode: GravityMode,
+    // Solid purgeで消去する段数（Noneなら消去可能な全段）
+    purge_amount: Option<usize>,
+    play_mode: PlayMode,
+    // プレイ開始時刻と経過時間（Sprintのタイム・Ultraの残り時間に使う）
+    play_started: Duration,
+    play_time: Duration,
+    // ゲーム終了理由（リザルト画面に表示）
+    outcome: Option<ModeOutcome>,
+    // サバイバルモードのせり上がり回数と、次にせり上がるプレイ経過時間
+    rises: u32,
+    next_rise_at: Option<Duration>,
+    // パズルモードで遊んでいるパズルと、まだ出ていない固定ピース列
+    puzzle: Option<Puzzle>,
+    piece_queue: Option<VecDeque<Tetromino>>,
+    // 操作中のピースについて求めたヒント（次のピースが出ると消える）
+    hint: Option<Hint>,
+    // 盤面のマスの描き方と、ハーフブロック表示で連結数を表示しているか
+    board_style: BoardStyle,
+    show_counts: bool,
+    // 連結グループの輪郭とボーナスまでの残りを重ねて表示しているか
+    show_groups: bool,
+    // プレイの統計と、サイドバーに統計を表示しているか
+    stats: GameStats,
+    show_stats: bool,
+    // 読み上げモードに渡す、まだ取り出されていない出来事
+    events: EventQueue,
 }
 
 impl GameState {
     fn new() -> Self {
+        Self::with_options(&GameOptions::default())
+    }
+
+    fn with_options(options: &GameOptions) -> Self {
+        let mut pieces = PieceGenerator::from_entropy();
         Self {
             mode: GameMode::Title,
             board: vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT],
             current_piece: None,
-            next_piece: Some(Tetromino::new_random()), // next_pieceを初期化
+            next_piece: Some(pieces.next_piece(&options.special_block_rates)), // next_pieceを初期化
+            spawned_piece: None,
+            pieces,
             animation: Vec::new(),
             lines_cleared: 0,
             fall_speed: FALL_SPEED_START,
             current_board_height: BOARD_HEIGHT,
             custom_score_system: CustomScoreSystem::new(),
+            solid_purge_mode: options.solid_purge_mode,
+            special_block_rates: options.special_block_rates,
+            gravity_mode: options.gravity_mode,
+            purge_amount: None,
+            play_mode: PlayMode::Endless,
+            play_started: Duration::ZERO,
+            play_time: Duration::ZERO,
+            outcome: None,
+            rises: 0,
+            next_rise_at: None,
+            puzzle: None,
+            piece_queue: None,
+            hint: None,
+            board_style: options.board_style,
+            show_counts: false,
+            show_groups: false,
+            stats: GameStats::default(),
+            show_stats: false,
+            events: EventQueue::default(),
         }
     }
 
-    fn ghost_piece(&self) -> Option<Tetromino> {
-        self.current_piece.as_ref().map(|piece| {
-            let mut ghost = piece.clone();
-            while self.is_valid_position(&ghost.moved(0, 1)) {
-                ghost = ghost.moved(0, 1);
+    /// タイトル画面で選んだモードを開始（`--board`の盤面はPRACTICEでだけ使う）
+    fn start_selected_mode(
+        &mut self,
+        play_mode: PlayMode,
+        start_board: Option<&(Board, usize)>,
+        now: Duration,
+    ) {
+        if let (PlayMode::Practice, Some((board, height))) = (play_mode, start_board) {
+            self.board = board.clone();
+            self.current_board_height = *height;
+            // 盤面に置かれたブロックの連結数と、そこから決まるMAX-CHAIN・CHAIN-BONUSを数え直す
+            self.update_all_connected_block_counts();
+            self.update_max_chains();
+            self.update_chain_bonus();
+        }
+        self.start_game(play_mode, now);
+    }
+
+    /// パズルの開始盤面と固定ピース列でプレイを開始
+    fn start_puzzle(&mut self, puzzle: &Puzzle, now: Duration) {
+        self.board = puzzle.board.clone();
+        self.current_board_height = puzzle.board_height;
+        let mut queue: VecDeque<Tetromino> = puzzle
+            .pieces
+            .iter()
+            .map(|piece| Tetromino::from_shape(piece.shape, piece.colors))
+            .collect();
+        self.next_piece = queue.pop_front();
+        self.piece_queue = Some(queue);
+        self.puzzle = Some(puzzle.clone());
+
+        // 盤面に置かれた連結ブロックの連結数を数え直す
+        self.update_all_connected_block_counts();
+        self.update_max_chains();
+        self.start_game(PlayMode::Puzzle, now);
+    }
+
+    /// パズルのクリア条件を満たしていればゲームを終了する
+    fn check_puzzle_goal(&mut self) -> bool {
+        let Some(puzzle) = &self.puzzle else {
+            return false;
+        };
+        if !puzzle
+            .goal
+            .is_met(&self.custom_score_system, self.lines_cleared)
+        {
+            return false;
+        }
+        self.mode = GameMode::GameOver;
+        self.outcome = Some(ModeOutcome::GoalReached);
+        true
+    }
+
+    /// 選択したモードでプレイを開始
+    fn start_game(&mut self, play_mode: PlayMode, now: Duration) {
+        self.mode = GameMode::Playing;
+        self.play_mode = play_mode;
+        self.play_started = now;
+        self.fall_speed = play_mode.fall_speed(self.lines_cleared);
+        if play_mode == PlayMode::Survival {
+            self.next_rise_at = Some(survival::rise_interval(0));
+        }
+        self.spawn_piece();
+    }
+
+    /// 経過時間を更新し、モードの目標・制限時間に達していればゲームを終了する
+    fn update_play_time(&mut self, now: Duration) {
+        self.play_time = now.saturating_sub(self.play_started);
+        if let Some(outcome) = self
+            .play_mode
+            .check_finished(self.lines_cleared, self.play_time)
+        {
+            self.mode = GameMode::GameOver;
+            self.outcome = Some(outcome);
+            return;
+        }
+        self.update_rising_rows();
+    }
+
+    /// サバイバルモード: せり上がりの時刻になったら盤面を押し上げて下から行を挿入する
+    /// ライン消去のアニメーション中は、終わるまでせり上がりを待つ
+    fn update_rising_rows(&mut self) {
+        let Some(next_rise_at) = self.next_rise_at else {
+            return;
+        };
+        if self.play_time < next_rise_at || !self.animation.is_empty() {
+            return;
+        }
+
+        let row =
+            survival::next_rising_row(self.rises, &mut random::create_default_random_provider());
+        let mut topped_out =
+            survival::insert_rising_row(&mut self.board, &mut self.current_board_height, row);
+        // 盤面が押し上げられたので、ヒントの配置は使えない
+        self.hint = None;
+
+        // 操作中のピースがせり上がった盤面と重なったら、1段押し上げる
+        if let Some(piece) = &self.current_piece {
+            if !self.is_valid_position(piece) {
+                let lifted = piece.moved(0, -1);
+                topped_out |= !self.is_valid_position(&lifted);
+                self.current_piece = Some(lifted);
             }
-            ghost
-        })
+        }
+
+        self.rises += 1;
+        self.events.push(GameEvent::RowRose);
+        self.next_rise_at = Some(self.play_time + survival::rise_interval(self.rises));
+        self.update_all_connected_block_counts();
+
+        if topped_out {
+            self.mode = GameMode::GameOver;
+            self.outcome = Some(ModeOutcome::ToppedOut);
+        } else {
+            // 自動消費モードでは、貯まっているCHAIN-BONUSでせり上がったSolidラインを消す
+            self.consume_chain_bonus_for_solid_lines();
+        }
+    }
+
+    fn ghost_piece(&self) -> Option<Tetromino> {
+        self.current_piece
+            .as_ref()
+            .map(|piece| placement::drop_to_floor(&self.board, self.current_board_height, piece))
+    }
+
+    /// 操作中のピースのおすすめ配置を求めてヒントに設定する
+    fn request_hint(&mut self) {
+        let Some(piece) = &self.current_piece else {
+            return;
+        };
+        let rules = SimulationRules {
+            gravity_mode: self.gravity_mode,
+            solid_purge_mode: self.solid_purge_mode,
+        };
+        self.hint = hint::best_placement(
+            &self.board,
+            self.current_board_height,
+            &self.custom_score_system,
+            piece,
+            rules,
+        );
     }
 
     fn spawn_piece(&mut self) {
         // next_pieceをcurrent_pieceにする
         self.current_piece = self.next_piece.take();
-        // 新しいnext_pieceを生成する
-        self.next_piece = Some(Tetromino::new_random());
+        // 新しいnext_pieceを生成する（パズルでは固定ピース列から取り出す）
+        self.next_piece = match &mut self.piece_queue {
+            Some(queue) => queue.pop_front(),
+            None => Some(self.pieces.next_piece(&self.special_block_rates)),
+        };
+        self.spawned_piece = self.current_piece.clone();
+        self.hint = None;
+        self.record_piece_event(true);
+
+        // パズルのピースを使い切ったら失敗
+        if self.current_piece.is_none() && self.piece_queue.is_some() {
+            self.mode = GameMode::GameOver;
+            self.outcome = Some(ModeOutcome::OutOfPieces);
+            return;
+        }
 
         // current_pieceが有効な位置にあるかチェック
         if let Some(piece) = &self.current_piece {
             if !self.is_valid_position(piece) {
                 self.mode = GameMode::GameOver;
+                self.outcome = Some(ModeOutcome::ToppedOut);
             }
         }
     }
 
-    fn is_valid_position(&self, piece: &Tetromino) -> bool {
-        for ((x, y), _) in piece.iter_blocks() {
-            // Check horizontal boundaries
-            if x < 0 || x >= BOARD_WIDTH as i8 {
-                return false;
-            }
-            // Check bottom boundary
-            if y >= self.current_board_height as i8 {
-                return false;
-            }
-            // Check collision with existing blocks, but only for visible part of the board (y >= 0)
-            if y >= 0 && self.board[y as usize][x as usize] != Cell::Empty {
-                return false;
-            }
-            // Allow blocks to be at y < 0 (above the visible board) without being invalid
-            // as long as they don't collide with existing blocks (which are only at y >= 0)
+    /// 操作中のピースの位置を出来事として記録する（`spawned`なら出現）
+    fn record_piece_event(&mut self, spawned: bool) {
+        let (Some(piece), Some(ghost)) = (self.current_piece.clone(), self.ghost_piece()) else {
+            return;
+        };
+        let drop = (ghost.pos.1 - piece.pos.1).max(0) as usize;
+        self.events.push(if spawned {
+            GameEvent::PieceSpawned { piece, drop }
+        } else {
+            GameEvent::PieceMoved { piece, drop }
+        });
+    }
+
+    /// 練習モード: 巻き戻し用に、操作中のピースを出現位置に戻した状態を複製する
+    fn practice_snapshot(&self) -> GameState {
+        let mut snapshot = self.clone();
+        if snapshot.current_piece.is_some() {
+            snapshot.current_piece = self.spawned_piece.clone();
         }
-        true
+        snapshot.events.clear();
+        snapshot
+    }
+
+    /// 練習モード: 1ピース分巻き戻す（`redo`ならやり直す）。戻せなければ何もしない
+    fn step_practice_history(&mut self, history: &mut UndoHistory<GameState>, redo: bool) -> bool {
+        let mut current = self.practice_snapshot();
+        let stepped = if redo {
+            history.redo(&mut current)
+        } else {
+            history.undo(&mut current)
+        };
+        if stepped {
+            // 表示の切り替えは巻き戻さない
+            current.board_style = self.board_style;
+            current.show_counts = self.show_counts;
+            current.show_groups = self.show_groups;
+            current.show_stats = self.show_stats;
+            *self = current;
+        }
+        stepped
+    }
+
+    fn is_valid_position(&self, piece: &Tetromino) -> bool {
+        placement::is_valid_position(&self.board, self.current_board_height, piece)
     }
 
     fn lock_piece(&mut self, time_provider: &dyn TimeProvider) {
         if let Some(piece) = self.current_piece.take() {
-            for ((x, y), color) in piece.iter_blocks() {
+            self.stats.record_piece(piece.shape);
+            for ((x, y), cell) in piece.iter_cells() {
                 if y >= 0 && y < BOARD_HEIGHT as i8 {
-                    self.board[y as usize][x as usize] = Cell::Occupied(color);
+                    self.board[y as usize][x as usize] = cell;
                 }
             }
         }
@@ -197,6 +470,7 @@ impl GameState {
     }
 
     fn update_max_chains(&mut self) {
+        let before = self.custom_score_system.max_chains.clone();
         // Scan the entire board to find the maximum connected block count for each color
         for y in 0..self.current_board_height {
             for x in 0..BOARD_WIDTH {
@@ -204,88 +478,48 @@ impl GameState {
                     self.custom_score_system
                         .max_chains
                         .update_max(color, count as u32);
+                    self.stats.record_group(count as u32);
                 }
             }
         }
+
+        // 記録を更新した色を出来事として残す
+        for color in [GameColor::Cyan, GameColor::Magenta, GameColor::Yellow] {
+            let count = self.custom_score_system.max_chains.get(color);
+            if count > before.get(color) {
+                self.events.push(GameEvent::MaxChain { color, count });
+            }
+        }
+    }
+
+    /// 盤面全体の連結グループを評価し、CHAIN-BONUS獲得量を更新
+    fn update_chain_bonus(&mut self) {
+        let before = self.custom_score_system.chain_bonus;
+        let total_chain_bonus = board_logic::calculate_chain_bonus(&self.board);
+        self.custom_score_system
+            .set_chain_bonus_from_total(total_chain_bonus);
+        if total_chain_bonus != before {
+            self.events.push(GameEvent::ChainBonus(total_chain_bonus));
+        }
     }
 
     fn update_all_connected_block_counts(&mut self) {
         // For full board update, we need to check all rows from 0 to current_board_height
-        // Use current_board_height as the limit and start from 0
-        let mut results = Vec::new();
-        let mut visited = vec![vec![false; BOARD_WIDTH]; BOARD_HEIGHT];
-
-        for y in 0..self.current_board_height {
-            for x in 0..BOARD_WIDTH {
-                if let Some(color) = match self.board[y][x] {
-                    Cell::Occupied(c) => Some(c),
-                    Cell::Connected { color: c, count: _ } => Some(c),
-                    _ => None,
-                } {
-                    if visited[y][x] {
-                        continue;
-                    }
-
-                    let mut component = Vec::new();
-                    let mut queue = std::collections::VecDeque::new();
-
-                    visited[y][x] = true;
-                    queue.push_back((x, y));
-                    component.push((x, y));
-
-                    while let Some((qx, qy)) = queue.pop_front() {
-                        let neighbors = [
-                            (qx as i8 - 1, qy as i8),
-                            (qx as i8 + 1, qy as i8),
-                            (qx as i8, qy as i8 - 1),
-                            (qx as i8, qy as i8 + 1),
-                        ];
-
-                        for (nx, ny) in neighbors {
-                            if nx >= 0
-                                && nx < BOARD_WIDTH as i8
-                                && ny >= 0
-                                && ny < self.current_board_height as i8
-                            {
-                                let (nx_usize, ny_usize) = (nx as usize, ny as usize);
-                                if !visited[ny_usize][nx_usize] {
-                                    let neighbor_color = match self.board[ny_usize][nx_usize] {
-                                        Cell::Occupied(c) => Some(c),
-                                        Cell::Connected { color: c, count: _ } => Some(c),
-                                        _ => None,
-                                    };
-                                    if let Some(neighbor_color) = neighbor_color {
-                                        if neighbor_color == color {
-                                            visited[ny_usize][nx_usize] = true;
-                                            queue.push_back((nx_usize, ny_usize));
-                                            component.push((nx_usize, ny_usize));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-
-                    let component_size = component.len() as u32;
-                    for &(px, py) in &component {
-                        results.push(((px, py), component_size));
-                    }
-                }
-            }
-        }
+        let groups = board_logic::find_color_groups(&self.board, self.current_board_height, &[]);
 
         // Update the board with the new counts
-        for ((x, y), count) in results {
-            if let Cell::Connected { color, count: _ } = self.board[y][x] {
-                self.board[y][x] = Cell::Connected {
-                    color,
-                    count: count as u8,
-                };
+        for group in groups {
+            let count = group.cells.len() as u8;
+            for (x, y) in group.cells {
+                if let Cell::Connected { color, count: _ } = self.board[y][x] {
+                    self.board[y][x] = Cell::Connected { color, count };
+                }
             }
         }
     }
 
-    fn consume_chain_bonus_for_solid_lines(&mut self) {
+    /// 盤面の底に積み上がっているSolidラインの段数
+    fn count_bottom_solid_lines(&self) -> usize {
         let mut solid_lines = 0usize;
         let mut y = self.current_board_height;
 
@@ -294,16 +528,61 @@ impl GameState {
             y += 1;
         }
 
-        if solid_lines == 0 {
+        solid_lines
+    }
+
+    /// CHAIN-BONUSの残量で消去できるSolidラインの最大段数
+    fn available_purge_lines(&self) -> usize {
+        self.count_bottom_solid_lines()
+            .min(self.custom_score_system.chain_bonus as usize)
+    }
+
+    /// Solid purgeで実際に消去される段数
+    fn purgeable_solid_lines(&self) -> usize {
+        let available = self.available_purge_lines();
+        self.purge_amount.map_or(available, |amount| amount.min(available))
+    }
+
+    fn consume_chain_bonus_for_solid_lines(&mut self) {
+        if self.solid_purge_mode == SolidPurgeMode::Manual {
             return;
         }
+        let solid_lines = self.count_bottom_solid_lines();
+        self.remove_bottom_solid_lines(solid_lines);
+    }
+
+    /// プレイヤー入力によるSolid purge（Manualモード専用）
+    fn purge_solid_lines(&mut self) {
+        if self.solid_purge_mode != SolidPurgeMode::Manual || !self.animation.is_empty() {
+            return;
+        }
+        let rows = self.purgeable_solid_lines();
+        self.remove_bottom_solid_lines(rows);
+        self.purge_amount = None;
+    }
+
+    /// Solid purgeで消去する段数を 全段 → 1 → 2 → … の順に切り替える
+    fn cycle_purge_amount(&mut self) {
+        let available = self.available_purge_lines();
+        self.purge_amount = match self.purge_amount {
+            None if available > 1 => Some(1),
+            Some(amount) if amount + 1 < available => Some(amount + 1),
+            _ => None,
+        };
+    }
+
+    /// CHAIN-BONUSを消費して底のSolidラインを最大`solid_lines`段消去し、消去した段数を返す
+    fn remove_bottom_solid_lines(&mut self, solid_lines: usize) -> usize {
+        if solid_lines == 0 {
+            return 0;
+        }
 
         let removable = self
             .custom_score_system
             .consume_chain_bonus(solid_lines as u32) as usize;
 
         if removable == 0 {
-            return;
+            return 0;
         }
 
         for _ in 0..removable {
@@ -317,88 +596,96 @@ impl GameState {
         for _ in 0..removable {
             self.board.insert(0, vec![Cell::Empty; BOARD_WIDTH]);
         }
+        // 盤面全体が下にずれるので、操作中のピースも同じだけ下げて相対位置を保つ。
+        // ヒントの配置はずれる前の盤面のものなので消す
+        if let Some(piece) = &self.current_piece {
+            self.current_piece = Some(piece.moved(0, removable as i8));
+        }
+        self.hint = None;
+        self.stats.solid_rows_purged += removable as u32;
+        self.events.push(GameEvent::SolidRowsPurged(removable));
+        self.events
+            .push(GameEvent::ChainBonus(self.custom_score_system.chain_bonus));
 
         self.current_board_height = (self.current_board_height + removable).min(BOARD_HEIGHT);
+        removable
     }
 
     fn handle_input(&mut self, input: GameInput) {
-        if self.current_piece.is_none() {
-            return;
-        }
-        let mut piece = self.current_piece.clone().unwrap();
-
+        // 表示の切り替えは、ピースの出現待ちの間も受け付ける
         match input {
-            GameInput::MoveLeft => piece = piece.moved(-1, 0),
-            GameInput::MoveRight => piece = piece.moved(1, 0),
-            GameInput::HardDrop => {
-                // Hard Drop: 即座に着地
-                while self.is_valid_position(&piece.moved(0, 1)) {
-                    piece = piece.moved(0, 1);
-                }
+            GameInput::ToggleHalfBlock => {
+                self.board_style = match self.board_style {
+                    BoardStyle::Blocks => BoardStyle::HalfBlock,
+                    BoardStyle::HalfBlock | BoardStyle::Counts => BoardStyle::Blocks,
+                };
+                return;
             }
-            GameInput::RotateClockwise => {
-                // Clockwise Rotation
-                piece = piece.rotated();
+            GameInput::ToggleCounts => {
+                self.show_counts = !self.show_counts;
+                return;
             }
-            GameInput::RotateCounterClockwise => {
-                // Counter-Clockwise Rotation
-                piece = piece.rotated_counter_clockwise();
+            GameInput::ToggleGroups => {
+                self.show_groups = !self.show_groups;
+                return;
             }
-            GameInput::SoftDrop => {
-                // Soft Drop
-                piece = piece.moved(0, 1);
+            GameInput::ToggleStats => {
+                self.show_stats = !self.show_stats;
+                return;
             }
-            _ => return, // その他の入力は無視
+            _ => {}
         }
+        let Some(piece) = &self.current_piece else {
+            return;
+        };
 
-        if self.is_valid_position(&piece) {
-            self.current_piece = Some(piece);
-        }
-    }
-}
-
-/// 盤面から揃っているラインを1本だけ検出（下から上に探索）
-fn find_first_complete_line(board: &Vec<Vec<Cell>>, current_board_height: usize) -> Option<usize> {
-    // 下から上に探索（Push Downで下から処理するため）
-    for y in (0..current_board_height).rev() {
-        let is_complete = board[y].iter().all(|&cell| {
-            matches!(cell, Cell::Occupied(_)) || matches!(cell, Cell::Connected { color: _, count: _ })
-        });
-        if is_complete {
-            return Some(y);
+        match input {
+            GameInput::SolidPurge => self.purge_solid_lines(),
+            GameInput::CyclePurgeAmount => self.cycle_purge_amount(),
+            GameInput::Hint => self.request_hint(),
+            // 移動・回転（回転はSRSの壁蹴りつき）。動かせない入力は無視
+            _ => {
+                if let Some(moved) =
+                    placement::apply_input(&self.board, self.current_board_height, piece, input)
+                {
+                    self.current_piece = Some(moved);
+                    self.record_piece_event(false);
+                }
+            }
         }
     }
-    None
 }
 
-/// 次の揃ったラインを検出してLineBlink アニメーションを開始
+/// 揃ったラインをすべて検出してLineBlink アニメーションを開始
 fn check_and_start_next_line_animation(state: &mut GameState, current_time: Duration) {
-    if let Some(line_y) = find_first_complete_line(&state.board, state.current_board_height) {
-        // 揃ったラインが1本見つかった
-        // 隣接ブロック接続処理
-        board_logic::find_and_connect_adjacent_blocks(&mut state.board, &[line_y]);
-        
+    let lines = animation::find_complete_lines(&state.board, state.current_board_height);
+    if !lines.is_empty() {
+        // 隣接ブロック接続処理（消去ラインは除外）
+        board_logic::find_and_connect_adjacent_blocks(&mut state.board, &lines);
+
         state.update_connected_block_counts();
         state.update_max_chains();
-        
-        // 盤面全体の連結グループを評価し、CHAIN-BONUS獲得量を更新
-        let total_chain_bonus = board_logic::calculate_chain_bonus(&state.board);
-        state.custom_score_system
-            .set_chain_bonus_from_total(total_chain_bonus);
-        
-        // スコア計算
-        let scores = animation::calculate_line_clear_score(
-            &state.board,
-            line_y,
-            &state.custom_score_system.max_chains,
-        );
-        for (_, points) in scores {
-            state.custom_score_system.add_score(points);
+        state.update_chain_bonus();
+
+        // スコア計算（ラインごとに加算）
+        for &line_y in &lines {
+            let scores = animation::calculate_line_clear_score(
+                &state.board,
+                line_y,
+                &state.custom_score_system.max_chains,
+            );
+            let line_points = scores.iter().map(|(_, points)| points).sum();
+            state.custom_score_system.add_line_clear_points(line_points);
         }
-        
-        // LineBlink アニメーション開始
+
+        // 消去ライン数を数え、Marathonではレベルに応じて落下速度を更新
+        state.lines_cleared += lines.len() as u32;
+        state.events.push(GameEvent::LinesCleared(lines.len()));
+        state.fall_speed = state.play_mode.fall_speed(state.lines_cleared);
+
+        // 揃ったすべてのラインで1つのLineBlink アニメーションを開始
         state.animation.push(Animation::LineBlink {
-            lines: vec![line_y],
+            lines,
             count: 0,
             start_time: current_time,
         });
@@ -407,11 +694,16 @@ fn check_and_start_next_line_animation(state: &mut GameState, current_time: Dura
         board_logic::find_and_connect_adjacent_blocks(&mut state.board, &[]);
         state.update_connected_block_counts();
         state.update_max_chains();
-        
-        let total_chain_bonus = board_logic::calculate_chain_bonus(&state.board);
-        state.custom_score_system
-            .set_chain_bonus_from_total(total_chain_bonus);
-        
+        state.update_chain_bonus();
+
+        // ロックに伴うライン消去が終わったのでコンボ・B2Bボーナスを確定
+        state.custom_score_system.finish_lock();
+
+        // パズルのクリア条件を満たしていれば終了
+        if state.check_puzzle_goal() {
+            return;
+        }
+
         // 揃ったラインがなければ新ピース生成
         state.spawn_piece();
     }
@@ -426,71 +718,59 @@ fn handle_animation(state: &mut GameState, time_provider: &dyn TimeProvider) {
     let current_time = time_provider.now();
     let result = update_animations(&mut state.animation, current_time);
 
-    // Handle completed line clears (段階的処理：1本ずつ)
+    // Handle completed line clears（揃ったラインをまとめて処理）
     for completed_lines in result.completed_line_blinks.clone() {
-        // 段階的検出では1本ずつ処理されるので、completed_linesには1本しか入っていない
-        assert_eq!(completed_lines.len(), 1, "段階的検出では1本ずつ処理する");
-        let line_y = completed_lines[0];
-        
-        // Bottom line かどうかを判定
-        let is_bottom_line = line_y == state.current_board_height - 1;
-
-        if is_bottom_line {
-            // Handle bottom line (standard Tetris clear)
-            state.board.remove(line_y);
-            state.board.insert(0, vec![Cell::Empty; BOARD_WIDTH]);
-
-            // Update connected block counts after bottom line clear
-            state.update_all_connected_block_counts();
-            
-            // Bottom line消去後、次のラインを検出
-            check_and_start_next_line_animation(state, current_time);
-        } else {
-            // Handle non-bottom line (custom clear with Solid conversion)
-            // Remove isolated blocks
-            board_logic::remove_isolated_blocks(&mut state.board, line_y);
+        let (bottom_lines, solid_lines, isolated_removed) = animation::process_line_clear(
+            &mut state.board,
+            state.current_board_height,
+            &completed_lines,
+            state.gravity_mode,
+        );
+        state.stats.bottom_lines += bottom_lines.len() as u32;
+        state.stats.solid_lines += solid_lines.len() as u32;
+        state.stats.isolated_blocks_removed += isolated_removed as u32;
 
-            // Turn line to Solid
-            for x in 0..BOARD_WIDTH {
-                state.board[line_y][x] = Cell::Solid;
-            }
+        // Update connected block counts after line clear
+        state.update_all_connected_block_counts();
 
-            // Update connected blocks after line clear
-            state.update_all_connected_block_counts();
-            state.consume_chain_bonus_for_solid_lines();
-            
-            // Trigger push-down animation
-            state.animation.push(Animation::PushDown {
-                solid_line_y: line_y,
+        if solid_lines.is_empty() && state.gravity_mode == GravityMode::Cascade {
+            // Cascade重力: 空いた行へ向かってブロックを1段ずつ落とす
+            state.animation.push(Animation::GravityFall {
                 start_time: current_time,
             });
+        } else if solid_lines.is_empty() {
+            // Bottom lineのみ消去した場合は、次のラインを検出
+            check_and_start_next_line_animation(state, current_time);
+        } else {
+            state.events.push(GameEvent::SolidRows(solid_lines.len()));
+            state.consume_chain_bonus_for_solid_lines();
+
+            // Solid化したすべてのラインで並行してpush-downアニメーションを開始
+            for solid_line_y in solid_lines {
+                state.animation.push(Animation::PushDown {
+                    solid_line_y,
+                    start_time: current_time,
+                });
+            }
         }
     }
 
     // Set continuing animations
     state.animation.extend(result.continuing_animations);
 
-    // Handle completed push downs
-    for solid_line_y in result.completed_push_downs {
-        // Process push down step
-        match process_push_down_step(
+    // Handle completed push downs（同時に下がっているラインをまとめて1ステップ進める）
+    if !result.completed_push_downs.is_empty() {
+        let step_results = process_push_down_steps(
             &mut state.board,
             &mut state.current_board_height,
-            solid_line_y,
-        ) {
-            PushDownStepResult::Completed => {
-                // Push down completed - update connected blocks as board structure changed
-                state.update_all_connected_block_counts();
-
-                // Push Down完了後、次のラインを検出
-                if state.animation.is_empty() {
-                    check_and_start_next_line_animation(state, current_time);
-                }
-            }
-            PushDownStepResult::Moved { new_solid_line_y } => {
-                // Board structure changed - update connected blocks
-                state.update_all_connected_block_counts();
+            &result.completed_push_downs,
+        );
+
+        // Board structure changed - update connected blocks
+        state.update_all_connected_block_counts();
 
+        for step_result in step_results {
+            if let PushDownStepResult::Moved { new_solid_line_y } = step_result {
                 // Continue push down animation at new position
                 state.animation.push(Animation::PushDown {
                     solid_line_y: new_solid_line_y,
@@ -498,50 +778,261 @@ fn handle_animation(state: &mut GameState, time_provider: &dyn TimeProvider) {
                 });
             }
         }
+
+        // すべてのPush Down完了後、次のラインを検出
+        if state.animation.is_empty() {
+            check_and_start_next_line_animation(state, current_time);
+        }
+    }
+
+    // Handle completed gravity falls（支えのない塊を1段落とす）
+    if result.completed_gravity_falls > 0 {
+        let moved = gravity::step_gravity(&mut state.board, state.current_board_height);
+        state.update_all_connected_block_counts();
+
+        if moved {
+            state.animation.push(Animation::GravityFall {
+                start_time: current_time,
+            });
+        } else {
+            // 落下が止まったら、新しい連結や揃ったラインを検出
+            check_and_start_next_line_animation(state, current_time);
+        }
     }
 }
 
-fn main() -> io::Result<()> {
-    let mut renderer = render::CrosstermRenderer::new();
-    execute!(renderer.stdout, EnterAlternateScreen, Hide)?;
-    execute!(
-        renderer.stdout,
-        PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
-    )?;
+/// 落下の1ステップ: ピースを1段下げ、下げられなければロックする（ピースがなければ出現させる）
+fn fall_one_step(
+    state: &mut GameState,
+    practice_history: &mut UndoHistory<GameState>,
+    time_provider: &dyn TimeProvider,
+) {
+    if let Some(piece) = &state.current_piece {
+        let moved_down = piece.moved(0, 1);
+        if state.is_valid_position(&moved_down) {
+            state.current_piece = Some(moved_down);
+        } else {
+            // 練習モードではロック直前の状態を巻き戻し用に記録
+            if state.play_mode == PlayMode::Practice {
+                practice_history.record(state.practice_snapshot());
+            }
+            state.lock_piece(time_provider);
+        }
+    } else {
+        state.spawn_piece();
+    }
+}
+
+/// クリア済みパズルの記録ファイル（ホームディレクトリ直下）
+fn puzzle_progress_path() -> Option<std::path::PathBuf> {
+    std::env::var_os("HOME")
+        .map(|home| std::path::PathBuf::from(home).join(".thud_and_tile_puzzles"))
+}
+
+fn load_puzzle_progress(path: Option<&std::path::Path>) -> PuzzleProgress {
+    path.and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|text| PuzzleProgress::from_text(&text))
+        .unwrap_or_default()
+}
+
+/// `--puzzles`で指定したパズルパックを読み込む
+fn load_puzzle_pack(path: &std::path::Path) -> Result<Vec<Puzzle>, String> {
+    let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    puzzle::parse_pack(&text)
+}
+
+/// クリア済みパズルの記録を保存する（失敗してもゲームは続け、呼び出し元がメッセージを伝える）
+fn save_puzzle_progress(
+    path: Option<&std::path::Path>,
+    progress: &PuzzleProgress,
+) -> Result<(), String> {
+    let Some(path) = path else {
+        return Ok(());
+    };
+    std::fs::write(path, progress.to_text())
+        .map_err(|err| format!("cannot save puzzle progress to {}: {}", path.display(), err))
+}
+
+/// `--report`: 統計レポートを1行のJSONとしてファイルの末尾に追記する（ゲームごとに1行）
+///
+/// 失敗してもゲームは続け、呼び出し元がメッセージを伝える
+fn save_report(path: Option<&std::path::Path>, state: &GameState) -> Result<(), String> {
+    use std::io::Write;
+
+    let Some(path) = path else {
+        return Ok(());
+    };
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", stats::report(state)))
+        .map_err(|err| format!("cannot save report to {}: {}", path.display(), err))
+}
+
+/// `--bot`: 画面を使わずにボットと対戦し、結果を標準出力に表示する
+fn run_bot(options: &GameOptions, command: &str) -> io::Result<()> {
+    let rules = SimulationRules {
+        gravity_mode: options.gravity_mode,
+        solid_purge_mode: options.solid_purge_mode,
+    };
+    let mut game = bot::BotGame::new(
+        rules,
+        options.special_block_rates,
+        PieceGenerator::from_entropy(),
+    );
+    let result = bot::BotChannel::spawn(command, options.bot_timeout)
+        .and_then(|mut channel| bot::run_bot_game(&mut channel, &mut game, options.bot_pieces));
+    match result {
+        Ok(name) => {
+            println!("bot: {}", name);
+            println!("{}", game.summary());
+            Ok(())
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            eprintln!("{}", game.summary());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `--narrate`: 画面を描かずに、出来事や読み上げコマンドの結果を1行ずつ標準出力に書いて遊ぶ
+fn run_narrated(options: &mut GameOptions, puzzles: &[Puzzle]) -> io::Result<()> {
+    // キーを1つずつ読むためにrawモードにする（代替画面やカーソル移動は使わない）
     terminal::enable_raw_mode()?;
+    let mut narrator = narration::Narrator::new(io::stdout());
+    let mut input_provider = CrosstermInputProvider::new();
+    let result = narrator
+        .say(narration::HELP)
+        .and_then(|()| run_game(options, puzzles, &mut input_provider, &mut narrator));
+    terminal::disable_raw_mode()?;
+    result
+}
 
+/// タイトルからゲームオーバーまでを進めるゲームループ（画面に描くか読み上げるかは`output`で決まる）
+fn run_game(
+    options: &mut GameOptions,
+    puzzles: &[Puzzle],
+    input_provider: &mut impl InputProvider,
+    output: &mut impl GameOutput,
+) -> io::Result<()> {
     let time_provider = SystemTimeProvider::new();
-    let mut input_provider = CrosstermInputProvider::new();
     let scheduler = create_default_scheduler();
-    let mut state = GameState::new();
-    let mut prev_state = state.clone();
+    let mut state = GameState::with_options(options);
+    let mut prev_mode = state.mode;
     let mut last_fall = time_provider.now();
 
-    render::draw_title_screen(&mut renderer)?;
+    let mut selected_mode = 0;
+    output.show_title(selected_mode)?;
+
+    let progress_path = puzzle_progress_path();
+    let mut puzzle_progress = load_puzzle_progress(progress_path.as_deref());
+    let mut selected_puzzle = 0;
+    let mut practice_history = UndoHistory::new(PRACTICE_HISTORY_LIMIT);
 
     loop {
-        if state.mode != GameMode::Title {
-            render::draw(&mut renderer, &prev_state, &state)?;
+        output.show_state(&mut state)?;
+
+        if prev_mode == GameMode::Playing && state.mode == GameMode::GameOver {
+            output.say(&narration::describe_results(&state))?;
+            if let Err(message) = save_report(options.report.as_deref(), &state) {
+                output.warn(message)?;
+            }
+            // パズルをクリアしたら記録して保存
+            if let (Some(ModeOutcome::GoalReached), Some(puzzle)) = (state.outcome, &state.puzzle) {
+                if puzzle_progress.mark_completed(&puzzle.name) {
+                    if let Err(message) =
+                        save_puzzle_progress(progress_path.as_deref(), &puzzle_progress)
+                    {
+                        output.warn(message)?;
+                    }
+                }
+            }
         }
-        prev_state = state.clone();
+        prev_mode = state.mode;
 
         match state.mode {
             GameMode::Title => {
-                if input_provider.poll_input(100)? {
-                    if let Some(input) = input_provider.read_input()? {
-                        match input {
-                            GameInput::Restart => {
-                                state = GameState::new();
-                                state.mode = GameMode::Playing;
-                                state.spawn_piece();
-                            }
-                            GameInput::Quit => break,
-                            _ => {}
+                if !input_provider.poll_input(100)? {
+                    continue;
+                }
+                match input_provider.read_input()? {
+                    Some(GameInput::Restart) => {
+                        state = GameState::with_options(options);
+                        practice_history = UndoHistory::new(PRACTICE_HISTORY_LIMIT);
+                        let play_mode = play_mode::SELECTABLE_MODES[selected_mode];
+                        if play_mode == PlayMode::Puzzle {
+                            state.mode = GameMode::PuzzleSelect;
+                            output.show_puzzle_select(
+                                puzzles,
+                                selected_puzzle,
+                                &puzzle_progress,
+                            )?;
+                        } else {
+                            state.start_selected_mode(
+                                play_mode,
+                                options.start_board.as_ref(),
+                                time_provider.now(),
+                            );
+                            last_fall = time_provider.now();
                         }
                     }
+                    // 上下キーでモードを選択
+                    Some(
+                        input @ (GameInput::RotateCounterClockwise | GameInput::RotateClockwise),
+                    ) => {
+                        let count = play_mode::SELECTABLE_MODES.len();
+                        selected_mode = if input == GameInput::RotateClockwise {
+                            (selected_mode + 1) % count
+                        } else {
+                            (selected_mode + count - 1) % count
+                        };
+                        output.show_title(selected_mode)?;
+                    }
+                    Some(GameInput::Resize(width, height)) if output.resize(width, height) => {
+                        output.show_title(selected_mode)?;
+                    }
+                    Some(GameInput::Quit) => break,
+                    _ => {}
+                }
+            }
+            GameMode::PuzzleSelect => {
+                if !input_provider.poll_input(100)? {
+                    continue;
+                }
+                match input_provider.read_input()? {
+                    Some(GameInput::Restart) => {
+                        state.start_puzzle(&puzzles[selected_puzzle], time_provider.now());
+                        last_fall = time_provider.now();
+                    }
+                    Some(
+                        input @ (GameInput::RotateCounterClockwise | GameInput::RotateClockwise),
+                    ) => {
+                        selected_puzzle = if input == GameInput::RotateClockwise {
+                            (selected_puzzle + 1) % puzzles.len()
+                        } else {
+                            (selected_puzzle + puzzles.len() - 1) % puzzles.len()
+                        };
+                        output.show_puzzle_select(puzzles, selected_puzzle, &puzzle_progress)?;
+                    }
+                    Some(GameInput::Resize(width, height)) if output.resize(width, height) => {
+                        output.show_puzzle_select(puzzles, selected_puzzle, &puzzle_progress)?;
+                    }
+                    Some(GameInput::Quit) => {
+                        state.mode = GameMode::Title;
+                        output.show_title(selected_mode)?;
+                    }
+                    _ => {}
                 }
             }
             GameMode::Playing => {
+                // タイマー更新と目標・制限時間の判定
+                state.update_play_time(time_provider.now());
+                if state.mode != GameMode::Playing {
+                    continue;
+                }
+
                 // アニメーション処理
                 if !state.animation.is_empty() {
                     handle_animation(&mut state, &time_provider);
@@ -549,29 +1040,49 @@ fn main() -> io::Result<()> {
                 }
 
                 // 入力処理 (ノンブロッキング)
-                let inputs = input_provider.read_all_pending()?;
-                for input in inputs {
+                for input in input_provider.read_all_pending()? {
                     match input {
                         GameInput::Quit => {
                             state.mode = GameMode::GameOver;
                             break;
                         }
+                        // 次のループで新しい大きさの配置に描き直す
+                        GameInput::Resize(width, height) => {
+                            output.resize(width, height);
+                        }
+                        GameInput::ToggleHalfBlock => {
+                            state.handle_input(input);
+                            // 次のゲームも同じ表示で始める
+                            options.board_style = state.board_style;
+                        }
+                        GameInput::ReadColumn(x) => {
+                            output.say(&narration::describe_column(&state, x as usize))?
+                        }
+                        GameInput::ReadBoard => {
+                            for line in narration::describe_board(&state) {
+                                output.say(&line)?;
+                            }
+                        }
+                        GameInput::ReadStatus => output.say(&narration::describe_status(&state))?,
+                        GameInput::Undo | GameInput::Redo
+                            if state.play_mode == PlayMode::Practice =>
+                        {
+                            let redo = input == GameInput::Redo;
+                            if state.step_practice_history(&mut practice_history, redo) {
+                                last_fall = time_provider.now();
+                                output.say(if redo { "Redone" } else { "Undone" })?;
+                                state.record_piece_event(true);
+                            }
+                        }
                         _ => state.handle_input(input),
                     }
                 }
 
                 // 落下処理
-                if time_provider.now() - last_fall >= state.fall_speed {
-                    if let Some(piece) = &state.current_piece {
-                        let moved_down = piece.moved(0, 1);
-                        if state.is_valid_position(&moved_down) {
-                            state.current_piece = Some(moved_down);
-                        } else {
-                            state.lock_piece(&time_provider);
-                        }
-                    } else {
-                        state.spawn_piece();
-                    }
+                if state.mode == GameMode::Playing
+                    && time_provider.now() - last_fall >= state.fall_speed
+                {
+                    fall_one_step(&mut state, &mut practice_history, &time_provider);
                     last_fall = time_provider.now();
                 }
 
@@ -579,24 +1090,118 @@ fn main() -> io::Result<()> {
                 scheduler.wait_for_next_frame();
             }
             GameMode::GameOver => {
-                if input_provider.poll_input(50)? {
-                    if let Some(input) = input_provider.read_input()? {
-                        match input {
-                            GameInput::Quit => break,
-                            GameInput::Restart => {
-                                state = GameState::new();
-                                render::draw_title_screen(&mut renderer)?;
-                            }
-                            _ => {}
-                        }
+                if !input_provider.poll_input(50)? {
+                    continue;
+                }
+                match input_provider.read_input()? {
+                    Some(GameInput::Quit) => break,
+                    Some(GameInput::Resize(width, height)) => {
+                        output.resize(width, height);
+                    }
+                    Some(input @ GameInput::ToggleHalfBlock) => {
+                        state.handle_input(input);
+                        options.board_style = state.board_style;
+                    }
+                    Some(
+                        input @ (GameInput::ToggleCounts
+                        | GameInput::ToggleGroups
+                        | GameInput::ToggleStats),
+                    ) => state.handle_input(input),
+                    // 練習モードではゲームオーバーからも巻き戻してプレイを続けられる
+                    Some(GameInput::Undo)
+                        if state.play_mode == PlayMode::Practice
+                            && state.step_practice_history(&mut practice_history, false) =>
+                    {
+                        last_fall = time_provider.now();
+                        output.say("Undone")?;
+                        state.record_piece_event(true);
+                    }
+                    Some(GameInput::Restart) if state.play_mode == PlayMode::Puzzle => {
+                        state = GameState::with_options(options);
+                        state.mode = GameMode::PuzzleSelect;
+                        output.show_puzzle_select(puzzles, selected_puzzle, &puzzle_progress)?;
                     }
+                    Some(GameInput::Restart) => {
+                        state = GameState::with_options(options);
+                        output.show_title(selected_mode)?;
+                    }
+                    _ => {}
                 }
             }
         }
     }
-    execute!(renderer.stdout, PopKeyboardEnhancementFlags)?;
-    execute!(renderer.stdout, Show, LeaveAlternateScreen, ResetColor)?;
-    terminal::disable_raw_mode()
+    Ok(())
+}
+
+fn main() -> io::Result<()> {
+    let mut options = match GameOptions::from_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+    if options.echo_bot {
+        return bot::run_echo_bot(io::stdin().lock(), io::stdout().lock());
+    }
+    if let Some(command) = &options.bot_command {
+        return run_bot(&options, command);
+    }
+    let puzzles = match &options.puzzle_pack {
+        Some(path) => match load_puzzle_pack(path) {
+            Ok(puzzles) => puzzles,
+            Err(message) => {
+                eprintln!("{}: {}", path.display(), message);
+                std::process::exit(2);
+            }
+        },
+        None => puzzle::builtin_puzzles(),
+    };
+    if options.narrate {
+        return run_narrated(&mut options, &puzzles);
+    }
+    let mut theme = match options.theme.as_deref().map(Theme::load) {
+        Some(Ok(theme)) => theme,
+        Some(Err(message)) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+        None => theme::CLASSIC,
+    };
+    theme.glyphs |= options.glyphs;
+    theme.charset = options.charset.unwrap_or_else(theme::Charset::from_locale);
+
+    let mut output = ScreenOutput {
+        renderer: render::CrosstermRenderer::new(theme),
+        save_errors: Vec::new(),
+    };
+    execute!(output.renderer.stdout, EnterAlternateScreen, Hide)?;
+    execute!(
+        output.renderer.stdout,
+        PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+    )?;
+    terminal::enable_raw_mode()?;
+
+    let result = run_game(
+        &mut options,
+        &puzzles,
+        &mut CrosstermInputProvider::new(),
+        &mut output,
+    );
+
+    execute!(output.renderer.stdout, PopKeyboardEnhancementFlags)?;
+    execute!(
+        output.renderer.stdout,
+        Show,
+        LeaveAlternateScreen,
+        ResetColor
+    )?;
+    terminal::disable_raw_mode()?;
+    // 保存に失敗したときのメッセージは代替画面を抜けてから表示する
+    for message in &output.save_errors {
+        eprintln!("{}", message);
+    }
+    result
 }
 
 #[cfg(test)]
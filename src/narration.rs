@@ -0,0 +1,337 @@
+//! 読み上げモード（CLI版, `--narrate`）
+//!
+//! 画面を描く代わりに、ゲームの出来事を1行ずつの英文で標準出力に書きます。
+//! カーソル移動を使わないので、スクリーンリーダーがそのまま読み上げられます。
+
+use std::io::{self, Write};
+
+use crate::cell::Cell;
+use crate::config::{BOARD_HEIGHT, BOARD_WIDTH};
+use crate::game_color::GameColor;
+use crate::play_mode::{self, ModeOutcome, PlayMode, SELECTABLE_MODES};
+use crate::puzzle::{Puzzle, PuzzleProgress};
+use crate::tetromino::Tetromino;
+use crate::GameState;
+
+/// ゲーム中の出来事（`GameState`が記録し、読み上げモードが取り出して読み上げる）
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    /// ピースが出現した（`drop`は着地までの段数）
+    PieceSpawned { piece: Tetromino, drop: usize },
+    /// プレイヤーの操作でピースが動いた・回った
+    PieceMoved { piece: Tetromino, drop: usize },
+    /// ラインが揃った
+    LinesCleared(usize),
+    /// 消えたラインがSolidラインになった
+    SolidRows(usize),
+    /// CHAIN-BONUSを消費して底のSolidラインを消した
+    SolidRowsPurged(usize),
+    /// CHAIN-BONUSの残量が変わった
+    ChainBonus(u32),
+    /// MAX-CHAINの記録を更新した
+    MaxChain { color: GameColor, count: u32 },
+    /// サバイバルモードで下から行がせり上がった
+    RowRose,
+}
+
+/// まだ読み上げていない出来事。読み上げが済めば捨てるものなので、状態の比較には含めない
+#[derive(Clone, Debug, Default)]
+pub struct EventQueue(Vec<GameEvent>);
+
+impl EventQueue {
+    pub fn push(&mut self, event: GameEvent) {
+        self.0.push(event);
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub fn drain(&mut self) -> impl Iterator<Item = GameEvent> + '_ {
+        self.0.drain(..)
+    }
+}
+
+impl PartialEq for EventQueue {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// 起動時の操作説明
+pub const HELP: &str = "Narrated mode. Left and Right move, Up and Down rotate, Space soft drops, \
+Shift+Down hard drops. Keys 1 to 9 and 0 read a column height, B reads the board, \
+S reads the score. Q quits.";
+
+/// 1行ずつ書き出す（rawモードでも行頭に戻るよう`\r\n`で区切る）
+pub struct Narrator<W: Write> {
+    out: W,
+}
+
+impl<W: Write> Narrator<W> {
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+
+    pub fn say(&mut self, line: &str) -> io::Result<()> {
+        write!(self.out, "{}\r\n", line)?;
+        self.out.flush()
+    }
+}
+
+fn color_name(color: GameColor) -> String {
+    format!("{:?}", color).to_lowercase()
+}
+
+/// マスの読み方（空きは`empty`、連結ブロックは連結数を添える）
+fn cell_name(cell: Cell) -> String {
+    match cell {
+        Cell::Empty => "empty".to_string(),
+        Cell::Occupied(color) => color_name(color),
+        Cell::Connected { color, count } => format!("{} {}", color_name(color), count),
+        Cell::Solid => "solid".to_string(),
+        Cell::Wildcard => "wildcard".to_string(),
+        Cell::Bomb => "bomb".to_string(),
+        Cell::Garbage => "garbage".to_string(),
+    }
+}
+
+/// ピースの形と、左の列から順に各列のブロック（上から下へ）
+/// 例: `T at columns 4 to 6: 4 cyan; 5 magenta over yellow; 6 cyan`
+fn describe_piece(piece: &Tetromino) -> String {
+    let mut columns: Vec<(i8, Vec<(i8, Cell)>)> = Vec::new();
+    for ((x, y), cell) in piece.iter_cells() {
+        match columns.iter_mut().find(|(column, _)| *column == x) {
+            Some((_, cells)) => cells.push((y, cell)),
+            None => columns.push((x, vec![(y, cell)])),
+        }
+    }
+    columns.sort_by_key(|(column, _)| *column);
+    let blocks: Vec<String> = columns
+        .iter_mut()
+        .map(|(column, cells)| {
+            cells.sort_by_key(|(y, _)| *y);
+            let names: Vec<String> = cells.iter().map(|(_, cell)| cell_name(*cell)).collect();
+            format!("{} {}", *column + 1, names.join(" over "))
+        })
+        .collect();
+    let (left, right) = (columns[0].0 + 1, columns[columns.len() - 1].0 + 1);
+    format!(
+        "{:?} at columns {} to {}: {}",
+        piece.shape,
+        left,
+        right,
+        blocks.join("; ")
+    )
+}
+
+fn describe_drop(drop: usize) -> String {
+    match drop {
+        0 => "landed".to_string(),
+        1 => "1 row above landing".to_string(),
+        rows => format!("{} rows above landing", rows),
+    }
+}
+
+fn plural(count: usize, one: &str, many: &str) -> String {
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+/// 出来事の読み上げ文
+pub fn describe(event: &GameEvent) -> String {
+    match event {
+        GameEvent::PieceSpawned { piece, drop } => {
+            format!("New {}, {}", describe_piece(piece), describe_drop(*drop))
+        }
+        GameEvent::PieceMoved { piece, drop } => {
+            format!("{}, {}", describe_piece(piece), describe_drop(*drop))
+        }
+        GameEvent::LinesCleared(lines) => format!("{} cleared", plural(*lines, "line", "lines")),
+        GameEvent::SolidRows(rows) => {
+            format!("{} turned Solid", plural(*rows, "row", "rows"))
+        }
+        GameEvent::SolidRowsPurged(rows) => {
+            format!("{} purged", plural(*rows, "Solid row", "Solid rows"))
+        }
+        GameEvent::ChainBonus(bonus) => format!("Chain bonus {}", bonus),
+        GameEvent::MaxChain { color, count } => {
+            format!("New max chain: {} {}", color_name(*color), count)
+        }
+        GameEvent::RowRose => "A row rose from the bottom".to_string(),
+    }
+}
+
+/// 列（0始まり）の高さと一番上のマス。段は盤面の底を1として数える
+pub fn describe_column(state: &GameState, x: usize) -> String {
+    let top = (0..BOARD_HEIGHT).find(|&y| state.board[y][x] != Cell::Empty);
+    match top {
+        Some(y) => format!(
+            "Column {}: height {}, top {}",
+            x + 1,
+            BOARD_HEIGHT - y,
+            cell_name(state.board[y][x])
+        ),
+        None => format!("Column {}: empty", x + 1),
+    }
+}
+
+/// 盤面を上の段から1行ずつ。同じマスが続くところは`empty x3`のようにまとめ、
+/// ブロックのない段は読まない
+pub fn describe_board(state: &GameState) -> Vec<String> {
+    let mut lines = Vec::new();
+    for y in 0..BOARD_HEIGHT {
+        let row = &state.board[y];
+        if row.iter().all(|cell| *cell == Cell::Empty) {
+            continue;
+        }
+        let mut runs: Vec<(String, usize)> = Vec::new();
+        for cell in row.iter().take(BOARD_WIDTH) {
+            let name = cell_name(*cell);
+            match runs.last_mut() {
+                Some((last, count)) if *last == name => *count += 1,
+                _ => runs.push((name, 1)),
+            }
+        }
+        let cells: Vec<String> = runs
+            .into_iter()
+            .map(|(name, count)| match count {
+                1 => name,
+                _ => format!("{} x{}", name, count),
+            })
+            .collect();
+        lines.push(format!("Row {}: {}", BOARD_HEIGHT - y, cells.join(", ")));
+    }
+    if lines.is_empty() {
+        lines.push("Board is empty".to_string());
+    }
+    lines
+}
+
+/// スコア・ライン数・CHAIN-BONUS・MAX-CHAIN・次のピース
+pub fn describe_status(state: &GameState) -> String {
+    let score = &state.custom_score_system;
+    let chains = &score.max_chains;
+    let next = match &state.next_piece {
+        Some(piece) => format!("{:?}", piece.shape),
+        None => "none".to_string(),
+    };
+    format!(
+        "Score {}, lines {}, chain bonus {}, max chain cyan {} magenta {} yellow {}, next {}",
+        score.score.total(),
+        state.lines_cleared,
+        score.chain_bonus,
+        chains.cyan,
+        chains.magenta,
+        chains.yellow,
+        next
+    )
+}
+
+/// タイトル画面で選んでいるモード
+pub fn describe_title(selected_mode: usize) -> String {
+    format!(
+        "Mode {}, {} of {}. Up and Down choose, Enter starts",
+        SELECTABLE_MODES[selected_mode].name(),
+        selected_mode + 1,
+        SELECTABLE_MODES.len()
+    )
+}
+
+/// パズル選択画面で選んでいるパズル
+pub fn describe_puzzle(puzzles: &[Puzzle], selected: usize, progress: &PuzzleProgress) -> String {
+    let puzzle = &puzzles[selected];
+    let cleared = if progress.is_completed(&puzzle.name) {
+        ", cleared"
+    } else {
+        ""
+    };
+    format!(
+        "Puzzle {}{}, {} of {}, goal {}. Enter starts, Q goes back",
+        puzzle.name,
+        cleared,
+        selected + 1,
+        puzzles.len(),
+        puzzle.goal.describe()
+    )
+}
+
+/// ゲーム終了時の結果
+pub fn describe_results(state: &GameState) -> String {
+    let headline = match state.outcome {
+        Some(ModeOutcome::GoalReached) => "Complete",
+        Some(ModeOutcome::TimeUp) => "Time up",
+        Some(ModeOutcome::OutOfPieces) => "Failed",
+        Some(ModeOutcome::ToppedOut) | None => "Game over",
+    };
+    let mode_name = match &state.puzzle {
+        Some(puzzle) => puzzle.name.clone(),
+        None => state.play_mode.name(),
+    };
+    let next = if state.play_mode == PlayMode::Puzzle {
+        "Enter for puzzles"
+    } else {
+        "Enter for title"
+    };
+    format!(
+        "{}. {}, time {}, lines {}, score {}, pieces {}, biggest group {}. {}, Q quits",
+        headline,
+        mode_name,
+        play_mode::format_timer(state.play_time),
+        state.lines_cleared,
+        state.custom_score_system.score.total(),
+        state.stats.pieces_placed,
+        state.stats.biggest_group,
+        next
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tetromino::TetrominoShape;
+
+    #[test]
+    fn test_describe_piece_lists_columns_left_to_right() {
+        let piece = Tetromino::from_shape(
+            TetrominoShape::O,
+            [
+                GameColor::Cyan,
+                GameColor::Magenta,
+                GameColor::Yellow,
+                GameColor::Cyan,
+            ],
+        );
+        let text = describe(&GameEvent::PieceMoved { piece, drop: 3 });
+        assert!(text.starts_with("O at columns "), "{}", text);
+        assert!(text.contains(" over "), "{}", text);
+        assert!(text.ends_with(", 3 rows above landing"), "{}", text);
+    }
+
+    #[test]
+    fn test_describe_board_reads_rows_from_the_top() {
+        let mut state = GameState::new();
+        state.board[BOARD_HEIGHT - 1] = vec![Cell::Solid; BOARD_WIDTH];
+        state.board[BOARD_HEIGHT - 2][0] = Cell::Connected {
+            color: GameColor::Cyan,
+            count: 2,
+        };
+        state.board[BOARD_HEIGHT - 2][1] = Cell::Connected {
+            color: GameColor::Cyan,
+            count: 2,
+        };
+
+        assert_eq!(
+            describe_board(&state),
+            vec!["Row 2: cyan 2 x2, empty x8", "Row 1: solid x10"]
+        );
+        assert_eq!(describe_column(&state, 0), "Column 1: height 2, top cyan 2");
+        assert_eq!(describe_column(&state, 9), "Column 10: height 1, top solid");
+    }
+
+    #[test]
+    fn test_narrator_ends_lines_for_raw_mode() {
+        let mut out = Vec::new();
+        Narrator::new(&mut out).say("2 lines cleared").unwrap();
+        assert_eq!(out, b"2 lines cleared\r\n");
+    }
+}
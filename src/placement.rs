@@ -0,0 +1,305 @@
+//! 操作中ピースが到達できる最終配置の列挙
+//!
+//! 左右移動・ソフトドロップ・ハードドロップ・SRSの壁蹴りつき回転を組み合わせて、ピースが
+//! 接地できる位置をすべて探します。ハードドロップしても固定されるまでは操作できるので、
+//! オーバーハングの下への滑り込み（タック）や壁蹴りでの回転入れ（スピン）も含みます。
+//! ボット・ヒント・盤面解析で共通に使います。
+
+use std::collections::HashSet;
+
+use crate::cell::{Board, Cell};
+use crate::config::BOARD_WIDTH;
+use crate::game_input::GameInput;
+use crate::tetromino::Tetromino;
+
+/// 探索で試す入力（同じ入力数の経路ではこの順に優先する）
+pub const PLACEMENT_INPUTS: [GameInput; 6] = [
+    GameInput::MoveLeft,
+    GameInput::MoveRight,
+    GameInput::RotateClockwise,
+    GameInput::RotateCounterClockwise,
+    GameInput::SoftDrop,
+    GameInput::HardDrop,
+];
+
+/// ピースが盤面のブロックと重ならず、フィールド内に収まるか
+///
+/// 盤面より上（y < 0）にはみ出すのは許す
+pub fn is_valid_position(board: &Board, current_board_height: usize, piece: &Tetromino) -> bool {
+    piece.iter_blocks().all(|((x, y), _)| {
+        (0..BOARD_WIDTH as i8).contains(&x)
+            && y < current_board_height as i8
+            && (y < 0 || board[y as usize][x as usize] == Cell::Empty)
+    })
+}
+
+/// ピースをそのまま真下に落とした位置
+pub fn drop_to_floor(board: &Board, current_board_height: usize, piece: &Tetromino) -> Tetromino {
+    let mut dropped = piece.clone();
+    while is_valid_position(board, current_board_height, &dropped.moved(0, 1)) {
+        dropped = dropped.moved(0, 1);
+    }
+    dropped
+}
+
+/// 移動・回転の入力を1つ適用したピース（動かせない入力ならNone）
+///
+/// 回転はSRSの壁蹴りつき。ハードドロップは接地するまで落とすだけで、固定はしない
+pub fn apply_input(
+    board: &Board,
+    current_board_height: usize,
+    piece: &Tetromino,
+    input: GameInput,
+) -> Option<Tetromino> {
+    let fits = |candidate: &Tetromino| is_valid_position(board, current_board_height, candidate);
+    let moved = match input {
+        GameInput::MoveLeft => piece.moved(-1, 0),
+        GameInput::MoveRight => piece.moved(1, 0),
+        GameInput::SoftDrop => piece.moved(0, 1),
+        GameInput::HardDrop => drop_to_floor(board, current_board_height, piece),
+        GameInput::RotateClockwise => return piece.rotated_with_kicks(true, fits),
+        GameInput::RotateCounterClockwise => return piece.rotated_with_kicks(false, fits),
+        _ => return None,
+    };
+    fits(&moved).then_some(moved)
+}
+
+/// ピースが到達できる最終配置と、そこまでの最短の入力列
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Placement {
+    /// 接地した位置・向きのピース
+    pub piece: Tetromino,
+    /// 操作開始時の位置からの入力列（この後は落下で固定される）
+    pub inputs: Vec<GameInput>,
+}
+
+impl Placement {
+    /// 固定されるセルの座標（並べ替え済み）
+    pub fn cells(&self) -> [(i8, i8); 4] {
+        piece_cells(&self.piece)
+    }
+}
+
+fn piece_cells(piece: &Tetromino) -> [(i8, i8); 4] {
+    let mut cells = [(0, 0); 4];
+    for (cell, (pos, _)) in cells.iter_mut().zip(piece.iter_blocks()) {
+        *cell = pos;
+    }
+    cells.sort_unstable();
+    cells
+}
+
+/// 探索した状態と、そこへ来た直前の状態・入力
+type SearchNode = (Tetromino, Option<(usize, GameInput)>);
+
+/// 到達できる最終配置をすべて、入力数の少ない順に返す
+///
+/// 位置と向きを状態とした幅優先探索で、最終配置は固定されるセルの集合で重複を除く
+/// （回転対称なピースで同じセルに置ける向きが複数あれば、最短で届くものだけを残す）。
+/// ピースが今の位置に収まっていなければ空
+pub fn enumerate_placements(
+    board: &Board,
+    current_board_height: usize,
+    piece: &Tetromino,
+) -> Vec<Placement> {
+    let nodes = search(board, current_board_height, piece);
+    let mut found = HashSet::new();
+    let mut placements = Vec::new();
+    for (index, (current, _)) in nodes.iter().enumerate() {
+        if is_grounded(board, current_board_height, current) && found.insert(piece_cells(current)) {
+            placements.push(Placement {
+                piece: current.clone(),
+                inputs: input_path(&nodes, index),
+            });
+        }
+    }
+    placements
+}
+
+/// `target`と同じ位置・向きで接地するまでの最短の入力列（届かなければNone）
+///
+/// 回転対称なピースでも向きまで一致する必要がある（向きが違うと色の並びが変わるため）
+pub fn path_to(
+    board: &Board,
+    current_board_height: usize,
+    piece: &Tetromino,
+    target: &Tetromino,
+) -> Option<Vec<GameInput>> {
+    let nodes = search(board, current_board_height, piece);
+    nodes
+        .iter()
+        .position(|(current, _)| {
+            current.pos == target.pos
+                && current.get_rotation_state() == target.get_rotation_state()
+                && is_grounded(board, current_board_height, current)
+        })
+        .map(|index| input_path(&nodes, index))
+}
+
+fn is_grounded(board: &Board, current_board_height: usize, piece: &Tetromino) -> bool {
+    !is_valid_position(board, current_board_height, &piece.moved(0, 1))
+}
+
+/// 位置と向きを状態とした幅優先探索で、到達できる状態を見つけた順に返す
+fn search(board: &Board, current_board_height: usize, piece: &Tetromino) -> Vec<SearchNode> {
+    if !is_valid_position(board, current_board_height, piece) {
+        return Vec::new();
+    }
+
+    let mut nodes: Vec<SearchNode> = vec![(piece.clone(), None)];
+    let mut visited = HashSet::from([(piece.pos, piece.get_rotation_state())]);
+    let mut index = 0;
+    while index < nodes.len() {
+        let current = nodes[index].0.clone();
+        for input in PLACEMENT_INPUTS {
+            let Some(next) = apply_input(board, current_board_height, &current, input) else {
+                continue;
+            };
+            if visited.insert((next.pos, next.get_rotation_state())) {
+                nodes.push((next, Some((index, input))));
+            }
+        }
+        index += 1;
+    }
+    nodes
+}
+
+/// 探索の親をたどって、開始位置からの入力列を組み立てる
+fn input_path(nodes: &[SearchNode], mut index: usize) -> Vec<GameInput> {
+    let mut inputs = Vec::new();
+    while let Some((parent, input)) = nodes[index].1 {
+        inputs.push(input);
+        index = parent;
+    }
+    inputs.reverse();
+    inputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board_notation::parse_board;
+    use crate::config::BOARD_HEIGHT;
+    use crate::game_color::GameColor;
+    use crate::tetromino::TetrominoShape;
+
+    fn piece(shape: TetrominoShape) -> Tetromino {
+        Tetromino::from_shape(
+            shape,
+            [
+                GameColor::Cyan,
+                GameColor::Magenta,
+                GameColor::Yellow,
+                GameColor::Cyan,
+            ],
+        )
+    }
+
+    /// 入力列を最初から適用し直して、最終配置に着くことを確かめる
+    fn replay(board: &Board, height: usize, start: &Tetromino, placement: &Placement) {
+        let mut current = start.clone();
+        for &input in &placement.inputs {
+            current = apply_input(board, height, &current, input)
+                .unwrap_or_else(|| panic!("{:?} failed in {:?}", input, placement.inputs));
+        }
+        assert_eq!(current, placement.piece);
+        assert!(!is_valid_position(board, height, &current.moved(0, 1)));
+    }
+
+    #[test]
+    fn test_empty_board_placement_counts() {
+        let board = vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT];
+        for (shape, expected) in [
+            (TetrominoShape::O, 9),
+            (TetrominoShape::I, 17),
+            (TetrominoShape::T, 34),
+            (TetrominoShape::S, 17),
+        ] {
+            let start = piece(shape);
+            let placements = enumerate_placements(&board, BOARD_HEIGHT, &start);
+            assert_eq!(placements.len(), expected, "{:?}", shape);
+            for placement in &placements {
+                replay(&board, BOARD_HEIGHT, &start, placement);
+            }
+        }
+
+        // 真下に落とすだけの配置は、ハードドロップ1回が最短
+        let start = piece(TetrominoShape::O);
+        let placements = enumerate_placements(&board, BOARD_HEIGHT, &start);
+        assert_eq!(placements[0].inputs, vec![GameInput::HardDrop]);
+    }
+
+    #[test]
+    fn test_tuck_under_overhang() {
+        // 左側の屋根の下（2段目の左3マス）はまっすぐ落としても入らない
+        let (board, height) = parse_board(
+            "\
+YYYYYY....
+..........
+YYYYYYYYY.",
+        )
+        .unwrap();
+        let start = piece(TetrominoShape::I);
+        let placements = enumerate_placements(&board, height, &start);
+        let row = (BOARD_HEIGHT - 2) as i8;
+        let tuck = placements
+            .iter()
+            .find(|placement| placement.cells() == [(0, row), (1, row), (2, row), (3, row)])
+            .expect("I piece should slide under the roof");
+        assert_eq!(tuck.inputs.last(), Some(&GameInput::MoveLeft));
+        replay(&board, height, &start, tuck);
+    }
+
+    #[test]
+    fn test_kick_into_overhang() {
+        // 右の列（x=7）の穴は屋根の下にあり、左の縦穴から回転で入れるしかない
+        let (board, height) = parse_board(
+            "\
+YYYYY..YYY
+YYYYY...YY
+YYYYY...YY
+YYYYYYY.YY",
+        )
+        .unwrap();
+        let start = piece(TetrominoShape::T);
+        let placements = enumerate_placements(&board, height, &start);
+
+        let bottom = (BOARD_HEIGHT - 1) as i8;
+        let slot = [
+            (6, bottom - 1),
+            (7, bottom - 2),
+            (7, bottom - 1),
+            (7, bottom),
+        ];
+        let spin = placements
+            .iter()
+            .find(|placement| placement.cells() == slot)
+            .expect("T piece should kick into the slot");
+        replay(&board, height, &start, spin);
+        assert_eq!(
+            path_to(&board, height, &start, &spin.piece),
+            Some(spin.inputs.clone())
+        );
+        // 宙に浮いた位置は接地していないので届かない扱い
+        assert_eq!(path_to(&board, height, &start, &start), None);
+
+        // 最後の入力は回転で、その場では回れず壁蹴りでずれて入る
+        let (last, approach) = spin.inputs.split_last().unwrap();
+        let mut before = start.clone();
+        for &input in approach {
+            before = apply_input(&board, height, &before, input).unwrap();
+        }
+        let unkicked = match last {
+            GameInput::RotateClockwise => before.rotated(),
+            GameInput::RotateCounterClockwise => before.rotated_counter_clockwise(),
+            other => panic!("last input should be a rotation: {:?}", other),
+        };
+        assert!(!is_valid_position(&board, height, &unkicked));
+    }
+
+    #[test]
+    fn test_piece_that_does_not_fit_has_no_placements() {
+        let board = vec![vec![Cell::Solid; BOARD_WIDTH]; BOARD_HEIGHT];
+        assert!(enumerate_placements(&board, BOARD_HEIGHT, &piece(TetrominoShape::T)).is_empty());
+    }
+}
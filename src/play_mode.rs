@@ -0,0 +1,194 @@
+//! ゲームモード（Sprint / Ultra / Marathon など）
+//!
+//! 各モードの目標・終了条件と、HUDやリザルト画面に表示する値の計算を扱います。
+
+use std::time::Duration;
+
+use crate::config::{
+    FALL_SPEED_MIN, FALL_SPEED_START, FALL_SPEED_STEP_PER_LEVEL, LINES_PER_LEVEL,
+    MARATHON_LEVEL_CAP, SPRINT_LINES,
+};
+
+/// プレイするゲームモード
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlayMode {
+    /// フィールドが埋まるまで続ける（従来の遊び方）
+    #[default]
+    Endless,
+    /// 指定ライン数をできるだけ速く消す
+    Sprint { lines: u32 },
+    /// 制限時間内のスコアを競う
+    Ultra { time_limit: Duration },
+    /// レベルが上がるごとに落下が速くなり、上限レベルをクリアすると終了
+    Marathon { level_cap: u32 },
+    /// 下からせり上がるSolid・ガベージ行に耐え続ける
+    Survival,
+    /// 手作りの盤面と固定ピース列でクリア条件を目指す（パズル選択画面から選ぶ）
+    Puzzle,
+    /// Endlessと同じルールで、ロックしたピースをUndo/Redoで巻き戻せる
+    Practice,
+}
+
+/// モードの終了理由
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModeOutcome {
+    /// Sprintの目標ライン数・Marathonの上限レベル・パズルのクリア条件に到達
+    GoalReached,
+    /// Ultraの制限時間が終了
+    TimeUp,
+    /// フィールドが埋まった
+    ToppedOut,
+    /// パズルのピースを使い切った
+    OutOfPieces,
+}
+
+/// タイトル画面で選択できるモード（表示順）
+pub const SELECTABLE_MODES: [PlayMode; 9] = [
+    PlayMode::Endless,
+    PlayMode::Sprint {
+        lines: SPRINT_LINES,
+    },
+    PlayMode::Ultra {
+        time_limit: Duration::from_secs(2 * 60),
+    },
+    PlayMode::Ultra {
+        time_limit: Duration::from_secs(3 * 60),
+    },
+    PlayMode::Ultra {
+        time_limit: Duration::from_secs(5 * 60),
+    },
+    PlayMode::Marathon {
+        level_cap: MARATHON_LEVEL_CAP,
+    },
+    PlayMode::Survival,
+    PlayMode::Puzzle,
+    PlayMode::Practice,
+];
+
+impl PlayMode {
+    /// タイトル画面・リザルト画面で使う名前
+    pub fn name(&self) -> String {
+        match self {
+            PlayMode::Endless => "ENDLESS".to_string(),
+            PlayMode::Sprint { lines } => format!("SPRINT {}L", lines),
+            PlayMode::Ultra { time_limit } => format!("ULTRA {}", format_clock(*time_limit)),
+            PlayMode::Marathon { level_cap } => format!("MARATHON L{}", level_cap),
+            PlayMode::Survival => "SURVIVAL".to_string(),
+            PlayMode::Puzzle => "PUZZLE".to_string(),
+            PlayMode::Practice => "PRACTICE".to_string(),
+        }
+    }
+
+    /// 消去ライン数から求めたレベル（Marathon以外は常に1）
+    pub fn level(&self, lines_cleared: u32) -> u32 {
+        match self {
+            PlayMode::Marathon { level_cap } => {
+                (lines_cleared / LINES_PER_LEVEL + 1).min(*level_cap)
+            }
+            _ => 1,
+        }
+    }
+
+    /// 現在のレベルでの落下間隔
+    pub fn fall_speed(&self, lines_cleared: u32) -> Duration {
+        let level_ups = self.level(lines_cleared) - 1;
+        FALL_SPEED_START
+            .saturating_sub(FALL_SPEED_STEP_PER_LEVEL * level_ups)
+            .max(FALL_SPEED_MIN)
+    }
+
+    /// 目標まで残りのライン数（Sprint・Marathonのみ）
+    pub fn lines_remaining(&self, lines_cleared: u32) -> Option<u32> {
+        match self {
+            PlayMode::Sprint { lines } => Some(lines.saturating_sub(lines_cleared)),
+            PlayMode::Marathon { level_cap } => {
+                Some((level_cap * LINES_PER_LEVEL).saturating_sub(lines_cleared))
+            }
+            _ => None,
+        }
+    }
+
+    /// 残り時間（Ultraのみ）
+    pub fn time_remaining(&self, play_time: Duration) -> Option<Duration> {
+        match self {
+            PlayMode::Ultra { time_limit } => Some(time_limit.saturating_sub(play_time)),
+            _ => None,
+        }
+    }
+
+    /// 目標・制限時間に達していれば終了理由を返す
+    pub fn check_finished(&self, lines_cleared: u32, play_time: Duration) -> Option<ModeOutcome> {
+        if self.lines_remaining(lines_cleared) == Some(0) {
+            return Some(ModeOutcome::GoalReached);
+        }
+        if self.time_remaining(play_time) == Some(Duration::ZERO) {
+            return Some(ModeOutcome::TimeUp);
+        }
+        None
+    }
+}
+
+/// 経過時間を `m:ss` 形式に整形
+pub fn format_clock(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// 経過時間を `m:ss.s` 形式に整形（タイマー表示用）
+pub fn format_timer(time: Duration) -> String {
+    format!("{}.{}", format_clock(time), time.subsec_millis() / 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sprint_finishes_when_goal_lines_are_cleared() {
+        let mode = PlayMode::Sprint { lines: 40 };
+        assert_eq!(mode.lines_remaining(15), Some(25));
+        assert_eq!(mode.check_finished(39, Duration::from_secs(600)), None);
+        assert_eq!(
+            mode.check_finished(41, Duration::from_secs(90)),
+            Some(ModeOutcome::GoalReached)
+        );
+    }
+
+    #[test]
+    fn test_ultra_finishes_when_time_runs_out() {
+        let mode = PlayMode::Ultra {
+            time_limit: Duration::from_secs(120),
+        };
+        assert_eq!(
+            mode.time_remaining(Duration::from_secs(30)),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(mode.check_finished(100, Duration::from_secs(119)), None);
+        assert_eq!(
+            mode.check_finished(0, Duration::from_secs(121)),
+            Some(ModeOutcome::TimeUp)
+        );
+        assert_eq!(mode.name(), "ULTRA 2:00");
+    }
+
+    #[test]
+    fn test_marathon_levels_speed_up_until_cap() {
+        let mode = PlayMode::Marathon { level_cap: 3 };
+        assert_eq!(mode.level(0), 1);
+        assert_eq!(mode.level(LINES_PER_LEVEL * 2), 3);
+        assert!(mode.fall_speed(LINES_PER_LEVEL) < mode.fall_speed(0));
+        assert_eq!(
+            mode.check_finished(LINES_PER_LEVEL * 3, Duration::ZERO),
+            Some(ModeOutcome::GoalReached)
+        );
+        // Endlessは速度が変わらず終了条件もない
+        assert_eq!(PlayMode::Endless.fall_speed(1000), FALL_SPEED_START);
+        assert_eq!(PlayMode::Endless.check_finished(1000, Duration::MAX), None);
+    }
+
+    #[test]
+    fn test_timer_format() {
+        assert_eq!(format_timer(Duration::from_millis(83_456)), "1:23.4");
+        assert_eq!(format_clock(Duration::from_secs(300)), "5:00");
+    }
+}
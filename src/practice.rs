@@ -0,0 +1,95 @@
+//! 練習モードの巻き戻し履歴
+//!
+//! ピースをロックする直前のゲーム状態をスナップショットとして記録し、Undo/Redoで
+//! 何ピース分でも行き来できるようにします。記録数には上限があり、超えた分は古いものから捨てます。
+
+use std::collections::VecDeque;
+
+/// Undo/Redo用のスナップショット履歴
+#[derive(Clone, Debug, PartialEq)]
+pub struct UndoHistory<T> {
+    undo: VecDeque<T>,
+    redo: Vec<T>,
+    limit: usize,
+}
+
+impl<T> UndoHistory<T> {
+    /// `limit`個までスナップショットを保持する履歴を作成
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+        }
+    }
+
+    /// スナップショットを記録する。新しく記録するとRedoできる状態は捨てる
+    pub fn record(&mut self, snapshot: T) {
+        self.redo.clear();
+        self.undo.push_back(snapshot);
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+
+    /// 1つ前のスナップショットと`current`を入れ替える（戻せなければfalse）
+    pub fn undo(&mut self, current: &mut T) -> bool {
+        let Some(snapshot) = self.undo.pop_back() else {
+            return false;
+        };
+        self.redo.push(std::mem::replace(current, snapshot));
+        true
+    }
+
+    /// Undoで戻した状態を1つやり直す（やり直せなければfalse）
+    pub fn redo(&mut self, current: &mut T) -> bool {
+        let Some(snapshot) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push_back(std::mem::replace(current, snapshot));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_and_redo_walk_through_snapshots() {
+        let mut history = UndoHistory::new(10);
+        let mut current = 0;
+        for next in 1..=3 {
+            history.record(current);
+            current = next;
+        }
+
+        assert!(history.undo(&mut current));
+        assert!(history.undo(&mut current));
+        assert_eq!(current, 1);
+
+        assert!(history.redo(&mut current));
+        assert_eq!(current, 2);
+
+        // 戻した後に新しく記録すると、やり直し用の状態は捨てられる
+        history.record(current);
+        current = 9;
+        assert!(!history.redo(&mut current));
+        assert_eq!(current, 9);
+    }
+
+    #[test]
+    fn test_history_drops_oldest_snapshots_over_limit() {
+        let mut history = UndoHistory::new(2);
+        let mut current = 0;
+        for next in 1..=5 {
+            history.record(current);
+            current = next;
+        }
+
+        assert!(history.undo(&mut current));
+        assert!(history.undo(&mut current));
+        assert!(!history.undo(&mut current));
+        assert_eq!(current, 3);
+    }
+}
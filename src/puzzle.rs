@@ -0,0 +1,352 @@
+//! パズル（チャレンジ）モード
+//!
+//! 手作りの開始盤面・固定のピース列・クリア条件からなるパズルと、
+//! 複数のパズルをまとめたパズルパックのテキスト形式を扱います。
+//! 書式は`puzzles/builtin.txt`の先頭コメントを参照してください。
+
+use std::collections::BTreeSet;
+
+use crate::board_notation;
+use crate::cell::{Board, Cell};
+use crate::config::BOARD_HEIGHT;
+use crate::game_color::GameColor;
+use crate::scoring::CustomScoreSystem;
+use crate::tetromino::TetrominoShape;
+
+/// 組み込みパズルパック
+pub const BUILTIN_PACK: &str = include_str!("../puzzles/builtin.txt");
+
+/// パズルのクリア条件
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PuzzleGoal {
+    /// 指定本数のラインを消す
+    Lines(u32),
+    /// CHAIN-BONUSを指定段数ためる
+    ChainBonus(u32),
+    /// 指定色のMAX-CHAINを指定値まで伸ばす
+    MaxChain(GameColor, u32),
+}
+
+impl PuzzleGoal {
+    /// 目標値
+    pub fn target(&self) -> u32 {
+        match *self {
+            PuzzleGoal::Lines(target)
+            | PuzzleGoal::ChainBonus(target)
+            | PuzzleGoal::MaxChain(_, target) => target,
+        }
+    }
+
+    /// 現在の達成値
+    pub fn progress(&self, score: &CustomScoreSystem, lines_cleared: u32) -> u32 {
+        match *self {
+            PuzzleGoal::Lines(_) => lines_cleared,
+            PuzzleGoal::ChainBonus(_) => score.chain_bonus,
+            PuzzleGoal::MaxChain(color, _) => score.max_chains.get(color),
+        }
+    }
+
+    pub fn is_met(&self, score: &CustomScoreSystem, lines_cleared: u32) -> bool {
+        self.progress(score, lines_cleared) >= self.target()
+    }
+
+    /// HUDに表示するラベル
+    pub fn label(&self) -> &'static str {
+        match self {
+            PuzzleGoal::Lines(_) => "GOAL LINES:",
+            PuzzleGoal::ChainBonus(_) => "GOAL BONUS:",
+            PuzzleGoal::MaxChain(GameColor::Cyan, _) => "GOAL CYAN:",
+            PuzzleGoal::MaxChain(GameColor::Magenta, _) => "GOAL MAGENTA:",
+            PuzzleGoal::MaxChain(_, _) => "GOAL YELLOW:",
+        }
+    }
+
+    /// パズル選択画面に表示する説明
+    pub fn describe(&self) -> String {
+        match self {
+            PuzzleGoal::Lines(lines) => format!("Clear {} lines", lines),
+            PuzzleGoal::ChainBonus(rows) => format!("Earn {} chain-bonus rows", rows),
+            PuzzleGoal::MaxChain(color, chain) => {
+                format!("Reach MAX-CHAIN {} in {:?}", chain, color)
+            }
+        }
+    }
+}
+
+/// パズルで出現するピース（形と4ブロックの色）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PuzzlePiece {
+    pub shape: TetrominoShape,
+    pub colors: [GameColor; 4],
+}
+
+/// 1問分のパズル
+#[derive(Clone, Debug, PartialEq)]
+pub struct Puzzle {
+    pub name: String,
+    pub goal: PuzzleGoal,
+    pub board: Board,
+    pub board_height: usize,
+    pub pieces: Vec<PuzzlePiece>,
+}
+
+fn parse_color(letter: char) -> Option<GameColor> {
+    match letter.to_ascii_uppercase() {
+        'C' => Some(GameColor::Cyan),
+        'M' => Some(GameColor::Magenta),
+        'Y' => Some(GameColor::Yellow),
+        _ => None,
+    }
+}
+
+fn parse_shape(letter: &str) -> Option<TetrominoShape> {
+    match letter {
+        "I" => Some(TetrominoShape::I),
+        "O" => Some(TetrominoShape::O),
+        "T" => Some(TetrominoShape::T),
+        "L" => Some(TetrominoShape::L),
+        "J" => Some(TetrominoShape::J),
+        "S" => Some(TetrominoShape::S),
+        "Z" => Some(TetrominoShape::Z),
+        _ => None,
+    }
+}
+
+/// `T:CMYC` 形式のピース指定を読み取る
+fn parse_piece(token: &str) -> Result<PuzzlePiece, String> {
+    let invalid = || format!("invalid piece: {}", token);
+    let (shape, colors) = token.split_once(':').ok_or_else(invalid)?;
+    let shape = parse_shape(shape).ok_or_else(invalid)?;
+    let colors: Vec<GameColor> = colors
+        .chars()
+        .map(parse_color)
+        .collect::<Option<_>>()
+        .ok_or_else(invalid)?;
+    let colors: [GameColor; 4] = colors.try_into().map_err(|_| invalid())?;
+    Ok(PuzzlePiece { shape, colors })
+}
+
+fn parse_goal(value: &str) -> Result<PuzzleGoal, String> {
+    let invalid = || format!("invalid goal: {}", value);
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let number = |text: &str| text.parse::<u32>().map_err(|_| invalid());
+    match parts.as_slice() {
+        ["lines", n] => Ok(PuzzleGoal::Lines(number(n)?)),
+        ["chain-bonus", n] => Ok(PuzzleGoal::ChainBonus(number(n)?)),
+        ["max-chain", color, n] => {
+            let color = match *color {
+                "cyan" => GameColor::Cyan,
+                "magenta" => GameColor::Magenta,
+                "yellow" => GameColor::Yellow,
+                _ => return Err(invalid()),
+            };
+            Ok(PuzzleGoal::MaxChain(color, number(n)?))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_puzzle(lines: &[&str]) -> Result<Puzzle, String> {
+    let mut name = None;
+    let mut goal = None;
+    let mut height = None;
+    let mut pieces = Vec::new();
+    let mut board_rows: Option<Vec<&str>> = None;
+
+    for &line in lines {
+        if let Some(rows) = board_rows.as_mut() {
+            rows.push(line);
+            continue;
+        }
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| format!("expected 'key: value': {}", line))?;
+        let value = value.trim();
+        match key.trim() {
+            "name" => name = Some(value.to_string()),
+            "goal" => goal = Some(parse_goal(value)?),
+            "height" => {
+                height = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("invalid height: {}", value))?,
+                )
+            }
+            "pieces" => {
+                pieces = value
+                    .split_whitespace()
+                    .map(parse_piece)
+                    .collect::<Result<_, _>>()?
+            }
+            "board" => board_rows = Some(Vec::new()),
+            other => return Err(format!("unknown puzzle key: {}", other)),
+        }
+    }
+
+    let name = name.ok_or("puzzle is missing 'name'")?;
+    let goal = goal.ok_or_else(|| format!("puzzle '{}' is missing 'goal'", name))?;
+    if pieces.is_empty() {
+        return Err(format!("puzzle '{}' has no pieces", name));
+    }
+    let (board, parsed_height) =
+        board_notation::parse_board(&board_rows.unwrap_or_default().join("\n"))?;
+    let board_height = height.unwrap_or(parsed_height);
+    if board_height == 0
+        || board_height > BOARD_HEIGHT
+        || board[board_height..]
+            .iter()
+            .any(|row| row.iter().any(|&cell| cell != Cell::Solid))
+    {
+        return Err(format!(
+            "puzzle '{}': rows below height {} must be Solid",
+            name, board_height
+        ));
+    }
+
+    Ok(Puzzle {
+        name,
+        goal,
+        board,
+        board_height,
+        pieces,
+    })
+}
+
+/// パズルパックのテキストを読み取る
+pub fn parse_pack(text: &str) -> Result<Vec<Puzzle>, String> {
+    let mut puzzles = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+
+    for line in text.lines() {
+        let line = line.trim();
+        // 空行とコメント（"# "で始まる行）は読み飛ばす。"#"だけが並ぶ盤面の行とは空白の有無で区別する
+        if line.is_empty() || line == "#" || line.starts_with("# ") {
+            continue;
+        }
+        if line == "[puzzle]" {
+            if let Some(lines) = current.take() {
+                puzzles.push(parse_puzzle(&lines)?);
+            }
+            current = Some(Vec::new());
+            continue;
+        }
+        match current.as_mut() {
+            Some(lines) => lines.push(line),
+            None => return Err(format!("expected [puzzle]: {}", line)),
+        }
+    }
+    if let Some(lines) = current {
+        puzzles.push(parse_puzzle(&lines)?);
+    }
+
+    if puzzles.is_empty() {
+        return Err("puzzle pack is empty".to_string());
+    }
+    Ok(puzzles)
+}
+
+/// 組み込みパズルパックを読み込む
+pub fn builtin_puzzles() -> Vec<Puzzle> {
+    parse_pack(BUILTIN_PACK).expect("built-in puzzle pack must be valid")
+}
+
+/// クリア済みパズルの記録（パズル名の集合）
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PuzzleProgress {
+    completed: BTreeSet<String>,
+}
+
+impl PuzzleProgress {
+    /// 1行に1つのパズル名を並べたテキストから読み込む
+    pub fn from_text(text: &str) -> Self {
+        Self {
+            completed: text
+                .lines()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        self.completed
+            .iter()
+            .map(|name| format!("{}\n", name))
+            .collect()
+    }
+
+    pub fn is_completed(&self, name: &str) -> bool {
+        self.completed.contains(name)
+    }
+
+    /// クリア済みにする。新しく記録した場合はtrueを返す
+    pub fn mark_completed(&mut self, name: &str) -> bool {
+        self.completed.insert(name.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_pack_is_valid() {
+        let puzzles = builtin_puzzles();
+        assert!(puzzles.len() >= 4);
+
+        let ten_chain = puzzles.iter().find(|p| p.name == "Ten Chain").unwrap();
+        assert_eq!(ten_chain.goal, PuzzleGoal::ChainBonus(1));
+        assert_eq!(ten_chain.board_height, BOARD_HEIGHT - 2);
+        assert_eq!(
+            ten_chain.board[BOARD_HEIGHT - 3][0],
+            Cell::Connected {
+                color: GameColor::Cyan,
+                count: 1
+            }
+        );
+        assert_eq!(
+            ten_chain.pieces[0],
+            PuzzlePiece {
+                shape: TetrominoShape::O,
+                colors: [GameColor::Cyan; 4]
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_are_reported() {
+        assert!(parse_pack("").is_err());
+        assert!(parse_pack("[puzzle]\nname: x\ngoal: lines 1\nboard:\n..........").is_err());
+        assert!(parse_pack("[puzzle]\nname: x\ngoal: score 5\npieces: I:CCCC").is_err());
+        assert!(parse_pack("[puzzle]\nname: x\ngoal: lines 1\npieces: I:CCC").is_err());
+        assert!(parse_pack(
+            "[puzzle]\nname: x\ngoal: lines 1\npieces: I:CCCC\nheight: 19\nboard:\nC........."
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_goal_progress() {
+        let mut score = CustomScoreSystem::new();
+        let goal = PuzzleGoal::MaxChain(GameColor::Cyan, 8);
+        score.max_chains.update_max(GameColor::Cyan, 7);
+        assert!(!goal.is_met(&score, 0));
+        score.max_chains.update_max(GameColor::Cyan, 8);
+        assert!(goal.is_met(&score, 0));
+        assert!(PuzzleGoal::Lines(2).is_met(&score, 3));
+    }
+
+    #[test]
+    fn test_progress_round_trips_through_text() {
+        let mut progress = PuzzleProgress::default();
+        assert!(progress.mark_completed("First Clear"));
+        assert!(!progress.mark_completed("First Clear"));
+        progress.mark_completed("Ten Chain");
+
+        let restored = PuzzleProgress::from_text(&progress.to_text());
+        assert_eq!(restored, progress);
+        assert!(restored.is_completed("Ten Chain"));
+        assert!(!restored.is_completed("Cyan Ladder"));
+    }
+}
@@ -218,6 +218,11 @@ pub fn create_default_random_provider() -> RandomProviderImpl {
     }
 }
 
+/// プラットフォームの乱数から、シード付きRandomProvider用のシードを決める
+pub fn entropy_seed() -> u64 {
+    (create_default_random_provider().gen_f64() * u64::MAX as f64) as u64
+}
+
 /// RandomProviderの具象実装のenum
 pub enum RandomProviderImpl {
     #[cfg(not(target_arch = "wasm32"))]
@@ -279,6 +284,61 @@ impl RandomProvider for RandomProviderImpl {
     }
 }
 
+/// 状態を複製・比較できるシード付きRandomProvider（xorshift64*）
+///
+/// ゲーム状態に持たせておくと、状態のスナップショットから同じ乱数列を再現できる
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SeededRandomProvider {
+    state: u64,
+}
+
+impl SeededRandomProvider {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: seed.max(1), // 状態0からは0しか生成されないので避ける
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+impl RandomProvider for SeededRandomProvider {
+    fn gen_range(&mut self, min: usize, max: usize) -> usize {
+        if min >= max {
+            return min;
+        }
+        min + (self.next_u64() % (max - min) as u64) as usize
+    }
+
+    fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+        if slice.is_empty() {
+            return None;
+        }
+        let index = self.gen_range(0, slice.len());
+        slice.get(index)
+    }
+
+    fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j = self.gen_range(0, i + 1);
+            slice.swap(i, j);
+        }
+    }
+
+    fn gen_bool(&mut self) -> bool {
+        self.next_u64() >> 63 == 1
+    }
+
+    fn gen_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / ((1u64 << 53) as f64))
+    }
+}
+
 /// テスト用の決定的RandomProvider
 pub struct DeterministicRandomProvider {
     values: Vec<usize>,
@@ -361,6 +421,22 @@ mod tests {
         assert!(items.contains(&4));
     }
 
+    #[test]
+    fn test_seeded_random_provider_replays_from_copy() {
+        let mut provider = SeededRandomProvider::new(42);
+        provider.gen_range(0, 100);
+
+        // 途中の状態を複製すると、以降は同じ乱数列になる
+        let mut copy = provider;
+        let original: Vec<usize> = (0..10).map(|_| provider.gen_range(0, 1000)).collect();
+        let replayed: Vec<usize> = (0..10).map(|_| copy.gen_range(0, 1000)).collect();
+        assert_eq!(original, replayed);
+        assert!(original.iter().all(|&value| value < 1000));
+
+        let f_val = SeededRandomProvider::new(0).gen_f64();
+        assert!((0.0..1.0).contains(&f_val));
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     #[test]
     fn test_native_random_provider() {
@@ -1,15 +1,24 @@
 use crossterm::{
     cursor::MoveTo,
-    execute,
-    style::{Print, ResetColor, SetForegroundColor},
+    queue,
+    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
     terminal,
 };
 use std::io::{self, Write};
 
 use crate::animation::Animation;
-use crate::cell::Cell;
+use crate::board_logic;
+use crate::cell::{Board, Cell};
 use crate::config::{BOARD_HEIGHT, BOARD_WIDTH};
 use crate::game_color::GameColor;
+use crate::glyph::{self, Pattern};
+use crate::hint::{Hint, MAX_HINT_REASONS};
+use crate::layout::{self, BoardStyle, Layout, SidebarStyle};
+use crate::play_mode::{self, ModeOutcome, PlayMode, SELECTABLE_MODES};
+use crate::puzzle::{Puzzle, PuzzleProgress};
+use crate::scoring::{CustomScoreSystem, LockScoreBreakdown, SolidPurgeMode};
+use crate::theme::{self, Charset, ColorDepth, Theme};
+use crate::virtual_terminal::VirtualTerminal;
 use crate::GameMode;
 use crate::GameState; // Import GameState from main.rs // 共通Animationを使用
 
@@ -21,54 +30,158 @@ pub trait Renderer {
     fn print(&mut self, s: &str) -> io::Result<()>;
     fn reset_color(&mut self) -> io::Result<()>;
     fn flush(&mut self) -> io::Result<()>;
+    /// 描画先の大きさ（幅, 高さ）。画面の配置はこの大きさから決める
+    fn size(&self) -> (u16, u16);
+    /// 連結ブロック・Solid・ゴースト・枠に使う色
+    fn theme(&self) -> &Theme {
+        &theme::CLASSIC
+    }
 }
 
+/// 端末への描画（ダブルバッファ）
+///
+/// `Renderer`の操作はオフスクリーンのフレーム（`VirtualTerminal`）に描き、`flush`で前回表示した
+/// フレームとセル単位で比較して、変わったセルだけのエスケープシーケンスを1回の書き込みで送る。
+/// 色はテーマのRGBを端末の色数に合わせて変換して送る
 pub struct CrosstermRenderer {
     pub stdout: io::Stdout,
+    color_depth: ColorDepth,
+    frame: VirtualTerminal,
+    // 端末に表示されているフレーム（Noneなら端末の内容は不明で、次のflushで全体を描き直す）
+    shown: Option<VirtualTerminal>,
 }
 
 impl CrosstermRenderer {
-    pub fn new() -> Self {
+    /// 端末の色数は環境変数から判定する（色を送らないならテーマを`monochrome`にする）
+    pub fn new(mut theme: Theme) -> Self {
+        let (width, height) = terminal::size().unwrap_or((80, 24));
+        let color_depth = ColorDepth::from_env();
+        let mut frame = VirtualTerminal::new(width.max(1) as usize, height.max(1) as usize);
+        theme.monochrome = color_depth == ColorDepth::NoColor;
+        frame.set_theme(theme);
         Self {
             stdout: io::stdout(),
+            color_depth,
+            frame,
+            shown: None,
         }
     }
+
+    /// 端末の大きさが変わったら、新しい大きさのフレームで次のflushに画面全体を描き直す
+    pub fn resize(&mut self, width: u16, height: u16) {
+        let theme = *self.frame.theme();
+        self.frame = VirtualTerminal::new(width.max(1) as usize, height.max(1) as usize);
+        self.frame.set_theme(theme);
+        self.shown = None;
+    }
 }
 
 impl Renderer for CrosstermRenderer {
     fn clear_screen(&mut self) -> io::Result<()> {
-        execute!(self.stdout, terminal::Clear(terminal::ClearType::All))
+        self.frame.clear_screen()
     }
 
     fn move_to(&mut self, x: u16, y: u16) -> io::Result<()> {
-        execute!(self.stdout, MoveTo(x, y))
+        self.frame.move_to(x, y)
     }
 
     fn set_foreground_color(&mut self, color: GameColor) -> io::Result<()> {
-        execute!(self.stdout, SetForegroundColor(color.into()))
+        self.frame.set_foreground_color(color)
     }
 
     fn set_background_color(&mut self, color: GameColor) -> io::Result<()> {
-        // Add this method
-        execute!(
-            self.stdout,
-            crossterm::style::SetBackgroundColor(color.into())
-        )
+        self.frame.set_background_color(color)
     }
 
     fn print(&mut self, s: &str) -> io::Result<()> {
-        execute!(self.stdout, Print(s))
+        self.frame.print(s)
     }
 
     fn reset_color(&mut self) -> io::Result<()> {
-        execute!(self.stdout, ResetColor)
+        self.frame.reset_color()
     }
 
+    /// 組み立てたフレームを端末に反映する
     fn flush(&mut self) -> io::Result<()> {
-        self.stdout.flush()
+        let mut output = Vec::new();
+        write_frame_diff(
+            &mut output,
+            self.shown.as_ref(),
+            &self.frame,
+            self.color_depth,
+        )?;
+        if !output.is_empty() {
+            self.stdout.write_all(&output)?;
+            self.stdout.flush()?;
+        }
+        self.shown = Some(self.frame.clone());
+        Ok(())
+    }
+
+    fn size(&self) -> (u16, u16) {
+        self.frame.size()
+    }
+
+    fn theme(&self) -> &Theme {
+        self.frame.theme()
     }
 }
 
+/// `shown`から`frame`へ変わったセルだけを描くエスケープシーケンスを`out`にためる
+///
+/// `shown`がNoneなら画面を消してから全体を描く。連続するセルではカーソル移動を、
+/// 同じ色が続くセルでは色の指定を省く。色は`frame`のテーマで`depth`に合わせて変換する
+fn write_frame_diff(
+    out: &mut impl Write,
+    shown: Option<&VirtualTerminal>,
+    frame: &VirtualTerminal,
+    depth: ColorDepth,
+) -> io::Result<()> {
+    let theme = frame.theme();
+    let terminal_color =
+        |color: Option<GameColor>| color.map_or(Color::Reset, |c| theme.terminal_color(c, depth));
+    let blank;
+    let shown = match shown {
+        Some(shown) if shown.size() == frame.size() => shown,
+        _ => {
+            queue!(out, ResetColor, terminal::Clear(terminal::ClearType::All))?;
+            let (width, height) = frame.size();
+            blank = VirtualTerminal::new(width as usize, height as usize);
+            &blank
+        }
+    };
+
+    let (width, height) = frame.size();
+    let mut cursor = None;
+    let mut colors = None;
+    for y in 0..height as usize {
+        for x in 0..width as usize {
+            let cell = frame.cell(x, y);
+            if cell == shown.cell(x, y) {
+                continue;
+            }
+            if cursor != Some((x, y)) {
+                queue!(out, MoveTo(x as u16, y as u16))?;
+            }
+            let cell_colors = (cell.foreground, cell.background);
+            if colors != Some(cell_colors) {
+                queue!(
+                    out,
+                    SetForegroundColor(terminal_color(cell.foreground)),
+                    SetBackgroundColor(terminal_color(cell.background))
+                )?;
+                colors = Some(cell_colors);
+            }
+            queue!(out, Print(cell.ch))?;
+            cursor = Some((x + 1, y));
+        }
+    }
+    if colors.is_some() {
+        queue!(out, ResetColor)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 pub mod mock_renderer {
     use super::Renderer;
@@ -90,12 +203,15 @@ pub mod mock_renderer {
 
     pub struct MockRenderer {
         pub commands: Rc<RefCell<Vec<RenderCommand>>>,
+        pub size: (u16, u16),
     }
 
     impl MockRenderer {
+        /// 標準の配置（盤面が左上、サイドバーが右）がちょうど収まる大きさで作る
         pub fn new() -> Self {
             Self {
                 commands: Rc::new(RefCell::new(Vec::new())),
+                size: (44, 26),
             }
         }
     }
@@ -142,6 +258,10 @@ pub mod mock_renderer {
             self.commands.borrow_mut().push(RenderCommand::Flush);
             Ok(())
         }
+
+        fn size(&self) -> (u16, u16) {
+            self.size
+        }
     }
 }
 
@@ -183,14 +303,8 @@ mod tests {
             start_time: Duration::new(0, 0),
         });
 
-        // Create a prev_state where the animation was just starting (count 0)
-        let mut prev_state = state.clone();
-        if let Some(Animation::LineBlink { count, .. }) = prev_state.animation.get_mut(0) {
-            *count = 0;
-        }
-
         // Draw the state
-        draw(&mut mock_renderer, &prev_state, &state).unwrap();
+        draw(&mut mock_renderer, &state).unwrap();
 
         // Assert that the connected block was drawn as "  " (off state)
         let expected_x = (connected_block_x as u16 * 2) + 1;
@@ -226,10 +340,9 @@ mod tests {
         if let Some(Animation::LineBlink { count, .. }) = state_on.animation.get_mut(0) {
             *count = 0; // "On" state
         }
-        let prev_state_on = state.clone(); // Previous state was "Off" (count 1)
 
         let mut mock_renderer_on = mock_renderer::MockRenderer::new();
-        draw(&mut mock_renderer_on, &prev_state_on, &state_on).unwrap();
+        draw(&mut mock_renderer_on, &state_on).unwrap();
 
         let commands_on = mock_renderer_on.commands.borrow();
         let mut found_move_to_on = false;
@@ -286,12 +399,8 @@ mod tests {
             count: test_count,
         };
 
-        // Create a prev_state where the block was empty to force a redraw
-        let mut prev_state = state.clone();
-        prev_state.board[block_y][block_x] = Cell::Empty;
-
         // Draw the state
-        draw(&mut mock_renderer, &prev_state, &state).unwrap();
+        draw(&mut mock_renderer, &state).unwrap();
 
         let expected_x = (block_x as u16 * 2) + 1;
         let expected_y = block_y as u16 + 1;
@@ -331,7 +440,7 @@ mod tests {
         let mut state = prev_state.clone();
         state.custom_score_system.chain_bonus = 5;
 
-        draw(&mut mock_renderer, &prev_state, &state).unwrap();
+        draw(&mut mock_renderer, &state).unwrap();
 
         let commands = mock_renderer.commands.borrow();
         let combined_line = commands.iter().find_map(|command| {
@@ -357,6 +466,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_combo_and_last_lock_breakdown_rendered_on_change() {
+        let mut mock_renderer = mock_renderer::MockRenderer::new();
+        let mut prev_state = GameState::new();
+        prev_state.mode = GameMode::Playing;
+
+        let mut state = prev_state.clone();
+        state.custom_score_system.add_line_clear_points(100);
+        state.custom_score_system.finish_lock();
+        state.custom_score_system.add_line_clear_points(200);
+        state.custom_score_system.finish_lock();
+
+        draw(&mut mock_renderer, &state).unwrap();
+
+        let commands = mock_renderer.commands.borrow();
+        let printed: Vec<String> = commands
+            .iter()
+            .filter_map(|command| match command {
+                RenderCommand::Print(text) => Some(text.clone()),
+                _ => None,
+            })
+            .collect();
+
+        assert!(printed.contains(&format_ui_value("COMBO:", 2)));
+        assert!(printed.contains(&format_ui_value("LAST LOCK:", 300)));
+        assert!(printed.contains(&format_ui_value("  COMBO x2:", 100)));
+    }
+
+    #[test]
+    fn test_hint_ghost_and_reasons_rendered_on_request() {
+        let mut mock_renderer = mock_renderer::MockRenderer::new();
+        let mut prev_state = GameState::new();
+        prev_state.mode = GameMode::Playing;
+        prev_state.current_piece = Some(crate::tetromino::Tetromino::from_shape(
+            crate::tetromino::TetrominoShape::O,
+            [
+                GameColor::Cyan,
+                GameColor::Magenta,
+                GameColor::Yellow,
+                GameColor::Cyan,
+            ],
+        ));
+
+        let mut state = prev_state.clone();
+        state.handle_input(crate::game_input::GameInput::Hint);
+        let hint = state
+            .hint
+            .clone()
+            .expect("hint should be found on an empty board");
+
+        draw(&mut mock_renderer, &state).unwrap();
+
+        let commands = mock_renderer.commands.borrow();
+        for ((x, y), _) in hint.placement.piece.iter_blocks() {
+            let move_to = RenderCommand::MoveTo((x as u16 * 2) + 1, y as u16 + 1);
+            let drawn = commands.windows(3).any(|window| {
+                window[0] == move_to
+                    && matches!(window[1], RenderCommand::SetForegroundColor(_))
+                    && window[2] == RenderCommand::Print("<>".to_string())
+            });
+            assert!(drawn, "hint cell ({}, {}) not drawn: {:?}", x, y, commands);
+        }
+        let reason_line = format!("  {:<width$}", hint.reasons[0], width = UI_LINE_WIDTH - 2);
+        assert!(commands.contains(&RenderCommand::Print(reason_line)));
+    }
+
     #[test]
     fn test_chain_bonus_display_handles_large_numbers() {
         let mut mock_renderer = mock_renderer::MockRenderer::new();
@@ -367,7 +542,7 @@ mod tests {
         let mut state = prev_state.clone();
         state.custom_score_system.chain_bonus = 120;
 
-        draw(&mut mock_renderer, &prev_state, &state).unwrap();
+        draw(&mut mock_renderer, &state).unwrap();
 
         let commands = mock_renderer.commands.borrow();
         let rendered_values: Vec<_> = commands
@@ -395,30 +570,273 @@ mod tests {
             combined_line
         );
     }
+
+    #[test]
+    fn test_theme_colors_solid_connected_ghost_and_frame() {
+        let mut state = GameState::new();
+        state.mode = GameMode::Playing;
+        state.board[BOARD_HEIGHT - 1][0] = Cell::Solid;
+        state.board[BOARD_HEIGHT - 1][1] = Cell::Connected {
+            color: GameColor::Cyan,
+            count: 1,
+        };
+        state.current_piece = Some(crate::tetromino::Tetromino::from_shape(
+            crate::tetromino::TetrominoShape::O,
+            [GameColor::Yellow; 4],
+        ));
+        let mut frame = VirtualTerminal::new(44, 26);
+        frame.set_theme(
+            Theme::parse(
+                "connected: dark\nconnected-text: white\nsolid: dark-grey\nghost: red\nframe: white",
+            )
+            .unwrap(),
+        );
+        draw(&mut frame, &state).unwrap();
+
+        let bottom = BOARD_HEIGHT;
+        assert_eq!(frame.cell(0, 0).foreground, Some(GameColor::White));
+        assert_eq!(frame.cell(1, bottom).foreground, Some(GameColor::DarkGrey));
+        let connected = frame.cell(4, bottom);
+        assert_eq!(connected.background, Some(GameColor::DarkCyan));
+        assert_eq!(connected.foreground, Some(GameColor::White));
+        let ghost = (0..=BOARD_HEIGHT)
+            .flat_map(|y| (0..BOARD_WIDTH * 2 + 2).map(move |x| (x, y)))
+            .map(|(x, y)| frame.cell(x, y))
+            .find(|cell| cell.ch == ':')
+            .expect("ghost is drawn");
+        assert_eq!(ghost.foreground, Some(GameColor::Red));
+
+        // 色を出せない端末（NO_COLOR）では、テーマに関係なく既定色で送る
+        let mut output = Vec::new();
+        write_frame_diff(&mut output, None, &frame, ColorDepth::NoColor).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(!output.contains("\x1b[38;5;"));
+        assert!(!output.contains("\x1b[97m"));
+    }
+
+    #[test]
+    fn test_group_overlay_counts_down_to_next_bonus() {
+        let mut board = vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT];
+        // Cyanの9個（次のボーナスまで1）とMagentaの3個（次のボーナスまで7）
+        board[BOARD_HEIGHT - 1][..9].fill(Cell::Occupied(GameColor::Cyan));
+        board[BOARD_HEIGHT - 2][..3].fill(Cell::Occupied(GameColor::Magenta));
+        board[BOARD_HEIGHT - 3][0] = Cell::Occupied(GameColor::Yellow);
+
+        let overlay = group_overlay(&board);
+        let cyan = overlay[BOARD_HEIGHT - 1][0].unwrap();
+        assert_eq!(cyan.remaining, Some(1));
+        assert!(cyan.near_bonus);
+        assert_eq!(cyan.edges, EDGE_LEFT | EDGE_TOP | EDGE_BOTTOM);
+        let magenta = overlay[BOARD_HEIGHT - 2][1].unwrap();
+        assert_eq!(magenta.remaining, None);
+        assert!(!magenta.near_bonus);
+        assert_eq!(overlay[BOARD_HEIGHT - 2][0].unwrap().remaining, Some(7));
+        // 単独のブロックはグループとして描かない
+        assert_eq!(overlay[BOARD_HEIGHT - 3][0], None);
+
+        let symbols = Symbols::for_charset(Charset::Ascii);
+        assert_eq!(group_outline(&symbols, EDGE_TOP | EDGE_BOTTOM), "==");
+        assert_eq!(group_outline(&symbols, EDGE_RIGHT | EDGE_TOP), "-+");
+    }
+
+    #[test]
+    fn test_frame_diff_writes_only_changed_cells() {
+        let mut state = GameState::new();
+        state.mode = GameMode::Playing;
+        let mut frame = VirtualTerminal::new(44, 26);
+        draw(&mut frame, &state).unwrap();
+
+        // 最初のフレームは画面を消してから全体を描く
+        let mut first = Vec::new();
+        write_frame_diff(&mut first, None, &frame, ColorDepth::Ansi16).unwrap();
+        let mut clear = Vec::new();
+        queue!(clear, ResetColor, terminal::Clear(terminal::ClearType::All)).unwrap();
+        assert!(first.starts_with(&clear));
+        assert!(String::from_utf8(first).unwrap().contains("SCORE:"));
+
+        // 同じ状態を描き直しても何も送らない
+        let shown = frame.clone();
+        draw(&mut frame, &state).unwrap();
+        let mut unchanged = Vec::new();
+        write_frame_diff(&mut unchanged, Some(&shown), &frame, ColorDepth::Ansi16).unwrap();
+        assert!(unchanged.is_empty());
+
+        // 10-CHAINの値が変わったら、その数字の1セルだけを送る
+        state.custom_score_system.chain_bonus = 7;
+        draw(&mut frame, &state).unwrap();
+        let mut changed = Vec::new();
+        write_frame_diff(&mut changed, Some(&shown), &frame, ColorDepth::Ansi16).unwrap();
+        let value_x = (BOARD_WIDTH * 2 + 4 + UI_LABEL_WIDTH + UI_VALUE_WIDTH - 1) as u16;
+        let mut expected = Vec::new();
+        queue!(
+            expected,
+            MoveTo(value_x, 4),
+            SetForegroundColor(Color::White),
+            SetBackgroundColor(Color::Reset),
+            Print('7'),
+            ResetColor
+        )
+        .unwrap();
+        assert_eq!(changed, expected);
+    }
 }
 
-pub fn draw_title_screen<R: Renderer>(renderer: &mut R) -> io::Result<()> {
+/// パズル選択画面を描画（クリア済みのパズルには印を付ける）
+pub fn draw_puzzle_select_screen<R: Renderer>(
+    renderer: &mut R,
+    puzzles: &[Puzzle],
+    selected: usize,
+    progress: &PuzzleProgress,
+) -> io::Result<()> {
+    let width = puzzles
+        .iter()
+        .map(|puzzle| puzzle.name.chars().count() as u16 + 3)
+        .max()
+        .unwrap_or(0)
+        .max(PUZZLE_SELECT_WIDTH);
+    let height = puzzles.len() as u16 + 9;
+    let Some((left, top)) = centered_area(renderer, width, height)? else {
+        return renderer.flush();
+    };
     renderer.clear_screen()?;
+    let title = "SELECT PUZZLE";
+    renderer.set_foreground_color(GameColor::Yellow)?;
+    renderer.move_to(left + layout::centered_x(width, title), top + 1)?;
+    renderer.print(title)?;
+
+    for (index, puzzle) in puzzles.iter().enumerate() {
+        let marker = if index == selected { ">" } else { " " };
+        let check = if progress.is_completed(&puzzle.name) {
+            "*"
+        } else {
+            " "
+        };
+        let color = if index == selected {
+            GameColor::Yellow
+        } else {
+            GameColor::White
+        };
+        renderer.set_foreground_color(color)?;
+        renderer.move_to(left, top + 3 + index as u16)?;
+        renderer.print(&format!("{}{} {}", marker, check, puzzle.name))?;
+    }
+
+    let footer_y = top + 4 + puzzles.len() as u16;
+    renderer.set_foreground_color(GameColor::White)?;
+    if let Some(puzzle) = puzzles.get(selected) {
+        renderer.move_to(left, footer_y)?;
+        renderer.print(&format!("GOAL: {}", puzzle.goal.describe()))?;
+        renderer.move_to(left, footer_y + 1)?;
+        renderer.print(&format!("PIECES: {}", puzzle.pieces.len()))?;
+    }
+    renderer.move_to(left, footer_y + 3)?;
+    renderer.print("* = cleared")?;
+    renderer.move_to(left, footer_y + 4)?;
+    renderer.print("Enter: Start  q: Back")?;
+    renderer.reset_color()?;
+    renderer.flush()
+}
+
+/// パズル選択画面の最小の幅（操作説明の行が収まる幅）
+const PUZZLE_SELECT_WIDTH: u16 = 22;
+
+/// 描画先の大きさとマスの描き方に合わせた配置（どの配置も収まらなければ、その旨を描いてNone）
+fn screen_layout<R: Renderer>(
+    renderer: &mut R,
+    board_style: BoardStyle,
+) -> io::Result<Option<Layout>> {
+    let (width, height) = renderer.size();
+    let layout = Layout::for_screen(width, height, board_style);
+    if layout.is_none() {
+        let needed = layout::minimum_sizes_text(board_style);
+        draw_too_small(renderer, width, height, &needed)?;
+    }
+    Ok(layout)
+}
+
+/// 画面の中央に置く`width`x`height`の領域の左上（収まらなければ、その旨を描いてNone）
+fn centered_area<R: Renderer>(
+    renderer: &mut R,
+    width: u16,
+    height: u16,
+) -> io::Result<Option<(u16, u16)>> {
+    let (screen_width, screen_height) = renderer.size();
+    if screen_width < width || screen_height < height {
+        let needed = format!("{}x{}", width, height);
+        draw_too_small(renderer, screen_width, screen_height, &needed)?;
+        return Ok(None);
+    }
+    Ok(Some((
+        (screen_width - width) / 2,
+        (screen_height - height) / 2,
+    )))
+}
+
+/// 端末が小さすぎるときの表示（必要な大きさと今の大きさ）
+fn draw_too_small<R: Renderer>(
+    renderer: &mut R,
+    width: u16,
+    height: u16,
+    needed: &str,
+) -> io::Result<()> {
+    renderer.clear_screen()?;
+    let lines = [
+        "TERMINAL TOO SMALL".to_string(),
+        format!("NEED {}", needed),
+        format!("NOW {}x{}", width, height),
+    ];
+    let top = height.saturating_sub(lines.len() as u16) / 2;
+    renderer.set_foreground_color(GameColor::Yellow)?;
+    for (offset, line) in lines.iter().enumerate() {
+        let line: String = line.chars().take(width as usize).collect();
+        renderer.move_to(layout::centered_x(width, &line), top + offset as u16)?;
+        renderer.print(&line)?;
+        renderer.set_foreground_color(GameColor::White)?;
+    }
+    renderer.reset_color()
+}
+
+/// タイトル画面を描画（`selected_mode`はSELECTABLE_MODES内の選択中のモード）
+pub fn draw_title_screen<R: Renderer>(renderer: &mut R, selected_mode: usize) -> io::Result<()> {
     let title = "THUD & TILE";
+    let select_msg = "Up/Down: Select Mode";
     let start_msg = "Press Enter to Start";
     let quit_msg = "Press 'q' to Quit";
 
-    let title_x = (BOARD_WIDTH * 2 + 3 - title.len()) as u16 / 2;
-    let title_y = (BOARD_HEIGHT / 2) as u16 - 2;
-
-    let start_x = (BOARD_WIDTH * 2 + 3 - start_msg.len()) as u16 / 2;
-    let start_y = (BOARD_HEIGHT / 2) as u16;
-
-    let quit_x = (BOARD_WIDTH * 2 + 3 - quit_msg.len()) as u16 / 2;
-    let quit_y = (BOARD_HEIGHT / 2) as u16 + 1;
+    let width = select_msg.len() as u16;
+    let height = SELECTABLE_MODES.len() as u16 + 6;
+    let Some((left, title_y)) = centered_area(renderer, width, height)? else {
+        return renderer.flush();
+    };
+    renderer.clear_screen()?;
+    let modes_y = title_y + 2;
+    let start_y = modes_y + SELECTABLE_MODES.len() as u16 + 1;
 
     renderer.set_foreground_color(GameColor::Yellow)?;
-    renderer.move_to(title_x, title_y)?;
+    renderer.move_to(left + layout::centered_x(width, title), title_y)?;
     renderer.print(title)?;
+
+    for (index, mode) in SELECTABLE_MODES.iter().enumerate() {
+        let (marker, color) = if index == selected_mode {
+            (">", GameColor::Yellow)
+        } else {
+            (" ", GameColor::White)
+        };
+        let item = format!("{} {:<12}", marker, mode.name());
+        renderer.set_foreground_color(color)?;
+        renderer.move_to(
+            left + layout::centered_x(width, &item),
+            modes_y + index as u16,
+        )?;
+        renderer.print(&item)?;
+    }
+
     renderer.set_foreground_color(GameColor::White)?;
-    renderer.move_to(start_x, start_y)?;
+    renderer.move_to(left + layout::centered_x(width, select_msg), start_y)?;
+    renderer.print(select_msg)?;
+    renderer.move_to(left + layout::centered_x(width, start_msg), start_y + 1)?;
     renderer.print(start_msg)?;
-    renderer.move_to(quit_x, quit_y)?;
+    renderer.move_to(left + layout::centered_x(width, quit_msg), start_y + 2)?;
     renderer.print(quit_msg)?;
     renderer.reset_color()?;
     renderer.flush()
@@ -431,17 +849,54 @@ fn draw_connected_cell<R: Renderer>(
     x: u16,
     y: u16,
 ) -> io::Result<()> {
+    let theme = *renderer.theme();
     renderer.move_to(x, y)?;
-    renderer.set_background_color(color)?;
-    renderer.set_foreground_color(GameColor::Black)?;
-    renderer.print(&format!("{:>2}", count))?;
+    renderer.set_background_color(theme.connected.resolve(color))?;
+    renderer.set_foreground_color(theme.connected_text)?;
+    if theme.glyphs {
+        // 色の記号を連結数の前に付ける（10以上は`+`）
+        let count = char::from_digit(count as u32, 10).unwrap_or('+');
+        let mark = Pattern::for_color(color).connected_mark();
+        renderer.print(&format!("{}{}", mark, count))?;
+    } else {
+        renderer.print(&format!("{:>2}", count))?;
+    }
+    renderer.reset_color()?;
+    Ok(())
+}
+
+/// 連結ブロック以外のブロックセルの表示文字と色（Solidの色と記号表示はテーマで決める）
+fn block_style(cell: Cell, theme: &Theme) -> (&'static str, GameColor) {
+    match cell {
+        Cell::Occupied(color) | Cell::Connected { color, count: _ } if theme.glyphs => {
+            (Pattern::for_color(color).block(), color)
+        }
+        Cell::Solid if theme.glyphs => (glyph::SOLID_GLYPH, theme.solid),
+        Cell::Occupied(color) => ("[]", color),
+        Cell::Connected { color, count: _ } => ("[]", color),
+        Cell::Wildcard => ("**", GameColor::White),
+        Cell::Bomb => ("()", GameColor::Red),
+        Cell::Garbage => ("##", GameColor::Grey),
+        Cell::Solid => ("[]", theme.solid),
+        Cell::Empty => ("  ", GameColor::White),
+    }
+}
+
+/// 現在のカーソル位置にブロックセルを描画（Connectedはdraw_connected_cellを使う）
+fn draw_block<R: Renderer>(renderer: &mut R, cell: Cell) -> io::Result<()> {
+    if cell == Cell::Empty {
+        return renderer.print("  ");
+    }
+    let (glyph, color) = block_style(cell, renderer.theme());
+    renderer.set_foreground_color(color)?;
+    renderer.print(glyph)?;
     renderer.reset_color()?;
     Ok(())
 }
 
 const UI_LABEL_WIDTH: usize = 12;
 const UI_VALUE_WIDTH: usize = 6;
-const UI_LINE_WIDTH: usize = UI_LABEL_WIDTH + UI_VALUE_WIDTH + 2;
+pub const UI_LINE_WIDTH: usize = UI_LABEL_WIDTH + UI_VALUE_WIDTH + 2;
 
 fn format_ui_value(label: &str, value: u32) -> String {
     format!(
@@ -453,323 +908,994 @@ fn format_ui_value(label: &str, value: u32) -> String {
     )
 }
 
-fn render_chain_bonus_value<R: Renderer>(
+fn format_ui_text(label: &str, value: &str) -> String {
+    format!(
+        "{label:<label_width$}{value:>value_width$}  ",
+        label = label,
+        value = value,
+        label_width = UI_LABEL_WIDTH,
+        value_width = UI_VALUE_WIDTH
+    )
+}
+
+fn render_combo_values<R: Renderer>(
     renderer: &mut R,
     ui_x: u16,
     base_y: u16,
-    chain_bonus: u32,
+    score_system: &CustomScoreSystem,
 ) -> io::Result<()> {
     renderer.set_foreground_color(GameColor::White)?;
     renderer.move_to(ui_x, base_y)?;
-    renderer.print(format_ui_value("10-CHAIN:", chain_bonus).as_str())?;
+    renderer.print(format_ui_value("COMBO:", score_system.combo).as_str())?;
+    renderer.move_to(ui_x, base_y + 1)?;
+    let back_to_back = if score_system.back_to_back {
+        "ON"
+    } else {
+        "OFF"
+    };
+    renderer.print(format_ui_text("B2B:", back_to_back).as_str())?;
     renderer.reset_color()?;
     Ok(())
 }
 
-pub fn draw<R: Renderer>(
+/// 直近のライン消去ロックで得た点数の内訳を表示
+fn render_last_lock_breakdown<R: Renderer>(
     renderer: &mut R,
-    prev_state: &GameState,
+    ui_x: u16,
+    base_y: u16,
+    breakdown: Option<&LockScoreBreakdown>,
+) -> io::Result<()> {
+    let default_breakdown = LockScoreBreakdown::default();
+    let breakdown = breakdown.unwrap_or(&default_breakdown);
+    renderer.set_foreground_color(GameColor::White)?;
+    renderer.move_to(ui_x, base_y)?;
+    renderer.print(format_ui_value("LAST LOCK:", breakdown.total()).as_str())?;
+    renderer.move_to(ui_x, base_y + 1)?;
+    renderer.print(format_ui_value("  BASE:", breakdown.base_points).as_str())?;
+    renderer.move_to(ui_x, base_y + 2)?;
+    let combo_label = format!("  COMBO x{}:", breakdown.combo);
+    renderer.print(format_ui_value(&combo_label, breakdown.combo_bonus).as_str())?;
+    renderer.move_to(ui_x, base_y + 3)?;
+    renderer.print(format_ui_value("  B2B:", breakdown.back_to_back_bonus).as_str())?;
+    renderer.reset_color()?;
+    Ok(())
+}
+
+/// ヒントのキーと、表示中のヒントの理由を表示
+fn render_hint_reasons<R: Renderer>(
+    renderer: &mut R,
+    ui_x: u16,
+    base_y: u16,
+    hint: Option<&Hint>,
+) -> io::Result<()> {
+    renderer.set_foreground_color(GameColor::White)?;
+    renderer.move_to(ui_x, base_y)?;
+    renderer.print(format_ui_text("HINT:", "H").as_str())?;
+    let reasons = hint.map_or(&[][..], |hint| &hint.reasons[..]);
+    for offset in 0..MAX_HINT_REASONS {
+        let reason = reasons.get(offset).map_or("", String::as_str);
+        renderer.move_to(ui_x, base_y + 1 + offset as u16)?;
+        renderer.print(format!("  {:<width$}", reason, width = UI_LINE_WIDTH - 2).as_str())?;
+    }
+    renderer.reset_color()?;
+    Ok(())
+}
+
+/// Manual purgeモードで、次のSolid purgeで消える段数 / 消去可能な段数を表示
+fn render_purge_value<R: Renderer>(
+    renderer: &mut R,
+    ui_x: u16,
+    base_y: u16,
     state: &GameState,
 ) -> io::Result<()> {
-    if prev_state == state {
-        return Ok(());
+    let value = format!(
+        "{}/{}",
+        state.purgeable_solid_lines(),
+        state.available_purge_lines()
+    );
+    renderer.set_foreground_color(GameColor::White)?;
+    renderer.move_to(ui_x, base_y)?;
+    renderer.print(format_ui_text("PURGE:", &value).as_str())?;
+    renderer.reset_color()?;
+    Ok(())
+}
+
+/// モード別のHUD（タイマーと目標までの残り）の表示内容
+fn mode_hud_lines(state: &GameState) -> [String; 2] {
+    if let Some(puzzle) = &state.puzzle {
+        // パズル: クリア条件の達成状況と残りピース数
+        let progress = puzzle
+            .goal
+            .progress(&state.custom_score_system, state.lines_cleared);
+        let pieces_left = state.piece_queue.as_ref().map_or(0, |queue| queue.len())
+            + usize::from(state.next_piece.is_some());
+        return [
+            format_ui_text(
+                puzzle.goal.label(),
+                &format!("{}/{}", progress, puzzle.goal.target()),
+            ),
+            format_ui_value("PIECES LEFT:", pieces_left as u32),
+        ];
+    }
+
+    let timer = match state.play_mode.time_remaining(state.play_time) {
+        Some(remaining) => format_ui_text("TIME LEFT:", &play_mode::format_timer(remaining)),
+        None => format_ui_text("TIME:", &play_mode::format_timer(state.play_time)),
+    };
+    let progress = match state.play_mode {
+        PlayMode::Sprint { .. } => format_ui_value(
+            "LINES LEFT:",
+            state
+                .play_mode
+                .lines_remaining(state.lines_cleared)
+                .unwrap_or(0),
+        ),
+        PlayMode::Marathon { level_cap } => format_ui_text(
+            "LEVEL:",
+            &format!(
+                "{}/{}",
+                state.play_mode.level(state.lines_cleared),
+                level_cap
+            ),
+        ),
+        PlayMode::Survival => format_ui_text(
+            "NEXT RISE:",
+            &play_mode::format_timer(
+                state
+                    .next_rise_at
+                    .unwrap_or_default()
+                    .saturating_sub(state.play_time),
+            ),
+        ),
+        // 練習: 巻き戻しのキー
+        PlayMode::Practice => format_ui_text("UNDO/REDO:", "Z/Y"),
+        PlayMode::Endless | PlayMode::Ultra { .. } | PlayMode::Puzzle => {
+            format_ui_value("LINES:", state.lines_cleared)
+        }
+    };
+    [timer, progress]
+}
+
+fn render_mode_hud<R: Renderer>(
+    renderer: &mut R,
+    ui_x: u16,
+    base_y: u16,
+    lines: &[String; 2],
+) -> io::Result<()> {
+    renderer.set_foreground_color(GameColor::White)?;
+    for (offset, line) in lines.iter().enumerate() {
+        renderer.move_to(ui_x, base_y + offset as u16)?;
+        renderer.print(line)?;
+    }
+    renderer.reset_color()?;
+    Ok(())
+}
+
+/// ゲーム終了時のリザルト画面を盤面の上に重ねて描画
+///
+/// 盤面の枠の内側に収まるよう、狭い盤面や低い盤面ではサイドバーと重ならない項目に絞った
+/// 短いレポートにする
+fn draw_results<R: Renderer>(
+    renderer: &mut R,
+    layout: &Layout,
+    state: &GameState,
+) -> io::Result<()> {
+    let (headline, color) = match state.outcome {
+        Some(ModeOutcome::GoalReached) => ("COMPLETE!", GameColor::Yellow),
+        Some(ModeOutcome::TimeUp) => ("TIME UP", GameColor::Yellow),
+        Some(ModeOutcome::OutOfPieces) => ("FAILED", GameColor::Red),
+        Some(ModeOutcome::ToppedOut) | None => ("GAME OVER", GameColor::Red),
+    };
+
+    let (frame_width, _) = layout.board_style.frame_size();
+    let width = frame_width as usize - 2;
+    let rows = layout.board_style.board_rows();
+    let mut lines = result_lines(state, width, false);
+    let longest = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    if longest > width || lines.len() as u16 + 4 > rows {
+        lines = result_lines(state, width, true);
+    }
+
+    // 上下の余白と見出しの下の空行を含めて、枠の内側の中央に重ねる（はみ出す行は描かない）
+    let height = (lines.len() as u16 + 4).min(rows);
+    let top = layout.board.1 + 1 + (rows - height) / 2;
+    let blank = " ".repeat(width);
+    for y in top..top + height {
+        renderer.move_to(layout.board_centered_x(&blank), y)?;
+        renderer.print(&blank)?;
     }
 
+    renderer.set_foreground_color(color)?;
+    renderer.move_to(layout.board_centered_x(headline), top + 1)?;
+    renderer.print(headline)?;
+    renderer.set_foreground_color(GameColor::White)?;
+    for (offset, line) in lines
+        .iter()
+        .take(height.saturating_sub(4) as usize)
+        .enumerate()
+    {
+        renderer.move_to(layout.board_centered_x(line), top + 3 + offset as u16)?;
+        renderer.print(line)?;
+    }
+    renderer.reset_color()?;
+    Ok(())
+}
+
+/// リザルト画面の見出しより下の行（`compact`ならTIME・LINES・SCOREなどサイドバーにある項目と
+/// 形ごとの内訳を省き、`width`文字に収まる`ラベル 値`の行にする）
+fn result_lines(state: &GameState, width: usize, compact: bool) -> Vec<String> {
+    let mode_name = match &state.puzzle {
+        Some(puzzle) => puzzle.name.clone(),
+        None => state.play_mode.name(),
+    };
+    let stats = &state.stats;
+    let value = |label: &str, value: String| {
+        if compact {
+            let pad = width.saturating_sub(label.chars().count() + 1);
+            format!("{} {:>pad$}", label, value)
+        } else {
+            format!("{} {}", label, value)
+        }
+    };
+    let mut lines = vec![mode_name.chars().take(width).collect()];
+    if !compact {
+        lines.extend([
+            format!("TIME  {}", play_mode::format_timer(state.play_time)),
+            format!("LINES {}", state.lines_cleared),
+            format!("SCORE {}", state.custom_score_system.score.total()),
+        ]);
+    }
+    match state.play_mode {
+        PlayMode::Marathon { .. } => lines.push(value(
+            "LEVEL",
+            state.play_mode.level(state.lines_cleared).to_string(),
+        )),
+        PlayMode::Survival => lines.push(value("RISES", state.rises.to_string())),
+        _ => {}
+    }
+    let enter = if state.play_mode == PlayMode::Puzzle {
+        "Enter: Puzzles"
+    } else {
+        "Enter: Title"
+    };
+    // プレイの統計レポート
+    let pieces_per_second = format!("{:.2}", stats.pieces_per_second(state.play_time));
+    if compact {
+        lines.extend([
+            value("PIECES", stats.pieces_placed.to_string()),
+            value("PPS", pieces_per_second),
+            value("BIGGEST", stats.biggest_group.to_string()),
+            String::new(),
+        ]);
+        let enter = if enter.len() <= width {
+            enter
+        } else {
+            "Enter:Back"
+        };
+        lines.push(enter.to_string());
+        return lines;
+    }
+    lines.extend([
+        String::new(),
+        format!("PIECES {} ({}/S)", stats.pieces_placed, pieces_per_second),
+        stats.shape_counts_text(0..4),
+        stats.shape_counts_text(4..7),
+        format!("BOTTOM/SOLID {}/{}", stats.bottom_lines, stats.solid_lines),
+        format!("PURGED {}", stats.solid_rows_purged),
+        format!("ISOLATED {}", stats.isolated_blocks_removed),
+        format!("BIGGEST {}", stats.biggest_group),
+        String::new(),
+        enter.to_string(),
+    ]);
+    lines
+}
+
+/// サイドバーの統計表示（SCORE〜MAX-CHAINの8行に入れ替える）
+fn stats_lines(state: &GameState) -> [String; 8] {
+    let stats = &state.stats;
+    [
+        format_ui_value("PIECES:", stats.pieces_placed),
+        format_ui_text(
+            "PPS:",
+            &format!("{:.2}", stats.pieces_per_second(state.play_time)),
+        ),
+        format!(
+            "  {:<width$}",
+            stats.shape_counts_text(0..4),
+            width = UI_LINE_WIDTH - 2
+        ),
+        format!(
+            "  {:<width$}",
+            stats.shape_counts_text(4..7),
+            width = UI_LINE_WIDTH - 2
+        ),
+        format_ui_text(
+            "LINES B/S:",
+            &format!("{}/{}", stats.bottom_lines, stats.solid_lines),
+        ),
+        format_ui_value("PURGED:", stats.solid_rows_purged),
+        format_ui_value("ISOLATED:", stats.isolated_blocks_removed),
+        format_ui_value("BIGGEST:", stats.biggest_group),
+    ]
+}
+
+/// 詰めたサイドバーの統計表示（形ごとの内訳は省く）
+fn condensed_stats_lines(state: &GameState) -> [String; 5] {
+    let stats = &state.stats;
+    [
+        format_ui_value("PIECES:", stats.pieces_placed),
+        format_ui_text(
+            "PPS:",
+            &format!("{:.2}", stats.pieces_per_second(state.play_time)),
+        ),
+        format_ui_text(
+            "LINES B/S:",
+            &format!("{}/{}", stats.bottom_lines, stats.solid_lines),
+        ),
+        format_ui_text(
+            "PURGED/ISO:",
+            &format!(
+                "{}/{}",
+                stats.solid_rows_purged, stats.isolated_blocks_removed
+            ),
+        ),
+        format_ui_value("BIGGEST:", stats.biggest_group),
+    ]
+}
+
+fn render_stats_lines<R: Renderer>(
+    renderer: &mut R,
+    ui_x: u16,
+    top: u16,
+    lines: &[String],
+) -> io::Result<()> {
+    renderer.set_foreground_color(GameColor::White)?;
+    for (offset, line) in lines.iter().enumerate() {
+        renderer.move_to(ui_x, top + offset as u16)?;
+        renderer.print(line)?;
+    }
+    renderer.reset_color()
+}
+
+fn render_chain_bonus_value<R: Renderer>(
+    renderer: &mut R,
+    ui_x: u16,
+    base_y: u16,
+    chain_bonus: u32,
+) -> io::Result<()> {
+    renderer.set_foreground_color(GameColor::White)?;
+    renderer.move_to(ui_x, base_y)?;
+    renderer.print(format_ui_value("10-CHAIN:", chain_bonus).as_str())?;
+    renderer.reset_color()?;
+    Ok(())
+}
+
+/// ゲーム画面の1フレームを最初から描く
+///
+/// 前のフレームとの差分は`Renderer`側（`CrosstermRenderer`のダブルバッファ）が取るので、
+/// ここではゲームの状態と描画先の大きさだけから画面全体を組み立てる
+pub fn draw<R: Renderer>(renderer: &mut R, state: &GameState) -> io::Result<()> {
     match state.mode {
-        GameMode::Title => { /* Do nothing, handled by draw_title_screen */ }
-        GameMode::Playing => {
-            if prev_state.mode != GameMode::Playing {
-                // Redraw the whole game screen
+        GameMode::Title | GameMode::PuzzleSelect => {
+            /* Do nothing, handled by draw_title_screen / draw_puzzle_select_screen */
+        }
+        GameMode::Playing | GameMode::GameOver => {
+            if let Some(layout) = screen_layout(renderer, board_style(state))? {
                 renderer.clear_screen()?;
-                // Redraw static elements
-                renderer.set_foreground_color(GameColor::Grey)?;
-                renderer.move_to(0, 0)?;
-                renderer.print("┌")?;
-                renderer.move_to((BOARD_WIDTH * 2) as u16 + 1, 0)?;
-                renderer.print("┐")?;
-                renderer.move_to(0, BOARD_HEIGHT as u16 + 1)?;
-                renderer.print("└")?;
-                renderer.move_to((BOARD_WIDTH * 2) as u16 + 1, BOARD_HEIGHT as u16 + 1)?;
-                renderer.print("┘")?;
-                for y in 1..=BOARD_HEIGHT {
-                    renderer.move_to(0, y as u16)?;
-                    renderer.print("│")?;
-                    renderer.move_to((BOARD_WIDTH * 2) as u16 + 1, y as u16)?;
-                    renderer.print("│")?;
+                draw_frame(renderer, &layout)?;
+                draw_board(renderer, &layout, state)?;
+                match layout.sidebar_style {
+                    SidebarStyle::Full => draw_sidebar(renderer, &layout, state)?,
+                    SidebarStyle::Condensed => draw_condensed_sidebar(renderer, &layout, state)?,
                 }
-                for x in 0..BOARD_WIDTH {
-                    renderer.move_to((x * 2) as u16 + 1, 0)?;
-                    renderer.print("──")?;
-                    renderer.move_to((x * 2) as u16 + 1, BOARD_HEIGHT as u16 + 1)?;
-                    renderer.print("──")?;
+                if state.mode == GameMode::GameOver {
+                    draw_results(renderer, &layout, state)?;
                 }
-                renderer.reset_color()?;
-                let ui_x = (BOARD_WIDTH * 2 + 4) as u16;
-                renderer.set_foreground_color(GameColor::White)?;
-                renderer.move_to(ui_x, 2)?;
-                renderer.print(format_ui_value("SCORE:", 0).as_str())?;
-                renderer.reset_color()?;
-                render_chain_bonus_value(renderer, ui_x, 4, 0)?;
-                renderer.set_foreground_color(GameColor::White)?;
-                renderer.move_to(ui_x, 5)?;
-                renderer.print(
-                    format!("{:<width$}", "MAX-CHAIN:", width = UI_LINE_WIDTH).as_str(),
-                )?;
-                renderer.move_to(ui_x, 6)?;
-                renderer.print(format_ui_value("  CYAN:", 0).as_str())?;
-                renderer.move_to(ui_x, 7)?;
-                renderer.print(format_ui_value("  MAGENTA:", 0).as_str())?;
-                renderer.move_to(ui_x, 8)?;
-                renderer.print(format_ui_value("  YELLOW:", 0).as_str())?;
-                renderer.reset_color()?;
             }
+        }
+    }
 
-            // --- 消去フェーズ ---
-            if let Some(ghost) = &prev_state.ghost_piece() {
-                if Some(ghost) != prev_state.current_piece.as_ref() {
-                    for ((x, y), _) in ghost.iter_blocks() {
-                        if y >= 0 {
-                            renderer.move_to((x as u16 * 2) + 1, y as u16 + 1)?;
-                            renderer.print("  ")?;
-                        }
-                    }
-                }
-            }
-            if let Some(piece) = &prev_state.current_piece {
-                if prev_state.animation.is_empty() {
-                    for ((x, y), _) in piece.iter_blocks() {
-                        if y >= 0 {
-                            renderer.move_to((x as u16 * 2) + 1, y as u16 + 1)?;
-                            renderer.print("  ")?;
-                        }
-                    }
-                }
-            }
+    renderer.flush()
+}
 
-            // --- 描画フェーズ ---
-            let blink_state = if let Some(Animation::LineBlink { lines, count, .. }) = state
-                .animation
-                .iter()
-                .find(|a| matches!(a, Animation::LineBlink { .. }))
-            {
-                Some((lines, count))
-            } else {
-                None
-            };
+/// 盤面の表示形式（ハーフブロック表示でグループを表示中なら通常の盤面に、連結数を表示中なら
+/// 1マス1文字の表示に切り替える）
+fn board_style(state: &GameState) -> BoardStyle {
+    match state.board_style {
+        BoardStyle::HalfBlock if state.show_groups => BoardStyle::Blocks,
+        BoardStyle::HalfBlock if state.show_counts => BoardStyle::Counts,
+        style => style,
+    }
+}
 
-            for (y, row) in state.board.iter().enumerate() {
-                // Handle blinking lines
-                if let Some((blinking_lines, count)) = blink_state {
-                    if blinking_lines.contains(&y) {
-                        let prev_anim_count = if let Some(Animation::LineBlink { count, .. }) =
-                            prev_state
-                                .animation
-                                .iter()
-                                .find(|a| matches!(a, Animation::LineBlink { .. }))
-                        {
-                            Some(count)
-                        } else {
-                            None
-                        };
-
-                        // Redraw if the blink on/off state has changed, or if animation just started.
-                        if prev_anim_count.is_none()
-                            || (prev_anim_count.unwrap_or(&0) % 2 != *count % 2)
-                        {
-                            for x in 0..BOARD_WIDTH {
-                                renderer.move_to((x as u16 * 2) + 1, y as u16 + 1)?;
-                                if count % 2 == 0 {
-                                    // "On" state
-                                    if let Cell::Occupied(color) = state.board[y][x] {
-                                        renderer.set_foreground_color(color)?;
-                                        renderer.print("[]")?;
-                                        renderer.reset_color()?;
-                                    } else if let Cell::Connected { color, count } =
-                                        state.board[y][x]
-                                    {
-                                        draw_connected_cell(
-                                            renderer,
-                                            color,
-                                            count,
-                                            (x as u16 * 2) + 1,
-                                            y as u16 + 1,
-                                        )?;
-                                    } else {
-                                        renderer.print("  ")?;
-                                    }
-                                } else {
-                                    // "Off" state
-                                    renderer.print("  ")?;
-                                }
-                            }
-                        }
-                        continue; // Done with this row
-                    }
-                }
+/// 文字セットごとの枠・ハーフブロック・塗りつぶしの文字
+struct Symbols {
+    top_left: &'static str,
+    top_right: &'static str,
+    bottom_left: &'static str,
+    bottom_right: &'static str,
+    vertical: &'static str,
+    horizontal: &'static str,
+    /// ハーフブロック表示で上のマスだけ・下のマスだけ・両方（前景色が上、背景色が下）
+    upper_half: &'static str,
+    lower_half: &'static str,
+    both_halves: &'static str,
+    /// 色を送らない端末で上下両方のマスを描く文字（背景色で下のマスを示せないため）
+    both_halves_plain: &'static str,
+    /// 1マス1文字の表示での、連結ブロック以外のブロック
+    filled: &'static str,
+    /// 連結グループの輪郭で、上の辺・下の辺・上下両方の辺
+    edge_top: &'static str,
+    edge_bottom: &'static str,
+    edge_both: &'static str,
+}
 
-                // Default drawing for non-blinking lines
-                for (x, &cell) in row.iter().enumerate() {
-                    let pos = (x as i8, y as i8);
-                    let was_ghost = prev_state
-                        .ghost_piece()
-                        .as_ref()
-                        .is_some_and(|g| g.iter_blocks().any(|(p, _)| p == pos));
-                    let was_piece = prev_state
-                        .current_piece
-                        .as_ref()
-                        .is_some_and(|p| p.iter_blocks().any(|(p, _)| p == pos));
-
-                    if cell != prev_state.board[y][x]
-                        || ((was_ghost || was_piece) && cell != Cell::Empty)
-                    {
-                        renderer.move_to((x as u16 * 2) + 1, y as u16 + 1)?;
-                        match cell {
-                            Cell::Empty => renderer.print("  ")?,
-                            Cell::Occupied(color) => {
-                                renderer.set_foreground_color(color)?;
-                                renderer.print("[]")?;
-                                renderer.reset_color()?;
-                            }
-                            Cell::Solid => {
-                                renderer.set_foreground_color(GameColor::Grey)?;
-                                renderer.print("[]")?;
-                                renderer.reset_color()?;
-                            }
-                            Cell::Connected { color, count } => {
-                                draw_connected_cell(
-                                    renderer,
-                                    color,
-                                    count,
-                                    (x as u16 * 2) + 1,
-                                    y as u16 + 1,
-                                )?;
-                            }
-                        }
+impl Symbols {
+    fn for_charset(charset: Charset) -> Symbols {
+        match charset {
+            Charset::Unicode => Symbols {
+                top_left: "┌",
+                top_right: "┐",
+                bottom_left: "└",
+                bottom_right: "┘",
+                vertical: "│",
+                horizontal: "─",
+                upper_half: "▀",
+                lower_half: "▄",
+                both_halves: "▀",
+                both_halves_plain: "█",
+                filled: "█",
+                edge_top: "▔",
+                edge_bottom: "▁",
+                edge_both: "═",
+            },
+            Charset::Ascii => Symbols {
+                top_left: "+",
+                top_right: "+",
+                bottom_left: "+",
+                bottom_right: "+",
+                vertical: "|",
+                horizontal: "-",
+                upper_half: "'",
+                lower_half: ".",
+                both_halves: ":",
+                both_halves_plain: ":",
+                filled: "@",
+                edge_top: "-",
+                edge_bottom: "_",
+                edge_both: "=",
+            },
+        }
+    }
+}
+
+/// 盤面の枠
+fn draw_frame<R: Renderer>(renderer: &mut R, layout: &Layout) -> io::Result<()> {
+    let (left, top) = layout.board;
+    let (frame_width, frame_height) = layout.board_style.frame_size();
+    let right = left + frame_width - 1;
+    let bottom = top + frame_height - 1;
+    let symbols = Symbols::for_charset(renderer.theme().charset);
+    let edge = symbols
+        .horizontal
+        .repeat(layout.board_style.cell_width() as usize);
+    let frame_color = renderer.theme().frame;
+    renderer.set_foreground_color(frame_color)?;
+    renderer.move_to(left, top)?;
+    renderer.print(symbols.top_left)?;
+    renderer.move_to(right, top)?;
+    renderer.print(symbols.top_right)?;
+    renderer.move_to(left, bottom)?;
+    renderer.print(symbols.bottom_left)?;
+    renderer.move_to(right, bottom)?;
+    renderer.print(symbols.bottom_right)?;
+    for y in top + 1..bottom {
+        renderer.move_to(left, y)?;
+        renderer.print(symbols.vertical)?;
+        renderer.move_to(right, y)?;
+        renderer.print(symbols.vertical)?;
+    }
+    for x in 0..BOARD_WIDTH {
+        let (screen_x, _) = layout.cell_position(x, 0);
+        renderer.move_to(screen_x, top)?;
+        renderer.print(&edge)?;
+        renderer.move_to(screen_x, bottom)?;
+        renderer.print(&edge)?;
+    }
+    renderer.reset_color()
+}
+
+/// 点滅中のラインのうち、消灯（countが奇数）しているライン
+fn blink_off_lines(state: &GameState) -> Option<&Vec<usize>> {
+    state
+        .animation
+        .iter()
+        .find_map(|animation| match animation {
+            Animation::LineBlink { lines, count, .. } if count % 2 == 1 => Some(lines),
+            _ => None,
+        })
+}
+
+/// 盤面のセル・ゴースト・ヒント・操作中のピース
+fn draw_board<R: Renderer>(renderer: &mut R, layout: &Layout, state: &GameState) -> io::Result<()> {
+    match layout.board_style {
+        BoardStyle::Blocks => {}
+        BoardStyle::HalfBlock => return draw_half_block_board(renderer, layout, state),
+        BoardStyle::Counts => return draw_counts_board(renderer, layout, state),
+    }
+    // 点滅中のラインは、消灯の間は空白にする
+    let blink_off_lines = blink_off_lines(state);
+    let groups = state.show_groups.then(|| group_overlay(&state.board));
+
+    for (y, row) in state.board.iter().enumerate() {
+        let blinking_off = blink_off_lines.is_some_and(|lines| lines.contains(&y));
+        for (x, &cell) in row.iter().enumerate() {
+            let (screen_x, screen_y) = layout.cell_position(x, y);
+            renderer.move_to(screen_x, screen_y)?;
+            match cell {
+                _ if blinking_off => renderer.print("  ")?,
+                Cell::Connected { color, .. } if groups.is_some() => {
+                    let group_cell = groups.as_ref().and_then(|groups| groups[y][x]);
+                    match group_cell {
+                        Some(group_cell) => draw_group_cell(renderer, color, group_cell)?,
+                        None => draw_block(renderer, cell)?,
                     }
                 }
+                Cell::Connected { color, count } => {
+                    draw_connected_cell(renderer, color, count, screen_x, screen_y)?;
+                }
+                _ => draw_block(renderer, cell)?,
             }
+        }
+    }
 
-            if let Some(ghost) = &state.ghost_piece() {
-                if Some(ghost) != state.current_piece.as_ref() {
-                    for ((x, y), color) in ghost.iter_blocks() {
-                        // color を取得
-                        if y >= 0 && state.board[y as usize][x as usize] == Cell::Empty {
-                            renderer.move_to((x as u16 * 2) + 1, y as u16 + 1)?;
-                            renderer.set_foreground_color(color)?;
-                            renderer.print("::")?;
-                        }
-                    }
+    if let Some(ghost) = &state.ghost_piece() {
+        if Some(ghost) != state.current_piece.as_ref() {
+            for ((x, y), cell) in ghost.iter_cells() {
+                // 着地するブロックの色からテーマのゴーストの色を求める
+                let color = ghost_color(cell, renderer.theme());
+                let glyph = if renderer.theme().glyphs {
+                    Pattern::for_color(block_style(cell, renderer.theme()).1).ghost()
+                } else {
+                    "::"
+                };
+                if y >= 0 && state.board[y as usize][x as usize] == Cell::Empty {
+                    let (screen_x, screen_y) = layout.cell_position(x as usize, y as usize);
+                    renderer.move_to(screen_x, screen_y)?;
+                    renderer.set_foreground_color(color)?;
+                    renderer.print(glyph)?;
                 }
             }
+        }
+    }
 
-            if let Some(piece) = &state.current_piece {
-                for ((x, y), color) in piece.iter_blocks() {
-                    if y >= 0 {
-                        renderer.move_to((x as u16 * 2) + 1, y as u16 + 1)?;
-                        renderer.set_foreground_color(color)?;
-                        renderer.print("[]")?;
-                        renderer.reset_color()?;
-                    }
-                }
+    // ヒントの配置はゴースト・ブロックと区別できるように"<>"で描く
+    if let Some(hint) = &state.hint {
+        for ((x, y), cell) in hint.placement.piece.iter_cells() {
+            let (_, color) = block_style(cell, renderer.theme());
+            if y >= 0 && state.board[y as usize][x as usize] == Cell::Empty {
+                let (screen_x, screen_y) = layout.cell_position(x as usize, y as usize);
+                renderer.move_to(screen_x, screen_y)?;
+                renderer.set_foreground_color(color)?;
+                renderer.print("<>")?;
             }
+        }
+    }
+
+    if let Some(piece) = &state.current_piece {
+        for ((x, y), cell) in piece.iter_cells() {
+            if y >= 0 {
+                let (screen_x, screen_y) = layout.cell_position(x as usize, y as usize);
+                renderer.move_to(screen_x, screen_y)?;
+                draw_block(renderer, cell)?;
+            }
+        }
+    }
+    renderer.reset_color()
+}
 
-            let ui_x = (BOARD_WIDTH * 2 + 4) as u16;
+/// 連結グループの輪郭になる辺
+const EDGE_LEFT: u8 = 1;
+const EDGE_RIGHT: u8 = 2;
+const EDGE_TOP: u8 = 4;
+const EDGE_BOTTOM: u8 = 8;
+
+/// CHAIN-BONUSが1段増えるグループの大きさ（`board_logic::calculate_chain_bonus`と同じ）
+const BONUS_GROUP_SIZE: usize = 10;
+
+/// 次のボーナスまでの残りがこれ以下のグループを強調する（8・9個、18・19個など）
+const NEAR_BONUS_REMAINING: usize = 2;
+
+/// グループ表示での連結ブロックの1マス
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct GroupCell {
+    /// 輪郭になる辺（`EDGE_*`の組み合わせ）
+    edges: u8,
+    /// 次のボーナスまでに必要な残りブロック数（グループの左上のマスだけに表示する）
+    remaining: Option<usize>,
+    /// 次のボーナスが近い
+    near_bonus: bool,
+}
 
-            let score_changed =
-                prev_state.custom_score_system.score != state.custom_score_system.score;
-            if score_changed {
-                renderer.set_foreground_color(GameColor::White)?;
-                renderer.move_to(ui_x, 2)?;
-                renderer.print(
-                    format_ui_value("SCORE:", state.custom_score_system.score.total()).as_str(),
-                )?;
-                renderer.reset_color()?;
+/// 2個以上の同色グループに属する各マスの輪郭とボーナスまでの残り
+/// ワイルドカードは複数のグループに属し得るので、輪郭を描かずに通常の表示のままにする
+fn group_overlay(board: &Board) -> Vec<Vec<Option<GroupCell>>> {
+    let groups = board_logic::find_color_groups(board, BOARD_HEIGHT, &[]);
+    let mut group_ids = vec![vec![None; BOARD_WIDTH]; BOARD_HEIGHT];
+    for (id, group) in groups.iter().enumerate() {
+        for &(x, y) in &group.cells {
+            if board[y][x] != Cell::Wildcard {
+                group_ids[y][x] = Some(id);
             }
+        }
+    }
 
-            let chain_bonus_changed = prev_state.custom_score_system.chain_bonus
-                != state.custom_score_system.chain_bonus;
-            if chain_bonus_changed {
-                render_chain_bonus_value(
-                    renderer,
-                    ui_x,
-                    4,
-                    state.custom_score_system.chain_bonus,
-                )?;
+    let mut overlay = vec![vec![None; BOARD_WIDTH]; BOARD_HEIGHT];
+    for (id, group) in groups.iter().enumerate() {
+        if group.cells.len() < 2 {
+            continue;
+        }
+        let remaining = BONUS_GROUP_SIZE - group.cells.len() % BONUS_GROUP_SIZE;
+        // 探索は上の行の左から始まるので、最初のマスがグループの左上になる
+        let anchor = group.cells[0];
+        for &(x, y) in &group.cells {
+            if group_ids[y][x] != Some(id) {
+                continue;
             }
+            let same_group = |dx: isize, dy: isize| {
+                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                (0..BOARD_WIDTH as isize).contains(&nx)
+                    && (0..BOARD_HEIGHT as isize).contains(&ny)
+                    && group_ids[ny as usize][nx as usize] == Some(id)
+            };
+            let edges = [
+                (EDGE_LEFT, -1, 0),
+                (EDGE_RIGHT, 1, 0),
+                (EDGE_TOP, 0, -1),
+                (EDGE_BOTTOM, 0, 1),
+            ]
+            .iter()
+            .filter(|&&(_, dx, dy)| !same_group(dx, dy))
+            .fold(0, |edges, &(edge, _, _)| edges | edge);
+            overlay[y][x] = Some(GroupCell {
+                edges,
+                remaining: ((x, y) == anchor).then_some(remaining),
+                near_bonus: remaining <= NEAR_BONUS_REMAINING,
+            });
+        }
+    }
+    overlay
+}
 
-            let max_chain_changed =
-                prev_state.custom_score_system.max_chains != state.custom_score_system.max_chains;
-            if max_chain_changed {
-                renderer.set_foreground_color(GameColor::White)?;
-                renderer.move_to(ui_x, 5)?;
-                renderer.print(
-                    format!("{:<width$}", "MAX-CHAIN:", width = UI_LINE_WIDTH).as_str(),
-                )?;
-                renderer.move_to(ui_x, 6)?;
-                renderer.print(
-                    format_ui_value("  CYAN:", state.custom_score_system.max_chains.cyan).as_str(),
-                )?;
-                renderer.move_to(ui_x, 7)?;
-                renderer.print(
-                    format_ui_value(
-                        "  MAGENTA:",
-                        state.custom_score_system.max_chains.magenta
-                    )
-                    .as_str(),
-                )?;
-                renderer.move_to(ui_x, 8)?;
-                renderer.print(
-                    format_ui_value("  YELLOW:", state.custom_score_system.max_chains.yellow)
-                        .as_str(),
-                )?;
-                renderer.reset_color()?;
+/// 輪郭の辺から1マス分（2文字）の表示を作る
+fn group_outline(symbols: &Symbols, edges: u8) -> String {
+    let (top, bottom) = (edges & EDGE_TOP != 0, edges & EDGE_BOTTOM != 0);
+    let horizontal = match (top, bottom) {
+        (true, true) => symbols.edge_both,
+        (true, false) => symbols.edge_top,
+        (false, true) => symbols.edge_bottom,
+        (false, false) => " ",
+    };
+    let side = |edge: u8, corners: [&'static str; 3]| match (edges & edge != 0, top, bottom) {
+        (false, _, _) => horizontal,
+        (true, true, true) => corners[0],
+        (true, true, false) => corners[1],
+        (true, false, true) => corners[2],
+        (true, false, false) => symbols.vertical,
+    };
+    format!(
+        "{}{}",
+        side(EDGE_LEFT, ["[", symbols.top_left, symbols.bottom_left]),
+        side(EDGE_RIGHT, ["]", symbols.top_right, symbols.bottom_right])
+    )
+}
+
+/// グループ表示の連結ブロック。次のボーナスが近いグループは色を反転して輪郭だけを色で描く
+fn draw_group_cell<R: Renderer>(
+    renderer: &mut R,
+    color: GameColor,
+    group_cell: GroupCell,
+) -> io::Result<()> {
+    let theme = *renderer.theme();
+    let (background, foreground) = if group_cell.near_bonus {
+        (theme.connected_text, theme.connected.resolve(color))
+    } else {
+        (theme.connected.resolve(color), theme.connected_text)
+    };
+    renderer.set_background_color(background)?;
+    renderer.set_foreground_color(foreground)?;
+    match group_cell.remaining {
+        Some(remaining) => renderer.print(&format!("{:>2}", remaining))?,
+        None => renderer.print(&group_outline(
+            &Symbols::for_charset(theme.charset),
+            group_cell.edges,
+        ))?,
+    }
+    renderer.reset_color()
+}
+
+/// 1文字で描く表示形式での盤面の1マス（ゴースト・ヒント・操作中のピースを重ねたもの）
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BoardView {
+    Block(Cell),
+    Ghost(GameColor),
+    Hint(GameColor),
+}
+
+/// ゴーストの色（着地するブロックの色からテーマで決める）
+fn ghost_color(cell: Cell, theme: &Theme) -> GameColor {
+    theme.ghost.resolve(block_style(cell, theme).1)
+}
+
+/// 盤面にゴースト・ヒント・操作中のピースを重ねた各マスの表示（空きマスはNone）
+fn compose_board_view(state: &GameState, theme: &Theme) -> Vec<Vec<Option<BoardView>>> {
+    let blink_off_lines = blink_off_lines(state);
+    let mut view: Vec<Vec<Option<BoardView>>> = state
+        .board
+        .iter()
+        .enumerate()
+        .map(|(y, row)| {
+            let blinking_off = blink_off_lines.is_some_and(|lines| lines.contains(&y));
+            row.iter()
+                .map(|&cell| {
+                    (cell != Cell::Empty && !blinking_off).then_some(BoardView::Block(cell))
+                })
+                .collect()
+        })
+        .collect();
+
+    let ghost = state
+        .ghost_piece()
+        .filter(|ghost| Some(ghost) != state.current_piece.as_ref());
+    let overlays = ghost
+        .iter()
+        .flat_map(|ghost| ghost.iter_cells())
+        .map(|(position, cell)| (position, BoardView::Ghost(ghost_color(cell, theme))))
+        .chain(state.hint.iter().flat_map(|hint| {
+            hint.placement
+                .piece
+                .iter_cells()
+                .map(|(position, cell)| (position, BoardView::Hint(block_style(cell, theme).1)))
+        }));
+    for ((x, y), overlay) in overlays {
+        // ゴーストとヒントは盤面の空きマスにだけ描く
+        if y >= 0 && state.board[y as usize][x as usize] == Cell::Empty {
+            view[y as usize][x as usize] = Some(overlay);
+        }
+    }
+
+    if let Some(piece) = &state.current_piece {
+        for ((x, y), cell) in piece.iter_cells() {
+            if y >= 0 {
+                view[y as usize][x as usize] = Some(BoardView::Block(cell));
             }
+        }
+    }
+    view
+}
 
-            // NEXTミノの描画
-            let next_piece_offset_x = ui_x;
-            let next_piece_offset_y = 14; // NEXT:ラベルの下
-
-            // 以前のNEXTミノをクリア
-            if let Some(prev_next_piece) = &prev_state.next_piece {
-                if prev_state.next_piece != state.next_piece {
-                    // NEXTミノが変更された場合、以前の位置をクリア
-                    for ((x, y), _) in prev_next_piece.iter_blocks() {
-                        let draw_x = next_piece_offset_x + (x as u16 * 2);
-                        let draw_y = next_piece_offset_y + y as u16;
-                        renderer.move_to(draw_x, draw_y)?;
-                        renderer.print("  ")?;
-                    }
+/// ハーフブロック表示での1マスの色（ゴーストは暗い色、ヒントは白で示す）
+fn half_block_color(view: BoardView, theme: &Theme) -> GameColor {
+    match view {
+        // SolidとGarbageを見分けられるよう、Garbageは暗い灰色にする
+        BoardView::Block(Cell::Garbage) => GameColor::DarkGrey,
+        BoardView::Block(Cell::Connected { color, .. }) => theme.connected.resolve(color),
+        BoardView::Block(cell) => block_style(cell, theme).1,
+        BoardView::Ghost(color) => theme::dark_variant(color),
+        BoardView::Hint(_) => GameColor::White,
+    }
+}
+
+/// 上下2マスを1文字にまとめて描く（上のマスは`▀`の前景色、下のマスは背景色。ASCIIでは`'` `.` `:`）
+fn draw_half_block_board<R: Renderer>(
+    renderer: &mut R,
+    layout: &Layout,
+    state: &GameState,
+) -> io::Result<()> {
+    let theme = *renderer.theme();
+    let symbols = Symbols::for_charset(theme.charset);
+    let color = |view: BoardView| half_block_color(view, &theme);
+    let view = compose_board_view(state, &theme);
+    for (pair_index, pair) in view.chunks(2).enumerate() {
+        for x in 0..BOARD_WIDTH {
+            let top = pair[0][x].map(color);
+            let bottom = pair.get(1).and_then(|row| row[x]).map(color);
+            let (screen_x, screen_y) = layout.cell_position(x, pair_index * 2);
+            renderer.move_to(screen_x, screen_y)?;
+            match (top, bottom) {
+                (None, None) => renderer.print(" ")?,
+                (Some(top), None) => {
+                    renderer.set_foreground_color(top)?;
+                    renderer.print(symbols.upper_half)?;
+                }
+                (None, Some(bottom)) => {
+                    renderer.set_foreground_color(bottom)?;
+                    renderer.print(symbols.lower_half)?;
+                }
+                (Some(top), Some(bottom)) => {
+                    renderer.set_foreground_color(top)?;
+                    renderer.set_background_color(bottom)?;
+                    renderer.print(if theme.monochrome {
+                        symbols.both_halves_plain
+                    } else {
+                        symbols.both_halves
+                    })?;
                 }
             }
+            renderer.reset_color()?;
+        }
+    }
+    Ok(())
+}
 
-            // 現在のNEXTミノを描画
-            if let Some(next_piece) = &state.next_piece {
-                renderer.set_foreground_color(GameColor::White)?;
-                renderer.move_to(ui_x, 13)?;
-                renderer.print("NEXT:")?; // "NEXT:" ラベル
-
-                for ((x, y), color) in next_piece.iter_blocks() {
-                    // ミノの座標を調整してUI領域に描画
-                    let draw_x = next_piece_offset_x + (x as u16 * 2);
-                    let draw_y = next_piece_offset_y + y as u16;
-                    renderer.move_to(draw_x, draw_y)?;
+/// 1マス1文字で描き、連結ブロックには連結数（10以上は`+`）を表示する
+fn draw_counts_board<R: Renderer>(
+    renderer: &mut R,
+    layout: &Layout,
+    state: &GameState,
+) -> io::Result<()> {
+    let theme = *renderer.theme();
+    for (y, row) in compose_board_view(state, &theme).iter().enumerate() {
+        for (x, view) in row.iter().enumerate() {
+            let (screen_x, screen_y) = layout.cell_position(x, y);
+            renderer.move_to(screen_x, screen_y)?;
+            match *view {
+                None => renderer.print(" ")?,
+                Some(BoardView::Block(Cell::Connected { color, count })) => {
+                    renderer.set_background_color(theme.connected.resolve(color))?;
+                    renderer.set_foreground_color(theme.connected_text)?;
+                    let count = char::from_digit(count as u32, 10).unwrap_or('+');
+                    renderer.print(&count.to_string())?;
+                }
+                Some(BoardView::Block(cell)) => {
+                    let (glyph, color) = block_style(cell, &theme);
+                    let glyph = match cell {
+                        Cell::Wildcard | Cell::Bomb | Cell::Garbage => &glyph[..1],
+                        _ if theme.glyphs => &glyph[..1],
+                        _ => Symbols::for_charset(theme.charset).filled,
+                    };
                     renderer.set_foreground_color(color)?;
-                    renderer.print("[]")?;
-                    renderer.reset_color()?;
+                    renderer.print(glyph)?;
+                }
+                Some(BoardView::Ghost(color)) => {
+                    renderer.set_foreground_color(color)?;
+                    renderer.print(":")?;
+                }
+                Some(BoardView::Hint(color)) => {
+                    renderer.set_foreground_color(color)?;
+                    renderer.print("<")?;
                 }
             }
+            renderer.reset_color()?;
         }
-        GameMode::GameOver => {
-            if prev_state.mode != GameMode::GameOver {
-                let msg = "GAME OVER";
-                let x = (BOARD_WIDTH * 2 + 3 - msg.len()) as u16 / 2;
-                let y = (BOARD_HEIGHT / 2) as u16;
-                renderer.set_foreground_color(GameColor::Red)?;
-                renderer.move_to(x, y)?;
-                renderer.print(msg)?;
-            }
+    }
+    Ok(())
+}
+
+/// NEXTの見出しとミノ（ミノは見出しの行から、出現位置の列に合わせて描く）
+fn draw_next_piece<R: Renderer>(
+    renderer: &mut R,
+    ui_x: u16,
+    label_y: u16,
+    piece_y: u16,
+    state: &GameState,
+) -> io::Result<()> {
+    let Some(next_piece) = &state.next_piece else {
+        return Ok(());
+    };
+    renderer.set_foreground_color(GameColor::White)?;
+    renderer.move_to(ui_x, label_y)?;
+    renderer.print("NEXT:")?; // "NEXT:" ラベル
+    renderer.reset_color()?;
+
+    for ((x, y), cell) in next_piece.iter_cells() {
+        // ミノの座標を調整してUI領域に描画
+        renderer.move_to(ui_x + (x as u16 * 2), piece_y + y as u16)?;
+        draw_block(renderer, cell)?;
+    }
+    Ok(())
+}
+
+/// 盤面の右側の表示（HUD・スコア・連結・コンボ・NEXT・内訳・ヒント）
+fn draw_sidebar<R: Renderer>(
+    renderer: &mut R,
+    layout: &Layout,
+    state: &GameState,
+) -> io::Result<()> {
+    let (ui_x, top) = layout.sidebar;
+    let score_system = &state.custom_score_system;
+
+    render_mode_hud(renderer, ui_x, top, &mode_hud_lines(state))?;
+
+    if state.show_stats {
+        // 統計表示中はSCORE〜MAX-CHAINの欄に統計を並べる
+        render_stats_lines(renderer, ui_x, top + 2, &stats_lines(state))?;
+    } else {
+        renderer.set_foreground_color(GameColor::White)?;
+        renderer.move_to(ui_x, top + 2)?;
+        renderer.print(format_ui_value("SCORE:", score_system.score.total()).as_str())?;
+        renderer.reset_color()?;
+
+        render_chain_bonus_value(renderer, ui_x, top + 4, score_system.chain_bonus)?;
+
+        renderer.set_foreground_color(GameColor::White)?;
+        renderer.move_to(ui_x, top + 5)?;
+        renderer.print(format!("{:<width$}", "MAX-CHAIN:", width = UI_LINE_WIDTH).as_str())?;
+        renderer.move_to(ui_x, top + 6)?;
+        renderer.print(format_ui_value("  CYAN:", score_system.max_chains.cyan).as_str())?;
+        renderer.move_to(ui_x, top + 7)?;
+        renderer.print(format_ui_value("  MAGENTA:", score_system.max_chains.magenta).as_str())?;
+        renderer.move_to(ui_x, top + 8)?;
+        renderer.print(format_ui_value("  YELLOW:", score_system.max_chains.yellow).as_str())?;
+        renderer.reset_color()?;
+    }
+
+    render_combo_values(renderer, ui_x, top + 10, score_system)?;
+    if state.solid_purge_mode == SolidPurgeMode::Manual {
+        render_purge_value(renderer, ui_x, top + 12, state)?;
+    }
+
+    // NEXT:ラベルの下にミノを描く
+    draw_next_piece(renderer, ui_x, top + 13, top + 14, state)?;
+
+    render_last_lock_breakdown(renderer, ui_x, top + 18, score_system.last_lock.as_ref())?;
+    render_hint_reasons(renderer, ui_x, top + 22, state.hint.as_ref())
+}
+
+/// 高さの足りない端末用に、項目を1行ずつに詰めたサイドバー
+fn draw_condensed_sidebar<R: Renderer>(
+    renderer: &mut R,
+    layout: &Layout,
+    state: &GameState,
+) -> io::Result<()> {
+    let (ui_x, top) = layout.sidebar;
+    let score_system = &state.custom_score_system;
+    let max_chains = &score_system.max_chains;
+    let back_to_back = if score_system.back_to_back {
+        "ON"
+    } else {
+        "OFF"
+    };
+    let last_lock = score_system
+        .last_lock
+        .as_ref()
+        .map_or(0, LockScoreBreakdown::total);
+
+    render_mode_hud(renderer, ui_x, top, &mode_hud_lines(state))?;
+    if state.show_stats {
+        render_stats_lines(renderer, ui_x, top + 2, &condensed_stats_lines(state))?;
+        if state.solid_purge_mode == SolidPurgeMode::Manual {
+            render_purge_value(renderer, ui_x, top + 7, state)?;
         }
+        draw_next_piece(renderer, ui_x, top + 8, top + 8, state)?;
+        return render_hint_reasons(renderer, ui_x, top + 10, state.hint.as_ref());
+    }
+    let lines = [
+        format_ui_value("SCORE:", score_system.score.total()),
+        format_ui_value("10-CHAIN:", score_system.chain_bonus),
+        format_ui_text(
+            "MAX C/M/Y:",
+            &format!(
+                "{}/{}/{}",
+                max_chains.cyan, max_chains.magenta, max_chains.yellow
+            ),
+        ),
+        format_ui_text(
+            "COMBO/B2B:",
+            &format!("{}/{}", score_system.combo, back_to_back),
+        ),
+        format_ui_value("LAST LOCK:", last_lock),
+    ];
+    render_stats_lines(renderer, ui_x, top + 2, &lines)?;
+    if state.solid_purge_mode == SolidPurgeMode::Manual {
+        render_purge_value(renderer, ui_x, top + 7, state)?;
     }
 
-    renderer.flush()
+    // NEXT:ラベルと同じ行から、ラベルの右にミノを描く
+    draw_next_piece(renderer, ui_x, top + 8, top + 8, state)?;
+    render_hint_reasons(renderer, ui_x, top + 10, state.hint.as_ref())
 }
@@ -1,3 +1,4 @@
+use crate::config::{BACK_TO_BACK_PERCENT, COMBO_STEP_PERCENT, DIFFICULT_CLEAR_LINES};
 use crate::game_color::GameColor;
 use std::fmt;
 
@@ -78,20 +79,86 @@ impl ColorMaxChains {
     }
 }
 
+/// CHAIN-BONUSでSolidラインを消去するタイミング
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SolidPurgeMode {
+    /// 底にSolidラインがあれば自動的に消費する
+    #[default]
+    Automatic,
+    /// プレイヤーのSolid purge入力で、消去する段数とタイミングを選ぶ
+    Manual,
+}
+
+/// コンボ・Back-to-Backボーナスの倍率設定
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreMultipliers {
+    /// コンボ1段ごとに加算されるボーナス（基本点に対する%）
+    pub combo_step_percent: u32,
+    /// Back-to-Back成立時に加算されるボーナス（基本点に対する%）
+    pub back_to_back_percent: u32,
+    /// 1ロックでこの本数以上のラインを消すと「難しい消去」とみなす
+    pub difficult_clear_lines: u32,
+}
+
+impl Default for ScoreMultipliers {
+    fn default() -> Self {
+        Self {
+            combo_step_percent: COMBO_STEP_PERCENT,
+            back_to_back_percent: BACK_TO_BACK_PERCENT,
+            difficult_clear_lines: DIFFICULT_CLEAR_LINES,
+        }
+    }
+}
+
+/// 1回のロックで獲得したスコアの内訳
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LockScoreBreakdown {
+    pub lines_cleared: u32,
+    pub base_points: u32,
+    pub combo: u32,
+    pub combo_bonus: u32,
+    pub back_to_back: bool,
+    pub back_to_back_bonus: u32,
+}
+
+impl LockScoreBreakdown {
+    /// ボーナスを含めた合計点
+    pub fn total(&self) -> u32 {
+        self.base_points + self.combo_bonus + self.back_to_back_bonus
+    }
+}
+
 /// カスタムスコアシステム全体を管理する構造体
 #[derive(Clone, Debug, PartialEq)]
 pub struct CustomScoreSystem {
     pub score: TotalScore,
     pub max_chains: ColorMaxChains,
     pub chain_bonus: u32,
+    pub multipliers: ScoreMultipliers,
+    /// ライン消去を伴うロックが連続した回数（消去なしのロックで0に戻る）
+    pub combo: u32,
+    /// 直前のライン消去が「難しい消去」だったか
+    pub back_to_back: bool,
+    /// 直近のライン消去を伴うロックの内訳
+    pub last_lock: Option<LockScoreBreakdown>,
+    pending_lock: LockScoreBreakdown,
 }
 
 impl CustomScoreSystem {
     pub fn new() -> Self {
+        Self::with_multipliers(ScoreMultipliers::default())
+    }
+
+    pub fn with_multipliers(multipliers: ScoreMultipliers) -> Self {
         Self {
             score: TotalScore::new(),
             max_chains: ColorMaxChains::new(),
             chain_bonus: 0,
+            multipliers,
+            combo: 0,
+            back_to_back: false,
+            last_lock: None,
+            pending_lock: LockScoreBreakdown::default(),
         }
     }
 
@@ -99,6 +166,43 @@ impl CustomScoreSystem {
         self.score.add(points);
     }
 
+    /// 消去ライン1本分の基本点を加算し、現在のロックの内訳に記録する
+    pub fn add_line_clear_points(&mut self, points: u32) {
+        self.add_score(points);
+        self.pending_lock.lines_cleared += 1;
+        self.pending_lock.base_points += points;
+    }
+
+    /// ロックに伴うライン消去がすべて終わった時点で呼び出し、
+    /// コンボ・Back-to-Backのボーナスを加算して内訳を返す
+    pub fn finish_lock(&mut self) -> LockScoreBreakdown {
+        let mut breakdown = std::mem::take(&mut self.pending_lock);
+
+        if breakdown.lines_cleared == 0 {
+            self.combo = 0;
+            return breakdown;
+        }
+
+        self.combo += 1;
+        breakdown.combo = self.combo;
+        breakdown.combo_bonus = breakdown.base_points
+            * self.multipliers.combo_step_percent
+            * (self.combo - 1)
+            / 100;
+
+        let difficult = breakdown.lines_cleared >= self.multipliers.difficult_clear_lines;
+        if difficult && self.back_to_back {
+            breakdown.back_to_back = true;
+            breakdown.back_to_back_bonus =
+                breakdown.base_points * self.multipliers.back_to_back_percent / 100;
+        }
+        self.back_to_back = difficult;
+
+        self.add_score(breakdown.combo_bonus + breakdown.back_to_back_bonus);
+        self.last_lock = Some(breakdown.clone());
+        breakdown
+    }
+
     /// chain_bonusに段数を加算する（制限なし）。
     pub fn add_chain_bonus(&mut self, lines: u32) {
         self.chain_bonus += lines;
@@ -263,4 +367,74 @@ mod tests {
         let expected = "SCORE:    1120\n\nMAX-CHAIN:\n  CYAN:    2\n  MAGENTA: 4\n  YELLOW:  5";
         assert_eq!(format!("{}", system), expected);
     }
+
+    #[test]
+    fn test_lock_without_clear_resets_combo() {
+        let mut system = CustomScoreSystem::new();
+        system.add_line_clear_points(100);
+        system.finish_lock();
+        assert_eq!(system.combo, 1);
+
+        let breakdown = system.finish_lock();
+        assert_eq!(breakdown, LockScoreBreakdown::default());
+        assert_eq!(system.combo, 0);
+        assert_eq!(system.score.total(), 100);
+    }
+
+    #[test]
+    fn test_consecutive_clears_apply_combo_bonus() {
+        let mut system = CustomScoreSystem::new();
+
+        system.add_line_clear_points(100);
+        let first = system.finish_lock();
+        assert_eq!(first.combo, 1);
+        assert_eq!(first.combo_bonus, 0);
+
+        // 2連続目: 基本点の50%
+        system.add_line_clear_points(200);
+        let second = system.finish_lock();
+        assert_eq!(second.combo, 2);
+        assert_eq!(second.combo_bonus, 100);
+
+        // 3連続目: 基本点の100%
+        system.add_line_clear_points(200);
+        let third = system.finish_lock();
+        assert_eq!(third.combo_bonus, 200);
+        assert_eq!(third.total(), 400);
+
+        assert_eq!(system.score.total(), 100 + 300 + 400);
+        assert_eq!(system.last_lock, Some(third));
+    }
+
+    #[test]
+    fn test_back_to_back_requires_consecutive_difficult_clears() {
+        let mut system = CustomScoreSystem::with_multipliers(ScoreMultipliers {
+            combo_step_percent: 0,
+            back_to_back_percent: 50,
+            difficult_clear_lines: 2,
+        });
+
+        // 1回目の難しい消去ではまだB2Bにならない
+        system.add_line_clear_points(100);
+        system.add_line_clear_points(100);
+        let first = system.finish_lock();
+        assert!(!first.back_to_back);
+        assert!(system.back_to_back);
+
+        // 消去なしのロックはB2Bを途切れさせない
+        system.finish_lock();
+        assert!(system.back_to_back);
+
+        system.add_line_clear_points(100);
+        system.add_line_clear_points(100);
+        let second = system.finish_lock();
+        assert!(second.back_to_back);
+        assert_eq!(second.back_to_back_bonus, 100);
+
+        // 1本消しでB2Bが途切れる
+        system.add_line_clear_points(100);
+        let third = system.finish_lock();
+        assert!(!third.back_to_back);
+        assert!(!system.back_to_back);
+    }
 }
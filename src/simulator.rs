@@ -0,0 +1,248 @@
+//! 配置結果の即時シミュレーション
+//!
+//! ピースを1つ置いたときの結果を、アニメーションを待たずに一度に求めます。
+//! 連結・連結数・MAX-CHAINの更新、CHAIN-BONUSの計算、揃ったラインの段階的な処理
+//! （Bottom lineの除去とNon-bottom lineのSolid化、孤立ブロックの消去、Push Down）、
+//! CHAIN-BONUSによるSolidライン消去まで、ゲーム本体と同じ順序で進めます。
+//! 引数は変更せず、結果を新しい値として返します。
+
+use crate::animation::{self, PushDownStepResult};
+use crate::board_logic;
+use crate::cell::{Board, Cell};
+use crate::config::{BOARD_HEIGHT, BOARD_WIDTH};
+use crate::gravity::{self, GravityMode};
+use crate::scoring::{CustomScoreSystem, SolidPurgeMode};
+use crate::tetromino::Tetromino;
+
+/// 配置の解決に効くゲームルール
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SimulationRules {
+    pub gravity_mode: GravityMode,
+    pub solid_purge_mode: SolidPurgeMode,
+}
+
+/// 1つの配置を解決した後の状態
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationOutcome {
+    pub board: Board,
+    pub current_board_height: usize,
+    pub score: CustomScoreSystem,
+    /// この配置で消えたライン数（Solid化したラインを含む）
+    pub lines_cleared: u32,
+}
+
+/// 接地したピースを固定し、ライン消去がすべて終わった後の盤面とスコアを返す
+pub fn simulate_placement(
+    board: &Board,
+    current_board_height: usize,
+    score: &CustomScoreSystem,
+    piece: &Tetromino,
+    rules: SimulationRules,
+) -> SimulationOutcome {
+    let mut outcome = SimulationOutcome {
+        board: board.clone(),
+        current_board_height,
+        score: score.clone(),
+        lines_cleared: 0,
+    };
+    for ((x, y), cell) in piece.iter_cells() {
+        if y >= 0 && y < BOARD_HEIGHT as i8 {
+            outcome.board[y as usize][x as usize] = cell;
+        }
+    }
+    outcome.resolve_lines(rules);
+    outcome
+}
+
+impl SimulationOutcome {
+    /// 揃ったラインがなくなるまで、検出→消去→重力・Push Downを繰り返す
+    fn resolve_lines(&mut self, rules: SimulationRules) {
+        loop {
+            let lines = animation::find_complete_lines(&self.board, self.current_board_height);
+
+            // 隣接ブロックの接続（消去ラインは除外）と、連結数・MAX-CHAIN・CHAIN-BONUSの更新
+            board_logic::find_and_connect_adjacent_blocks(&mut self.board, &lines);
+            self.update_connected_block_counts();
+            self.update_max_chains();
+            let total_chain_bonus = board_logic::calculate_chain_bonus(&self.board);
+            self.score.set_chain_bonus_from_total(total_chain_bonus);
+
+            if lines.is_empty() {
+                // ロックに伴うライン消去が終わったのでコンボ・B2Bボーナスを確定
+                self.score.finish_lock();
+                return;
+            }
+
+            for &line_y in &lines {
+                let scores = animation::calculate_line_clear_score(
+                    &self.board,
+                    line_y,
+                    &self.score.max_chains,
+                );
+                let line_points = scores.iter().map(|(_, points)| points).sum();
+                self.score.add_line_clear_points(line_points);
+            }
+            self.lines_cleared += lines.len() as u32;
+
+            // LineBlink完了時の処理
+            let (_, solid_lines, _) = animation::process_line_clear(
+                &mut self.board,
+                self.current_board_height,
+                &lines,
+                rules.gravity_mode,
+            );
+            self.update_all_connected_block_counts();
+
+            if solid_lines.is_empty() {
+                if rules.gravity_mode == GravityMode::Cascade {
+                    // 支えのない塊が止まるまで1段ずつ落とす
+                    while gravity::step_gravity(&mut self.board, self.current_board_height) {
+                        self.update_all_connected_block_counts();
+                    }
+                    self.update_all_connected_block_counts();
+                }
+                continue;
+            }
+
+            if rules.solid_purge_mode == SolidPurgeMode::Automatic {
+                let solid_rows = self.count_bottom_solid_lines();
+                self.remove_bottom_solid_lines(solid_rows);
+            }
+
+            // Solid化したラインを、同時に1段ずつ底まで押し下げる
+            let mut pushing = solid_lines;
+            while !pushing.is_empty() {
+                let results = animation::process_push_down_steps(
+                    &mut self.board,
+                    &mut self.current_board_height,
+                    &pushing,
+                );
+                self.update_all_connected_block_counts();
+                pushing = results
+                    .into_iter()
+                    .filter_map(|result| match result {
+                        PushDownStepResult::Moved { new_solid_line_y } => Some(new_solid_line_y),
+                        PushDownStepResult::Completed => None,
+                    })
+                    .collect();
+            }
+        }
+    }
+
+    fn update_connected_block_counts(&mut self) {
+        for ((x, y), count) in board_logic::count_connected_blocks(&self.board, 0) {
+            if let Cell::Connected { color, .. } = self.board[y][x] {
+                self.board[y][x] = Cell::Connected {
+                    color,
+                    count: count as u8,
+                };
+            }
+        }
+    }
+
+    fn update_all_connected_block_counts(&mut self) {
+        let groups = board_logic::find_color_groups(&self.board, self.current_board_height, &[]);
+        for group in groups {
+            let count = group.cells.len() as u8;
+            for (x, y) in group.cells {
+                if let Cell::Connected { color, .. } = self.board[y][x] {
+                    self.board[y][x] = Cell::Connected { color, count };
+                }
+            }
+        }
+    }
+
+    fn update_max_chains(&mut self) {
+        for row in &self.board[..self.current_board_height] {
+            for &cell in row {
+                if let Cell::Connected { color, count } = cell {
+                    self.score.max_chains.update_max(color, count as u32);
+                }
+            }
+        }
+    }
+
+    /// 盤面の底に積み上がっているSolidラインの段数
+    fn count_bottom_solid_lines(&self) -> usize {
+        self.board[self.current_board_height..]
+            .iter()
+            .take_while(|row| row.iter().all(|&cell| cell == Cell::Solid))
+            .count()
+    }
+
+    /// CHAIN-BONUSを消費して底のSolidラインを最大`solid_lines`段消去する
+    fn remove_bottom_solid_lines(&mut self, solid_lines: usize) {
+        if solid_lines == 0 {
+            return;
+        }
+        let removable = self.score.consume_chain_bonus(solid_lines as u32) as usize;
+        let removable = removable.min(BOARD_HEIGHT - self.current_board_height);
+        for _ in 0..removable {
+            self.board.remove(self.current_board_height);
+            self.board.insert(0, vec![Cell::Empty; BOARD_WIDTH]);
+        }
+        self.current_board_height += removable;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board_notation::{board_to_text, parse_board};
+    use crate::game_color::GameColor;
+    use crate::tetromino::TetrominoShape;
+
+    fn i_piece(x: i8, y: i8, colors: [GameColor; 4]) -> Tetromino {
+        let mut piece = Tetromino::from_shape(TetrominoShape::I, colors);
+        piece.pos = (x, y - 1); // 状態0のIは2段目（y=1）に並ぶ
+        piece
+    }
+
+    #[test]
+    fn test_bottom_line_is_removed_and_scored() {
+        let (board, height) = parse_board("CMYCMY....").unwrap();
+        let piece = i_piece(6, (BOARD_HEIGHT - 1) as i8, [GameColor::Cyan; 4]);
+        let mut score = CustomScoreSystem::new();
+        score.max_chains.update_max(GameColor::Cyan, 3);
+        let outcome =
+            simulate_placement(&board, height, &score, &piece, SimulationRules::default());
+
+        assert_eq!(outcome.lines_cleared, 1);
+        assert_eq!(outcome.current_board_height, BOARD_HEIGHT);
+        assert!(outcome
+            .board
+            .iter()
+            .flatten()
+            .all(|&cell| cell == Cell::Empty));
+        assert!(outcome.score.score.total() > 0);
+        // 引数は変更しない
+        assert_eq!(score.score.total(), 0);
+        assert_eq!(board[BOARD_HEIGHT - 1][0], Cell::Occupied(GameColor::Cyan));
+    }
+
+    #[test]
+    fn test_non_bottom_line_becomes_solid_and_is_pushed_down() {
+        // 2段目を揃えると、下の孤立ブロックは消えてSolidラインが底まで下がる
+        let (board, height) = parse_board(
+            "\
+MYCMYC....
+C.........",
+        )
+        .unwrap();
+        let piece = i_piece(6, (BOARD_HEIGHT - 2) as i8, [GameColor::Yellow; 4]);
+        let outcome = simulate_placement(
+            &board,
+            height,
+            &CustomScoreSystem::new(),
+            &piece,
+            SimulationRules::default(),
+        );
+
+        assert_eq!(outcome.lines_cleared, 1);
+        assert_eq!(outcome.current_board_height, BOARD_HEIGHT - 1);
+        assert_eq!(
+            board_to_text(&outcome.board, outcome.current_board_height),
+            "----------\n##########"
+        );
+    }
+}
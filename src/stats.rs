@@ -0,0 +1,181 @@
+//! プレイの統計（CLI版）
+//!
+//! 固定したピース数や形ごとの内訳、消えたラインの種類などを数えます。
+//! サイドバーの統計表示（`T`キー）、リザルト画面のレポート、`--report`で書き出すJSONに使います。
+
+use std::time::Duration;
+
+use crate::json::Json;
+use crate::play_mode::ModeOutcome;
+use crate::tetromino::TetrominoShape;
+use crate::GameState;
+
+/// 統計で数える形（表示とJSONの順）
+pub const SHAPES: [TetrominoShape; 7] = [
+    TetrominoShape::I,
+    TetrominoShape::O,
+    TetrominoShape::T,
+    TetrominoShape::L,
+    TetrominoShape::J,
+    TetrominoShape::S,
+    TetrominoShape::Z,
+];
+
+/// 1ゲーム分の統計（練習モードの巻き戻しでは状態と一緒に戻る）
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GameStats {
+    pub pieces_placed: u32,
+    /// 形ごとの固定数（`SHAPES`の順）
+    pub shape_counts: [u32; 7],
+    /// 最下段から取り除いたライン数
+    pub bottom_lines: u32,
+    /// Solidラインになったライン数
+    pub solid_lines: u32,
+    /// CHAIN-BONUSを消費して消したSolidラインの段数
+    pub solid_rows_purged: u32,
+    /// Solid化の下で消えた孤立ブロック数
+    pub isolated_blocks_removed: u32,
+    /// これまでで最も大きい連結グループ（色を問わない）
+    pub biggest_group: u32,
+}
+
+impl GameStats {
+    /// ピースを1つ固定した
+    pub fn record_piece(&mut self, shape: TetrominoShape) {
+        self.pieces_placed += 1;
+        if let Some(index) = SHAPES.iter().position(|&s| s == shape) {
+            self.shape_counts[index] += 1;
+        }
+    }
+
+    /// 形ごとの固定数
+    pub fn shape_count(&self, shape: TetrominoShape) -> u32 {
+        SHAPES
+            .iter()
+            .position(|&s| s == shape)
+            .map_or(0, |index| self.shape_counts[index])
+    }
+
+    /// 連結グループの大きさを記録する（最大値だけを残す）
+    pub fn record_group(&mut self, size: u32) {
+        self.biggest_group = self.biggest_group.max(size);
+    }
+
+    /// 1秒あたりに固定したピース数
+    pub fn pieces_per_second(&self, play_time: Duration) -> f64 {
+        let seconds = play_time.as_secs_f64();
+        if seconds > 0.0 {
+            self.pieces_placed as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+
+    /// 形ごとの固定数を1行にしたもの（`SHAPES`のうち`shapes`の範囲、例: `I3 O2 T1 L0`）
+    pub fn shape_counts_text(&self, shapes: std::ops::Range<usize>) -> String {
+        SHAPES[shapes]
+            .iter()
+            .map(|&shape| format!("{:?}{}", shape, self.shape_count(shape)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn outcome_name(outcome: Option<ModeOutcome>) -> &'static str {
+    match outcome {
+        Some(ModeOutcome::GoalReached) => "goal_reached",
+        Some(ModeOutcome::TimeUp) => "time_up",
+        Some(ModeOutcome::OutOfPieces) => "out_of_pieces",
+        Some(ModeOutcome::ToppedOut) | None => "game_over",
+    }
+}
+
+/// ゲーム終了時のレポート（`--report`で書き出す）
+pub fn report(state: &GameState) -> Json {
+    let stats = &state.stats;
+    let score = &state.custom_score_system;
+    let mode = match &state.puzzle {
+        Some(puzzle) => puzzle.name.clone(),
+        None => state.play_mode.name(),
+    };
+    // 1秒あたりのピース数は小数点以下2桁に丸める
+    let pieces_per_second = (stats.pieces_per_second(state.play_time) * 100.0).round() / 100.0;
+    let shapes = SHAPES
+        .iter()
+        .zip(stats.shape_counts)
+        .map(|(shape, count)| (format!("{:?}", shape), Json::from(count)))
+        .collect();
+    Json::object([
+        ("mode", mode.into()),
+        ("outcome", outcome_name(state.outcome).into()),
+        ("time_ms", (state.play_time.as_millis() as i64).into()),
+        ("score", score.score.total().into()),
+        ("lines", state.lines_cleared.into()),
+        ("bottom_lines", stats.bottom_lines.into()),
+        ("solid_lines", stats.solid_lines.into()),
+        ("solid_rows_purged", stats.solid_rows_purged.into()),
+        (
+            "isolated_blocks_removed",
+            stats.isolated_blocks_removed.into(),
+        ),
+        ("pieces", stats.pieces_placed.into()),
+        ("pieces_per_second", Json::Number(pieces_per_second)),
+        ("shapes", Json::Object(shapes)),
+        ("biggest_group", stats.biggest_group.into()),
+        (
+            "max_chains",
+            Json::object([
+                ("cyan", score.max_chains.cyan.into()),
+                ("magenta", score.max_chains.magenta.into()),
+                ("yellow", score.max_chains.yellow.into()),
+            ]),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_pieces_by_shape_and_rate() {
+        let mut stats = GameStats::default();
+        stats.record_piece(TetrominoShape::T);
+        stats.record_piece(TetrominoShape::T);
+        stats.record_piece(TetrominoShape::I);
+        stats.record_group(12);
+        stats.record_group(4);
+
+        assert_eq!(stats.pieces_placed, 3);
+        assert_eq!(stats.shape_counts_text(0..4), "I1 O0 T2 L0");
+        assert_eq!(stats.biggest_group, 12);
+        assert_eq!(stats.pieces_per_second(Duration::from_secs(2)), 1.5);
+        assert_eq!(stats.pieces_per_second(Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn test_report_is_json_with_stats() {
+        let mut state = GameState::new();
+        state.stats.record_piece(TetrominoShape::O);
+        state.stats.solid_lines = 2;
+        state.play_time = Duration::from_millis(1500);
+
+        let report = report(&state);
+        assert_eq!(report.get("mode").and_then(Json::as_str), Some("ENDLESS"));
+        assert_eq!(
+            report.get("outcome").and_then(Json::as_str),
+            Some("game_over")
+        );
+        assert_eq!(report.get("solid_lines").and_then(Json::as_i64), Some(2));
+        assert_eq!(
+            report
+                .get("shapes")
+                .and_then(|shapes| shapes.get("O"))
+                .and_then(Json::as_i64),
+            Some(1)
+        );
+        let text = report.to_string();
+        assert!(text.contains("\"pieces_per_second\":0.67"), "{}", text);
+        assert_eq!(crate::json::parse(&text).unwrap(), report);
+    }
+}
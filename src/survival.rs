@@ -0,0 +1,121 @@
+//! サバイバルモードのせり上がり
+//!
+//! 一定間隔で盤面の下からSolidラインまたはガベージラインを押し上げます。
+//! 間隔はせり上がるたびに短くなります。
+
+use std::time::Duration;
+
+use crate::cell::{Board, Cell};
+use crate::config::{
+    BOARD_WIDTH, SURVIVAL_GARBAGE_EVERY, SURVIVAL_RISE_INTERVAL_MIN, SURVIVAL_RISE_INTERVAL_START,
+    SURVIVAL_RISE_INTERVAL_STEP,
+};
+use crate::random::RandomProvider;
+
+/// 下からせり上がる行の種類
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RisingRow {
+    /// 底のSolidラインの上に積まれ、フィールドを1段浅くする（CHAIN-BONUSで消去できる）
+    Solid,
+    /// プレイ領域の最下段に入る、1マスだけ穴の空いたガベージ行（穴を埋めれば消去できる）
+    Garbage { hole_x: usize },
+}
+
+/// `rises`回せり上がった後、次のせり上がりまでの間隔
+pub fn rise_interval(rises: u32) -> Duration {
+    SURVIVAL_RISE_INTERVAL_START
+        .saturating_sub(SURVIVAL_RISE_INTERVAL_STEP * rises)
+        .max(SURVIVAL_RISE_INTERVAL_MIN)
+}
+
+/// `rises`回目のせり上がりで入る行（`SURVIVAL_GARBAGE_EVERY`回に1回がガベージ行）
+pub fn next_rising_row<R: RandomProvider>(rises: u32, provider: &mut R) -> RisingRow {
+    if (rises + 1).is_multiple_of(SURVIVAL_GARBAGE_EVERY) {
+        RisingRow::Garbage {
+            hole_x: provider.gen_range(0, BOARD_WIDTH),
+        }
+    } else {
+        RisingRow::Solid
+    }
+}
+
+/// 盤面全体を1段押し上げて、下から`row`を挿入する
+///
+/// 盤面の最上段にブロックがあった場合や、フィールドの高さが0になった場合は
+/// 積み上がりきった（トップアウト）としてtrueを返す
+pub fn insert_rising_row(
+    board: &mut Board,
+    current_board_height: &mut usize,
+    row: RisingRow,
+) -> bool {
+    let topped_out = board[0].iter().any(|cell| cell.is_block());
+    board.remove(0);
+
+    // 最上段を取り除いたので、プレイ領域の最下段は current_board_height - 1 になる
+    let bottom_y = current_board_height.saturating_sub(1);
+    match row {
+        RisingRow::Solid => {
+            board.insert(bottom_y, vec![Cell::Solid; BOARD_WIDTH]);
+            *current_board_height = bottom_y;
+        }
+        RisingRow::Garbage { hole_x } => {
+            let mut garbage = vec![Cell::Garbage; BOARD_WIDTH];
+            garbage[hole_x] = Cell::Empty;
+            board.insert(bottom_y, garbage);
+        }
+    }
+
+    topped_out || *current_board_height == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BOARD_HEIGHT;
+    use crate::game_color::GameColor;
+
+    #[test]
+    fn test_solid_row_rises_on_top_of_solid_stack() {
+        let mut board = vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT];
+        let mut height = BOARD_HEIGHT - 1;
+        board[BOARD_HEIGHT - 1].fill(Cell::Solid);
+        board[BOARD_HEIGHT - 2][0] = Cell::Occupied(GameColor::Cyan);
+
+        let topped_out = insert_rising_row(&mut board, &mut height, RisingRow::Solid);
+
+        assert!(!topped_out);
+        assert_eq!(height, BOARD_HEIGHT - 2);
+        assert_eq!(board[BOARD_HEIGHT - 3][0], Cell::Occupied(GameColor::Cyan));
+        assert!(board[BOARD_HEIGHT - 2..]
+            .iter()
+            .all(|row| row.iter().all(|&cell| cell == Cell::Solid)));
+    }
+
+    #[test]
+    fn test_garbage_row_keeps_field_height_and_has_a_hole() {
+        let mut board = vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT];
+        let mut height = BOARD_HEIGHT;
+
+        insert_rising_row(&mut board, &mut height, RisingRow::Garbage { hole_x: 3 });
+
+        assert_eq!(height, BOARD_HEIGHT);
+        assert_eq!(board[BOARD_HEIGHT - 1][3], Cell::Empty);
+        assert_eq!(board[BOARD_HEIGHT - 1][4], Cell::Garbage);
+    }
+
+    #[test]
+    fn test_blocks_pushed_out_of_the_top_cause_top_out() {
+        let mut board = vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT];
+        let mut height = BOARD_HEIGHT;
+        board[0][5] = Cell::Occupied(GameColor::Yellow);
+
+        assert!(insert_rising_row(&mut board, &mut height, RisingRow::Solid));
+    }
+
+    #[test]
+    fn test_rise_interval_speeds_up_to_minimum() {
+        assert_eq!(rise_interval(0), SURVIVAL_RISE_INTERVAL_START);
+        assert!(rise_interval(1) < rise_interval(0));
+        assert_eq!(rise_interval(u32::MAX / 1000), SURVIVAL_RISE_INTERVAL_MIN);
+    }
+}
@@ -20,7 +20,8 @@ fn test_isolated_blocks_are_removed_on_non_bottom_clear() {
     board[cleared_line_y + 3][3] = Cell::Occupied(GameColor::Green);
 
     // Call the remove_isolated_blocks function
-    board_logic::remove_isolated_blocks(&mut board, cleared_line_y);
+    let removed = board_logic::remove_isolated_blocks(&mut board, cleared_line_y);
+    assert_eq!(removed, 1);
 
     // The isolated red block should be removed
     assert_eq!(
@@ -291,3 +292,92 @@ fn test_calculate_chain_bonus_large_group() {
     let bonus = board_logic::calculate_chain_bonus(&board);
     assert_eq!(bonus, 4, "45個のグループで4段のボーナス");
 }
+
+#[test]
+fn test_wildcard_joins_every_adjacent_color_group() {
+    let mut board = vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT];
+    let y = BOARD_HEIGHT - 1;
+
+    // Cyan - Wildcard - Magenta の並び：ワイルドカードは両方のグループに参加する
+    board[y][0] = Cell::Occupied(GameColor::Cyan);
+    board[y][1] = Cell::Wildcard;
+    board[y][2] = Cell::Occupied(GameColor::Magenta);
+
+    board_logic::find_and_connect_adjacent_blocks(&mut board, &[]);
+
+    assert_eq!(
+        board[y][0],
+        Cell::Connected {
+            color: GameColor::Cyan,
+            count: 1
+        }
+    );
+    assert_eq!(
+        board[y][2],
+        Cell::Connected {
+            color: GameColor::Magenta,
+            count: 1
+        }
+    );
+    // ワイルドカード自身は変化しない
+    assert_eq!(board[y][1], Cell::Wildcard);
+
+    let mut counts = board_logic::count_connected_blocks(&board, 0);
+    counts.sort();
+    assert_eq!(counts, vec![((0, y), 2), ((2, y), 2)]);
+}
+
+#[test]
+fn test_garbage_never_connects_and_is_not_removed_as_isolated() {
+    let mut board = vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT];
+    let y = BOARD_HEIGHT - 1;
+
+    board[y][0] = Cell::Occupied(GameColor::Cyan);
+    board[y][1] = Cell::Garbage;
+    board[y][2] = Cell::Garbage;
+
+    board_logic::find_and_connect_adjacent_blocks(&mut board, &[]);
+    assert_eq!(board[y][0], Cell::Occupied(GameColor::Cyan));
+
+    board_logic::remove_isolated_blocks(&mut board, 0);
+    // 孤立した色付きブロックは消去される
+    assert_eq!(board[y][0], Cell::Empty);
+    assert_eq!(board[y][1], Cell::Garbage);
+    assert_eq!(board[y][2], Cell::Garbage);
+}
+
+#[test]
+fn test_calculate_chain_bonus_counts_wildcards_in_each_group() {
+    let mut board = vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT];
+
+    // 9個のCyanと9個のMagentaが1つのワイルドカードを共有 → それぞれ10個のグループ
+    board[BOARD_HEIGHT - 1][..9].fill(Cell::Occupied(GameColor::Cyan));
+    board[BOARD_HEIGHT - 3][..9].fill(Cell::Occupied(GameColor::Magenta));
+    board[BOARD_HEIGHT - 2][0] = Cell::Wildcard;
+
+    assert_eq!(board_logic::calculate_chain_bonus(&board), 2);
+}
+
+#[test]
+fn test_bomb_clears_surrounding_area_except_solid() {
+    let mut board = vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT];
+    let line_y = BOARD_HEIGHT - 2;
+
+    board[line_y - 1].fill(Cell::Occupied(GameColor::Yellow));
+    board[line_y].fill(Cell::Occupied(GameColor::Cyan));
+    board[line_y + 1].fill(Cell::Solid);
+    board[line_y][5] = Cell::Bomb;
+
+    let cleared = animation::detonate_bombs(&mut board, line_y, BOARD_HEIGHT - 1);
+
+    assert_eq!(cleared, 3, "消去ラインより上の3マスだけが爆発で消える");
+    for (x, &cell) in board[line_y - 1].iter().enumerate() {
+        let expected = if (4..=6).contains(&x) {
+            Cell::Empty
+        } else {
+            Cell::Occupied(GameColor::Yellow)
+        };
+        assert_eq!(cell, expected);
+    }
+    assert!(board[line_y + 1].iter().all(|&cell| cell == Cell::Solid));
+}
@@ -118,23 +118,16 @@ fn test_max_chain_updated_after_piece_landing() {
     let mut state = GameState::new();
     state.mode = GameMode::Playing;
 
-    // Create a test scenario with connected blocks
-    // Place some cyan blocks in a connected pattern
-    state.board[BOARD_HEIGHT - 2][0] = Cell::Occupied(GameColor::Cyan);
-    state.board[BOARD_HEIGHT - 2][1] = Cell::Occupied(GameColor::Cyan);
-    state.board[BOARD_HEIGHT - 3][0] = Cell::Occupied(GameColor::Cyan);
-
-    // Place some magenta blocks in a larger connected pattern
-    for x in 3..=6 {
-        state.board[BOARD_HEIGHT - 2][x] = Cell::Occupied(GameColor::Magenta);
-    }
-    state.board[BOARD_HEIGHT - 3][3] = Cell::Occupied(GameColor::Magenta);
-
-    // Place some yellow blocks in an even larger pattern
-    for x in 7..=9 {
-        state.board[BOARD_HEIGHT - 2][x] = Cell::Occupied(GameColor::Yellow);
-        state.board[BOARD_HEIGHT - 3][x] = Cell::Occupied(GameColor::Yellow);
-    }
+    // Create a test scenario with connected blocks:
+    // cyan L (3 blocks), magenta row with one block on top (5), yellow 3x2 grid (6)
+    let (board, _) = board_notation::parse_board(
+        "
+        C..M...YYY
+        CC.MMMMYYY
+        ..........",
+    )
+    .unwrap();
+    state.board = board;
 
     // Initially, the custom score system should have zero max chains
     assert_eq!(state.custom_score_system.max_chains.get(GameColor::Cyan), 0);
@@ -259,6 +252,35 @@ fn test_chain_bonus_increases_when_group_reaches_threshold() {
     assert_eq!(state.custom_score_system.chain_bonus, 1);
 }
 
+#[test]
+fn test_start_board_is_used_only_in_practice_with_its_chains() {
+    // 連結数の入った盤面（10個のCyanグループ）
+    let start_board = board_notation::parse_board(
+        "
+        ccccc.....
+        ccccc.....
+        ##########",
+    )
+    .unwrap();
+
+    let mut endless = GameState::new();
+    endless.start_selected_mode(PlayMode::Endless, Some(&start_board), Duration::ZERO);
+    assert!(endless
+        .board
+        .iter()
+        .flatten()
+        .all(|&cell| cell == Cell::Empty));
+
+    let mut practice = GameState::new();
+    practice.start_selected_mode(PlayMode::Practice, Some(&start_board), Duration::ZERO);
+    assert_eq!(practice.current_board_height, start_board.1);
+    assert_eq!(
+        practice.custom_score_system.max_chains.get(GameColor::Cyan),
+        10
+    );
+    assert_eq!(practice.custom_score_system.chain_bonus, 1);
+}
+
 #[test]
 fn test_chain_bonus_updates_when_group_size_changes() {
     let time_provider = MockTimeProvider::new();
@@ -375,3 +397,634 @@ fn test_consuming_chain_bonus_removes_solid_lines_from_bottom() {
         .iter()
         .all(|row| row.iter().all(|cell| !matches!(cell, Cell::Solid))));
 }
+
+#[test]
+fn test_manual_purge_mode_keeps_solid_lines_until_requested() {
+    let options = GameOptions {
+        solid_purge_mode: SolidPurgeMode::Manual,
+        ..GameOptions::default()
+    };
+    let mut state = GameState::with_options(&options);
+    state.mode = GameMode::Playing;
+    state.current_piece = Some(state.pieces.next_piece(&state.special_block_rates));
+
+    for y in (BOARD_HEIGHT - 3)..BOARD_HEIGHT {
+        for x in 0..BOARD_WIDTH {
+            state.board[y][x] = Cell::Solid;
+        }
+    }
+    state.current_board_height = BOARD_HEIGHT - 3;
+    state.custom_score_system.chain_bonus = 2;
+
+    // Manualモードでは自動消費されない
+    state.consume_chain_bonus_for_solid_lines();
+    assert_eq!(state.custom_score_system.chain_bonus, 2);
+    assert_eq!(state.current_board_height, BOARD_HEIGHT - 3);
+
+    // CHAIN-BONUSの残量までしか消去できない
+    assert_eq!(state.available_purge_lines(), 2);
+    assert_eq!(state.purgeable_solid_lines(), 2);
+
+    // 段数を1段に切り替えてからpurgeする
+    state.handle_input(GameInput::CyclePurgeAmount);
+    assert_eq!(state.purgeable_solid_lines(), 1);
+    let piece_y = state.current_piece.as_ref().unwrap().pos.1;
+
+    state.handle_input(GameInput::SolidPurge);
+
+    assert_eq!(state.custom_score_system.chain_bonus, 1);
+    assert_eq!(state.current_board_height, BOARD_HEIGHT - 2);
+    assert_eq!(state.current_piece.as_ref().unwrap().pos.1, piece_y + 1);
+    // purge後は段数の選択が「全段」に戻る
+    assert_eq!(state.purge_amount, None);
+}
+
+#[test]
+fn test_board_shifts_clear_the_hint() {
+    let options = GameOptions {
+        solid_purge_mode: SolidPurgeMode::Manual,
+        ..GameOptions::default()
+    };
+    let mut state = GameState::with_options(&options);
+    state.start_game(PlayMode::Survival, Duration::ZERO);
+    state.board[BOARD_HEIGHT - 1] = vec![Cell::Solid; BOARD_WIDTH];
+    state.current_board_height = BOARD_HEIGHT - 1;
+    state.custom_score_system.chain_bonus = 1;
+
+    // Solid purgeで盤面が下にずれる
+    state.handle_input(GameInput::Hint);
+    assert!(state.hint.is_some());
+    state.handle_input(GameInput::SolidPurge);
+    assert_eq!(state.current_board_height, BOARD_HEIGHT);
+    assert!(state.hint.is_none());
+
+    // せり上がりで盤面が上にずれる
+    state.handle_input(GameInput::Hint);
+    assert!(state.hint.is_some());
+    state.update_play_time(SURVIVAL_RISE_INTERVAL_START);
+    assert_eq!(state.rises, 1);
+    assert!(state.hint.is_none());
+}
+
+#[test]
+fn test_cycle_purge_amount_wraps_to_all() {
+    let options = GameOptions {
+        solid_purge_mode: SolidPurgeMode::Manual,
+        ..GameOptions::default()
+    };
+    let mut state = GameState::with_options(&options);
+    state.mode = GameMode::Playing;
+
+    for y in (BOARD_HEIGHT - 3)..BOARD_HEIGHT {
+        for x in 0..BOARD_WIDTH {
+            state.board[y][x] = Cell::Solid;
+        }
+    }
+    state.current_board_height = BOARD_HEIGHT - 3;
+    state.custom_score_system.chain_bonus = 5;
+
+    let mut amounts = Vec::new();
+    for _ in 0..4 {
+        state.cycle_purge_amount();
+        amounts.push(state.purge_amount);
+    }
+    assert_eq!(amounts, vec![Some(1), Some(2), None, Some(1)]);
+}
+
+#[test]
+fn test_multiple_completed_lines_blink_and_push_down_together() {
+    let mut time_provider = MockTimeProvider::new();
+    let mut state = GameState::new();
+    state.mode = GameMode::Playing;
+
+    // 間を空けた2本のラインを最下段以外に用意する（最下段は空けておく）
+    for y in [BOARD_HEIGHT - 6, BOARD_HEIGHT - 3] {
+        for x in 0..BOARD_WIDTH {
+            state.board[y][x] = Cell::Occupied(GameColor::Blue);
+        }
+    }
+
+    check_and_start_next_line_animation(&mut state, time_provider.now());
+
+    // 揃った2本のラインが1つのLineBlinkで同時に点滅する
+    assert_eq!(
+        state.animation,
+        vec![Animation::LineBlink {
+            lines: vec![BOARD_HEIGHT - 3, BOARD_HEIGHT - 6],
+            count: 0,
+            start_time: time_provider.now(),
+        }]
+    );
+
+    time_provider.advance(BLINK_ANIMATION_STEP * BLINK_COUNT_MAX as u32);
+    handle_animation(&mut state, &time_provider);
+
+    // 両方のラインがSolid化し、並行してPushDownが始まる
+    let mut push_downs: Vec<usize> = state
+        .animation
+        .iter()
+        .filter_map(|anim| match anim {
+            Animation::PushDown { solid_line_y, .. } => Some(*solid_line_y),
+            _ => None,
+        })
+        .collect();
+    push_downs.sort();
+    assert_eq!(push_downs, vec![BOARD_HEIGHT - 6, BOARD_HEIGHT - 3]);
+
+    // すべてのPushDownが終わるまで進める
+    for _ in 0..BOARD_HEIGHT * 2 {
+        if !state
+            .animation
+            .iter()
+            .any(|anim| matches!(anim, Animation::PushDown { .. }))
+        {
+            break;
+        }
+        time_provider.advance(PUSH_DOWN_STEP_DURATION);
+        handle_animation(&mut state, &time_provider);
+    }
+
+    // 2本のSolidラインが底に積み重なり、フィールドが2段浅くなる
+    assert_eq!(state.current_board_height, BOARD_HEIGHT - 2);
+    for y in (BOARD_HEIGHT - 2)..BOARD_HEIGHT {
+        assert!(state.board[y].iter().all(|cell| *cell == Cell::Solid));
+    }
+    // 点数は2ライン分加算される
+    let last_lock = state.custom_score_system.last_lock.unwrap();
+    assert_eq!(last_lock.lines_cleared, 2);
+}
+
+#[test]
+fn test_cascade_gravity_drops_blocks_and_triggers_follow_up_clear() {
+    let mut time_provider = MockTimeProvider::new();
+    let mut state = GameState::with_options(&GameOptions {
+        gravity_mode: GravityMode::Cascade,
+        ..GameOptions::default()
+    });
+    state.mode = GameMode::Playing;
+
+    for x in 0..BOARD_WIDTH {
+        state.board[BOARD_HEIGHT - 1][x] = Cell::Occupied(GameColor::Blue);
+    }
+    // 消去後、Yellowの列とCyanの縦棒が落ちて最下段が再び揃う
+    for x in 0..BOARD_WIDTH - 1 {
+        state.board[BOARD_HEIGHT - 2][x] = Cell::Occupied(GameColor::Yellow);
+    }
+    state.board[BOARD_HEIGHT - 4][BOARD_WIDTH - 1] = Cell::Occupied(GameColor::Cyan);
+    state.board[BOARD_HEIGHT - 3][BOARD_WIDTH - 1] = Cell::Occupied(GameColor::Cyan);
+
+    check_and_start_next_line_animation(&mut state, time_provider.now());
+    time_provider.advance(BLINK_ANIMATION_STEP * BLINK_COUNT_MAX as u32);
+    handle_animation(&mut state, &time_provider);
+
+    // 最下段は空いたまま残り、落下アニメーションが始まる
+    assert!(state.board[BOARD_HEIGHT - 1]
+        .iter()
+        .all(|cell| *cell == Cell::Empty));
+    assert_eq!(
+        state.animation,
+        vec![Animation::GravityFall {
+            start_time: time_provider.now()
+        }]
+    );
+
+    for _ in 0..BOARD_HEIGHT {
+        if !matches!(state.animation.first(), Some(Animation::GravityFall { .. })) {
+            break;
+        }
+        time_provider.advance(GRAVITY_STEP_DURATION);
+        handle_animation(&mut state, &time_provider);
+    }
+
+    // 着地で揃った最下段が続けて点滅する
+    assert_eq!(
+        state.board[BOARD_HEIGHT - 1][0].color(),
+        Some(GameColor::Yellow)
+    );
+    assert!(matches!(
+        state.animation.as_slice(),
+        [Animation::LineBlink { lines, .. }] if *lines == vec![BOARD_HEIGHT - 1]
+    ));
+}
+
+#[test]
+fn test_sprint_ends_with_results_when_goal_lines_are_cleared() {
+    let mut time_provider = MockTimeProvider::new();
+    let mut state = GameState::new();
+    state.start_game(PlayMode::Sprint { lines: 1 }, time_provider.now());
+    assert_eq!(state.mode, GameMode::Playing);
+
+    for x in 0..BOARD_WIDTH {
+        state.board[BOARD_HEIGHT - 1][x] = Cell::Occupied(GameColor::Blue);
+    }
+    check_and_start_next_line_animation(&mut state, time_provider.now());
+    assert_eq!(state.lines_cleared, 1);
+
+    time_provider.advance(Duration::from_secs(42));
+    state.update_play_time(time_provider.now());
+
+    assert_eq!(state.mode, GameMode::GameOver);
+    assert_eq!(state.outcome, Some(ModeOutcome::GoalReached));
+    assert_eq!(state.play_time, Duration::from_secs(42));
+}
+
+#[test]
+fn test_marathon_fall_speed_increases_with_level() {
+    let time_provider = MockTimeProvider::new();
+    let mut state = GameState::new();
+    state.start_game(PlayMode::Marathon { level_cap: 15 }, time_provider.now());
+    let initial_speed = state.fall_speed;
+
+    state.lines_cleared = LINES_PER_LEVEL - 1;
+    for x in 0..BOARD_WIDTH {
+        state.board[BOARD_HEIGHT - 1][x] = Cell::Occupied(GameColor::Blue);
+    }
+    check_and_start_next_line_animation(&mut state, time_provider.now());
+
+    assert!(state.fall_speed < initial_speed);
+    state.update_play_time(time_provider.now());
+    assert_eq!(state.mode, GameMode::Playing);
+}
+
+#[test]
+fn test_survival_rows_rise_on_schedule_and_chain_bonus_removes_them() {
+    let mut time_provider = MockTimeProvider::new();
+    let mut state = GameState::new();
+    state.start_game(PlayMode::Survival, time_provider.now());
+    state.board[BOARD_HEIGHT - 1][0] = Cell::Occupied(GameColor::Cyan);
+
+    time_provider.advance(SURVIVAL_RISE_INTERVAL_START - Duration::from_millis(1));
+    state.update_play_time(time_provider.now());
+    assert_eq!(state.rises, 0);
+
+    time_provider.advance(Duration::from_millis(1));
+    state.update_play_time(time_provider.now());
+
+    // 最初のせり上がりはSolidライン。盤面が押し上げられ、フィールドが1段浅くなる
+    assert_eq!(state.rises, 1);
+    assert_eq!(state.current_board_height, BOARD_HEIGHT - 1);
+    assert_eq!(
+        state.board[BOARD_HEIGHT - 2][0],
+        Cell::Occupied(GameColor::Cyan)
+    );
+    assert_eq!(
+        state.next_rise_at,
+        Some(time_provider.now() + survival::rise_interval(1))
+    );
+
+    // CHAIN-BONUSがあれば次のせり上がりで溜まったSolidラインが消える
+    state.custom_score_system.set_chain_bonus_from_total(5);
+    time_provider.advance(survival::rise_interval(1));
+    state.update_play_time(time_provider.now());
+    assert_eq!(state.rises, 2);
+    assert_eq!(state.current_board_height, BOARD_HEIGHT);
+    assert_eq!(state.mode, GameMode::Playing);
+}
+
+#[test]
+fn test_survival_purge_after_rise_moves_active_piece_with_the_board() {
+    let mut time_provider = MockTimeProvider::new();
+    let mut state = GameState::new();
+    state.start_game(PlayMode::Survival, time_provider.now());
+    time_provider.advance(SURVIVAL_RISE_INTERVAL_START);
+    state.update_play_time(time_provider.now());
+    assert_eq!(state.current_board_height, BOARD_HEIGHT - 1);
+
+    let before = state.current_piece.clone().expect("active piece");
+    state.custom_score_system.set_chain_bonus_from_total(5);
+    time_provider.advance(survival::rise_interval(1));
+    state.update_play_time(time_provider.now());
+
+    // 1段せり上がった後に2段のSolidラインが消えたので、盤面は2段下がる。
+    // せり上がりではピースは動かず、消去では盤面と一緒に2段下がる
+    assert_eq!(state.current_board_height, BOARD_HEIGHT);
+    let after = state.current_piece.clone().expect("active piece");
+    assert_eq!(after.pos, (before.pos.0, before.pos.1 + 2));
+    assert!(state.is_valid_position(&after));
+}
+
+/// ハードドロップでピースを固定し、ライン消去のアニメーションを最後まで進める
+fn hard_drop_and_settle(state: &mut GameState, time_provider: &mut MockTimeProvider) {
+    state.handle_input(GameInput::HardDrop);
+    state.lock_piece(time_provider);
+    for _ in 0..BOARD_HEIGHT * 4 {
+        if state.animation.is_empty() {
+            break;
+        }
+        time_provider.advance(BLINK_ANIMATION_STEP * BLINK_COUNT_MAX as u32);
+        handle_animation(state, time_provider);
+    }
+}
+
+/// 組み込みパズルの解答（ピースごとに、ハードドロップの前に入れる操作）
+fn builtin_puzzle_solution(name: &str) -> Vec<Vec<GameInput>> {
+    use GameInput::*;
+    match name {
+        "First Clear" => vec![vec![MoveLeft, MoveLeft, MoveLeft]],
+        "Cyan Ladder" => vec![vec![MoveRight, MoveRight, MoveRight]],
+        "Ten Chain" => vec![vec![MoveLeft, MoveLeft, MoveLeft]],
+        "Double Down" => vec![
+            vec![],
+            vec![RotateClockwise, MoveRight, MoveRight, MoveRight, MoveRight],
+        ],
+        _ => panic!("no solution for {}", name),
+    }
+}
+
+#[test]
+fn test_builtin_puzzle_is_solved_with_its_solution() {
+    let mut time_provider = MockTimeProvider::new();
+    let puzzles = puzzle::builtin_puzzles();
+    for puzzle in &puzzles {
+        let name = &puzzle.name;
+        let mut state = GameState::new();
+        state.start_puzzle(puzzle, time_provider.now());
+        assert_eq!(state.current_board_height, puzzle.board_height);
+
+        for inputs in builtin_puzzle_solution(name) {
+            assert_eq!(state.mode, GameMode::Playing, "{}", name);
+            for input in inputs {
+                state.handle_input(input);
+            }
+            hard_drop_and_settle(&mut state, &mut time_provider);
+        }
+
+        assert_eq!(state.mode, GameMode::GameOver, "{}", name);
+        assert_eq!(state.outcome, Some(ModeOutcome::GoalReached), "{}", name);
+    }
+}
+
+#[test]
+fn test_builtin_puzzle_is_not_solved_by_hard_drops_alone() {
+    let mut time_provider = MockTimeProvider::new();
+    for puzzle in &puzzle::builtin_puzzles() {
+        let mut state = GameState::new();
+        state.start_puzzle(puzzle, time_provider.now());
+        for _ in 0..puzzle.pieces.len() {
+            if state.mode != GameMode::Playing {
+                break;
+            }
+            hard_drop_and_settle(&mut state, &mut time_provider);
+        }
+
+        assert_eq!(state.mode, GameMode::GameOver, "{}", puzzle.name);
+        assert_ne!(
+            state.outcome,
+            Some(ModeOutcome::GoalReached),
+            "{}",
+            puzzle.name
+        );
+    }
+}
+
+#[test]
+fn test_puzzle_progress_save_failure_is_reported() {
+    let progress = PuzzleProgress::default();
+    assert_eq!(save_puzzle_progress(None, &progress), Ok(()));
+
+    let path = std::env::temp_dir()
+        .join("thud_and_tile_missing_dir")
+        .join("progress");
+    let message = save_puzzle_progress(Some(&path), &progress).unwrap_err();
+    assert!(message.contains("thud_and_tile_missing_dir"), "{}", message);
+}
+
+#[test]
+fn test_report_appends_one_line_per_game_and_reports_failures() {
+    let mut state = GameState::new();
+    assert_eq!(save_report(None, &state), Ok(()));
+
+    let path = std::env::temp_dir().join(format!("thud_and_tile_report_{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    save_report(Some(&path), &state).unwrap();
+    state.lines_cleared = 3;
+    save_report(Some(&path), &state).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", text);
+    let last = json::parse(lines[1]).unwrap();
+    assert_eq!(last.get("lines").and_then(json::Json::as_i64), Some(3));
+
+    let missing = std::env::temp_dir()
+        .join("thud_and_tile_missing_dir")
+        .join("report.jsonl");
+    let message = save_report(Some(&missing), &state).unwrap_err();
+    assert!(message.contains("thud_and_tile_missing_dir"), "{}", message);
+}
+
+#[test]
+fn test_puzzle_fails_when_pieces_run_out() {
+    let mut time_provider = MockTimeProvider::new();
+    let puzzle = puzzle::parse_pack(
+        "[puzzle]\nname: Impossible\ngoal: lines 3\npieces: O:CMYC\nboard:\nCMYC..MYCM",
+    )
+    .unwrap()
+    .remove(0);
+    let mut state = GameState::new();
+    state.start_puzzle(&puzzle, time_provider.now());
+    assert!(state.next_piece.is_none());
+
+    hard_drop_and_settle(&mut state, &mut time_provider);
+
+    assert_eq!(state.mode, GameMode::GameOver);
+    assert_eq!(state.outcome, Some(ModeOutcome::OutOfPieces));
+    assert_eq!(state.lines_cleared, 1);
+}
+
+#[test]
+fn test_practice_undo_replays_the_same_pieces() {
+    let mut time_provider = MockTimeProvider::new();
+    let mut state = GameState::new();
+    state.pieces = PieceGenerator::new(42);
+    state.next_piece = Some(state.pieces.next_piece(&state.special_block_rates));
+    state.start_game(PlayMode::Practice, time_provider.now());
+    let start = state.clone();
+
+    // ロック直前の状態を記録しながら3ピース置く
+    let mut history = UndoHistory::new(PRACTICE_HISTORY_LIMIT);
+    let mut placed = Vec::new();
+    for _ in 0..3 {
+        placed.push(state.current_piece.clone());
+        history.record(state.practice_snapshot());
+        hard_drop_and_settle(&mut state, &mut time_provider);
+    }
+    let after_three = state.clone();
+
+    // 3ピース分戻すと開始時の状態に、やり直すと元の状態に戻る
+    for _ in 0..3 {
+        assert!(state.step_practice_history(&mut history, false));
+    }
+    assert!(!state.step_practice_history(&mut history, false));
+    assert_eq!(state, start);
+    for _ in 0..3 {
+        assert!(state.step_practice_history(&mut history, true));
+    }
+    assert_eq!(state, after_three);
+
+    // 乱数とバッグも巻き戻るので、同じピースが同じ順で出てくる
+    for _ in 0..3 {
+        state.step_practice_history(&mut history, false);
+    }
+    for piece in &placed {
+        assert_eq!(&state.current_piece, piece);
+        hard_drop_and_settle(&mut state, &mut time_provider);
+    }
+    assert_eq!(state.board, after_three.board);
+    assert_eq!(state.next_piece, after_three.next_piece);
+}
+
+/// 固定したピースのライン消去アニメーションを最後まで進める
+fn lock_and_settle(state: &mut GameState, time_provider: &mut MockTimeProvider) {
+    state.lock_piece(time_provider);
+    for _ in 0..BOARD_HEIGHT * 8 {
+        if state.animation.is_empty() {
+            break;
+        }
+        time_provider.advance(BLINK_ANIMATION_STEP * BLINK_COUNT_MAX as u32);
+        handle_animation(state, time_provider);
+    }
+    assert!(state.animation.is_empty());
+}
+
+#[test]
+fn test_simulator_matches_engine_on_random_games() {
+    use crate::random::{RandomProvider, SeededRandomProvider};
+    use crate::simulator::{simulate_placement, SimulationRules};
+
+    let gravity_modes = [
+        GravityMode::Naive,
+        GravityMode::Sticky,
+        GravityMode::Cascade,
+    ];
+    let purge_modes = [SolidPurgeMode::Automatic, SolidPurgeMode::Manual];
+    let colors = [GameColor::Cyan, GameColor::Magenta, GameColor::Yellow];
+    let mut rng = SeededRandomProvider::new(2024);
+    let mut total_lines = 0;
+
+    for trial in 0..24u64 {
+        let mut time_provider = MockTimeProvider::new();
+        let mut state = GameState::with_options(&GameOptions {
+            gravity_mode: gravity_modes[trial as usize % 3],
+            solid_purge_mode: purge_modes[trial as usize / 3 % 2],
+            special_block_rates: if trial % 4 == 3 {
+                SpecialBlockRates {
+                    wildcard_percent: 10,
+                    bomb_percent: 10,
+                    garbage_percent: 10,
+                }
+            } else {
+                SpecialBlockRates::default()
+            },
+            ..GameOptions::default()
+        });
+        state.pieces = PieceGenerator::new(trial);
+        state.next_piece = Some(state.pieces.next_piece(&state.special_block_rates));
+
+        // 下の数段を、各段1マス以上空けてランダムに埋める
+        for y in BOARD_HEIGHT - 6..BOARD_HEIGHT {
+            let hole = rng.gen_range(0, BOARD_WIDTH);
+            for x in (0..BOARD_WIDTH).filter(|&x| x != hole) {
+                if rng.gen_range(0, 100) < 75 {
+                    state.board[y][x] = Cell::Occupied(colors[rng.gen_range(0, colors.len())]);
+                }
+            }
+        }
+        state.start_game(PlayMode::Endless, time_provider.now());
+
+        for turn in 0..40 {
+            if state.mode != GameMode::Playing {
+                break;
+            }
+            // 低い位置の配置を優先して選び、ライン消去を起こしやすくする
+            let piece = state.current_piece.clone().unwrap();
+            let mut placements =
+                placement::enumerate_placements(&state.board, state.current_board_height, &piece);
+            let depth = |placement: &placement::Placement| -> i32 {
+                placement.cells().iter().map(|&(_, y)| y as i32).sum()
+            };
+            placements.sort_by_key(|placement| std::cmp::Reverse(depth(placement)));
+            let pick = rng.gen_range(0, placements.len().min(3));
+            let chosen = placements[pick].piece.clone();
+
+            let rules = SimulationRules {
+                gravity_mode: state.gravity_mode,
+                solid_purge_mode: state.solid_purge_mode,
+            };
+            let expected = simulate_placement(
+                &state.board,
+                state.current_board_height,
+                &state.custom_score_system,
+                &chosen,
+                rules,
+            );
+            let lines_before = state.lines_cleared;
+            state.current_piece = Some(chosen);
+            lock_and_settle(&mut state, &mut time_provider);
+
+            let context = format!("trial {} turn {} {:?}", trial, turn, rules);
+            assert_eq!(state.board, expected.board, "{}", context);
+            assert_eq!(
+                state.current_board_height, expected.current_board_height,
+                "{}",
+                context
+            );
+            assert_eq!(state.custom_score_system, expected.score, "{}", context);
+            assert_eq!(
+                state.lines_cleared - lines_before,
+                expected.lines_cleared,
+                "{}",
+                context
+            );
+            total_lines += expected.lines_cleared;
+        }
+    }
+    // ライン消去を伴う配置を十分に含んでいること
+    assert!(total_lines > 50, "only {} lines cleared", total_lines);
+}
+
+#[test]
+fn test_events_report_spawn_moves_and_line_clear() {
+    let mut time_provider = MockTimeProvider::new();
+    let puzzles = puzzle::builtin_puzzles();
+    let puzzle = puzzles.iter().find(|p| p.name == "First Clear").unwrap();
+    let mut state = GameState::new();
+    state.start_puzzle(puzzle, time_provider.now());
+    for input in builtin_puzzle_solution("First Clear").remove(0) {
+        state.handle_input(input);
+    }
+    hard_drop_and_settle(&mut state, &mut time_provider);
+
+    let events: Vec<GameEvent> = state.events.drain().collect();
+    assert!(matches!(events[0], GameEvent::PieceSpawned { .. }));
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, GameEvent::PieceMoved { drop: 0, .. })));
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, GameEvent::LinesCleared(lines) if *lines > 0)));
+    assert_eq!(state.events.drain().count(), 0);
+}
+
+#[test]
+fn test_stats_count_locked_pieces_and_cleared_lines() {
+    let mut time_provider = MockTimeProvider::new();
+    let puzzles = puzzle::builtin_puzzles();
+    let puzzle = puzzles.iter().find(|p| p.name == "First Clear").unwrap();
+    let mut state = GameState::new();
+    state.start_puzzle(puzzle, time_provider.now());
+    let shape = state.current_piece.as_ref().unwrap().shape;
+    for input in builtin_puzzle_solution("First Clear").remove(0) {
+        state.handle_input(input);
+    }
+    hard_drop_and_settle(&mut state, &mut time_provider);
+
+    assert_eq!(state.stats.pieces_placed, 1);
+    assert_eq!(state.stats.shape_count(shape), 1);
+    assert!(state.lines_cleared > 0);
+    assert_eq!(
+        state.stats.bottom_lines + state.stats.solid_lines,
+        state.lines_cleared
+    );
+    assert!(state.stats.biggest_group >= state.custom_score_system.max_chains.cyan);
+}
@@ -4,4 +4,5 @@ use tetromino::TetrominoShape;
 mod board_logic_tests;
 mod color_consistency_tests;
 mod game_state_tests;
+mod render_golden_tests;
 mod rotation_tests;
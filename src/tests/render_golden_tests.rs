@@ -0,0 +1,304 @@
+use super::*;
+use crate::board_notation::parse_board;
+use crate::tetromino::TetrominoShape;
+use crate::theme::{self, Charset, Theme};
+use crate::virtual_terminal::VirtualTerminal;
+use std::fs;
+use std::path::PathBuf;
+
+// 仮想端末に描いた画面全体を、src/tests/golden/のテキストと比較するゴールデン画面テスト
+// 描画を変更して画面が変わるのが正しい場合は、UPDATE_GOLDEN=1 cargo test で書き直して差分を確認する
+
+const SCREEN_WIDTH: usize = 64;
+const SCREEN_HEIGHT: usize = BOARD_HEIGHT + 6;
+
+fn assert_golden(name: &str, terminal: &VirtualTerminal) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests/golden")
+        .join(format!("{}.txt", name));
+    let actual = terminal.dump();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!(
+            "cannot read {} ({}); run with UPDATE_GOLDEN=1 to create it",
+            path.display(),
+            err
+        )
+    });
+    assert!(
+        actual == expected,
+        "screen differs from {}:\n{}",
+        path.display(),
+        actual
+    );
+}
+
+/// 固定のピース列と盤面でEndlessを始めた状態
+fn mid_game_state() -> GameState {
+    let mut state = GameState::new();
+    state.pieces = PieceGenerator::new(7);
+    state.next_piece = Some(state.pieces.next_piece(&state.special_block_rates));
+    let (board, height) = parse_board(
+        "\
+Y.........
+YY...MM...
+CCCCMMYYY.
+CCC..MMY..
+##########",
+    )
+    .unwrap();
+    state.board = board;
+    state.current_board_height = height;
+    state.update_all_connected_block_counts();
+    state.update_max_chains();
+    state.start_game(PlayMode::Endless, Duration::ZERO);
+    state
+}
+
+#[test]
+fn test_golden_title_screen() {
+    let mut terminal = VirtualTerminal::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    render::draw_title_screen(&mut terminal, 2).unwrap();
+    assert_golden("title", &terminal);
+}
+
+#[test]
+fn test_golden_mid_game() {
+    let mut terminal = VirtualTerminal::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    let state = mid_game_state();
+    render::draw(&mut terminal, &state).unwrap();
+    assert_golden("mid_game", &terminal);
+}
+
+#[test]
+fn test_golden_line_blink_and_push_down() {
+    let mut terminal = VirtualTerminal::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    let mut time_provider = MockTimeProvider::new();
+    let mut state = mid_game_state();
+    render::draw(&mut terminal, &state).unwrap();
+
+    // 右端の空きにIを縦に落として、最下段の1つ上の段をそろえる（Solid化してPush Downする）
+    let piece = Tetromino::from_shape(TetrominoShape::I, [GameColor::Blue; 4]).rotated();
+    let piece = (0..BOARD_WIDTH as i8)
+        .map(|x| piece.moved(x - piece.pos.0, 0))
+        .filter(|moved| state.is_valid_position(moved))
+        .map(|moved| placement::drop_to_floor(&state.board, state.current_board_height, &moved))
+        .find(|landed| landed.iter_blocks().any(|((x, _), _)| x == 9))
+        .expect("vertical I fits in the right column");
+    state.current_piece = Some(piece);
+    render::draw(&mut terminal, &state).unwrap();
+
+    state.lock_piece(&time_provider);
+    render::draw(&mut terminal, &state).unwrap();
+    // 点滅の消灯フレーム
+    time_provider.advance(BLINK_ANIMATION_STEP);
+    handle_animation(&mut state, &time_provider);
+    render::draw(&mut terminal, &state).unwrap();
+    assert_golden("line_blink", &terminal);
+
+    // 点滅が終わり、Solidラインを押し下げている途中のフレーム
+    for _ in 0..BLINK_COUNT_MAX * 2 {
+        if state
+            .animation
+            .iter()
+            .any(|animation| matches!(animation, Animation::PushDown { .. }))
+        {
+            break;
+        }
+        time_provider.advance(BLINK_ANIMATION_STEP);
+        handle_animation(&mut state, &time_provider);
+        render::draw(&mut terminal, &state).unwrap();
+    }
+    assert!(state
+        .animation
+        .iter()
+        .any(|animation| matches!(animation, Animation::PushDown { .. })));
+    assert_golden("push_down", &terminal);
+}
+
+#[test]
+fn test_golden_layouts_follow_terminal_size() {
+    let state = mid_game_state();
+
+    // 高さの足りない80x24では、サイドバーを詰めて右に置く
+    let mut short = VirtualTerminal::new(80, 24);
+    render::draw(&mut short, &state).unwrap();
+    assert_golden("condensed_sidebar", &short);
+
+    // 幅の足りない端末では、サイドバーを盤面の下に置く
+    let mut narrow = VirtualTerminal::new(30, 40);
+    render::draw(&mut narrow, &state).unwrap();
+    assert_golden("sidebar_below", &narrow);
+
+    let mut tiny = VirtualTerminal::new(30, 20);
+    render::draw(&mut tiny, &state).unwrap();
+    assert_golden("too_small", &tiny);
+}
+
+#[test]
+fn test_golden_half_block_board() {
+    let mut state = mid_game_state();
+    state.board_style = BoardStyle::HalfBlock;
+
+    // 上下2マスを1文字にまとめ、小さなペインでも盤面とサイドバーが収まる
+    let mut pane = VirtualTerminal::new(36, 16);
+    render::draw(&mut pane, &state).unwrap();
+    assert_golden("half_block", &pane);
+
+    // 色を送らない端末では、上下両方のマスを背景色に頼らない`█`で描く
+    let mut theme = theme::CLASSIC;
+    theme.monochrome = true;
+    let mut monochrome = VirtualTerminal::new(36, 16);
+    monochrome.set_theme(theme);
+    render::draw(&mut monochrome, &state).unwrap();
+    assert!(monochrome.text().contains('█'));
+    assert_golden("half_block_monochrome", &monochrome);
+
+    // 連結数を表示するときは、1マス1文字で描く（水色のブロックを連結ブロックにしておく）
+    for cell in state.board.iter_mut().flatten() {
+        if *cell == Cell::Occupied(GameColor::Cyan) {
+            *cell = Cell::Connected {
+                color: GameColor::Cyan,
+                count: 0,
+            };
+        }
+    }
+    state.update_all_connected_block_counts();
+    state.handle_input(GameInput::ToggleCounts);
+    let mut counts = VirtualTerminal::new(36, 24);
+    render::draw(&mut counts, &state).unwrap();
+    assert_golden("half_block_counts", &counts);
+}
+
+#[test]
+fn test_golden_glyph_mode() {
+    // 色ごとの記号で描く（連結ブロックは記号と連結数、ゴーストは記号の閉じ側）
+    let mut state = mid_game_state();
+    for cell in state.board.iter_mut().flatten() {
+        if *cell == Cell::Occupied(GameColor::Magenta) {
+            *cell = Cell::Connected {
+                color: GameColor::Magenta,
+                count: 0,
+            };
+        }
+    }
+    state.update_all_connected_block_counts();
+    let mut terminal = VirtualTerminal::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    terminal.set_theme(Theme::parse("glyphs: on").unwrap());
+    render::draw(&mut terminal, &state).unwrap();
+    assert_golden("glyphs", &terminal);
+}
+
+#[test]
+fn test_golden_ascii_profile() {
+    // ASCIIだけで描く（枠は+ - |、ハーフブロックは' . :）
+    let mut theme = theme::CLASSIC;
+    theme.charset = Charset::Ascii;
+    let mut state = mid_game_state();
+
+    let mut terminal = VirtualTerminal::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    terminal.set_theme(theme);
+    render::draw(&mut terminal, &state).unwrap();
+    assert!(terminal.text().is_ascii());
+    assert_golden("mid_game_ascii", &terminal);
+
+    state.board_style = BoardStyle::HalfBlock;
+    let mut pane = VirtualTerminal::new(36, 16);
+    pane.set_theme(theme);
+    render::draw(&mut pane, &state).unwrap();
+    assert!(pane.text().is_ascii());
+    assert_golden("half_block_ascii", &pane);
+
+    state.handle_input(GameInput::ToggleCounts);
+    let mut counts = VirtualTerminal::new(36, 24);
+    counts.set_theme(theme);
+    render::draw(&mut counts, &state).unwrap();
+    assert!(counts.text().is_ascii());
+}
+
+#[test]
+fn test_golden_group_outlines() {
+    // 8個のCyanはボーナスが近いので反転し、左上のマスに次のボーナスまでの残りを表示する
+    let mut state = mid_game_state();
+    let (board, height) = parse_board(
+        "\
+C.......YY
+CC..MM..Y.
+CCC.MM.YY.
+CC..MMMY..
+##########",
+    )
+    .unwrap();
+    state.board = board;
+    state.current_board_height = height;
+    board_logic::find_and_connect_adjacent_blocks(&mut state.board, &[]);
+    state.update_all_connected_block_counts();
+    state.handle_input(GameInput::ToggleGroups);
+
+    let mut terminal = VirtualTerminal::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    render::draw(&mut terminal, &state).unwrap();
+    assert_golden("group_outlines", &terminal);
+
+    // ハーフブロック表示中も、輪郭を描ける通常の盤面に切り替えて描く
+    state.board_style = BoardStyle::HalfBlock;
+    let mut half_block = VirtualTerminal::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    render::draw(&mut half_block, &state).unwrap();
+    assert_eq!(half_block.dump(), terminal.dump());
+    state.board_style = BoardStyle::Blocks;
+
+    let mut theme = theme::CLASSIC;
+    theme.charset = Charset::Ascii;
+    let mut ascii = VirtualTerminal::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    ascii.set_theme(theme);
+    render::draw(&mut ascii, &state).unwrap();
+    assert_golden("group_outlines_ascii", &ascii);
+}
+
+#[test]
+fn test_golden_stats_sidebar() {
+    let mut state = mid_game_state();
+    for shape in [
+        TetrominoShape::T,
+        TetrominoShape::I,
+        TetrominoShape::T,
+        TetrominoShape::S,
+    ] {
+        state.stats.record_piece(shape);
+    }
+    state.stats.bottom_lines = 1;
+    state.stats.solid_lines = 2;
+    state.stats.solid_rows_purged = 1;
+    state.stats.isolated_blocks_removed = 3;
+    state.stats.record_group(7);
+    state.play_time = Duration::from_secs(8);
+    state.handle_input(GameInput::ToggleStats);
+
+    let mut terminal = VirtualTerminal::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    render::draw(&mut terminal, &state).unwrap();
+    assert_golden("stats_sidebar", &terminal);
+
+    let mut short = VirtualTerminal::new(80, 24);
+    render::draw(&mut short, &state).unwrap();
+    assert_golden("stats_sidebar_condensed", &short);
+}
+
+#[test]
+fn test_golden_game_over() {
+    let mut terminal = VirtualTerminal::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    let mut state = mid_game_state();
+    render::draw(&mut terminal, &state).unwrap();
+
+    state.mode = GameMode::GameOver;
+    state.outcome = Some(ModeOutcome::ToppedOut);
+    render::draw(&mut terminal, &state).unwrap();
+    assert_golden("game_over", &terminal);
+
+    // ハーフブロック表示の小さなペインでは、枠の内側に収まる短いレポートにする
+    state.board_style = BoardStyle::HalfBlock;
+    let mut pane = VirtualTerminal::new(36, 16);
+    render::draw(&mut pane, &state).unwrap();
+    assert_golden("game_over_half_block", &pane);
+}
@@ -1,9 +1,10 @@
+use crate::cell::{BlockKind, Cell};
 use crate::game_color::GameColor;
-use crate::random::{create_default_random_provider, RandomProvider};
-use lazy_static::lazy_static;
-use std::sync::Mutex;
+use crate::random::{entropy_seed, RandomProvider, SeededRandomProvider};
 
-use crate::config::{BOARD_WIDTH, COLOR_PALETTE};
+use crate::config::{
+    BOARD_WIDTH, BOMB_SPAWN_PERCENT, COLOR_PALETTE, GARBAGE_SPAWN_PERCENT, WILDCARD_SPAWN_PERCENT,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TetrominoShape {
@@ -111,34 +112,88 @@ impl TetrominoShape {
     }
 }
 
-pub struct TetrominoBag {
+/// ゲームごとのピース生成器（7-bagとシード付き乱数）
+///
+/// 状態ごと複製できるので、ゲーム状態のスナップショットに含めておけば、
+/// 巻き戻した後も同じピースが同じ順で出てくる
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PieceGenerator {
     bag: Vec<TetrominoShape>,
-    random_provider: crate::random::RandomProviderImpl,
+    random_provider: SeededRandomProvider,
 }
 
-impl TetrominoBag {
-    pub fn new() -> Self {
-        let mut bag = TetrominoShape::all_shapes();
-        let mut random_provider = create_default_random_provider();
-
-        random_provider.shuffle(&mut bag);
-        TetrominoBag {
-            bag,
-            random_provider,
+impl PieceGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            bag: Vec::new(),
+            random_provider: SeededRandomProvider::new(seed),
         }
     }
 
-    pub fn next(&mut self) -> TetrominoShape {
+    /// プラットフォームの乱数からシードを決めて作成
+    pub fn from_entropy() -> Self {
+        Self::new(entropy_seed())
+    }
+
+    fn next_shape(&mut self) -> TetrominoShape {
         if self.bag.is_empty() {
             self.bag = TetrominoShape::all_shapes();
             self.random_provider.shuffle(&mut self.bag);
         }
         self.bag.pop().unwrap()
     }
+
+    /// 次のピースを生成し、特殊ブロックの出現確率を適用する
+    pub fn next_piece(&mut self, rates: &SpecialBlockRates) -> Tetromino {
+        let shape = self.next_shape();
+        let mut tetromino = Tetromino::random_colored(shape, &mut self.random_provider);
+        for kind in tetromino.kinds.iter_mut() {
+            *kind = rates.roll(&mut self.random_provider);
+        }
+        tetromino
+    }
+}
+
+/// ピース内の各ブロックが特殊ブロックになる確率（%）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpecialBlockRates {
+    pub wildcard_percent: u32,
+    pub bomb_percent: u32,
+    pub garbage_percent: u32,
+}
+
+impl Default for SpecialBlockRates {
+    fn default() -> Self {
+        Self {
+            wildcard_percent: WILDCARD_SPAWN_PERCENT,
+            bomb_percent: BOMB_SPAWN_PERCENT,
+            garbage_percent: GARBAGE_SPAWN_PERCENT,
+        }
+    }
 }
 
-lazy_static! {
-    static ref TETROMINO_BAG: Mutex<TetrominoBag> = Mutex::new(TetrominoBag::new());
+impl SpecialBlockRates {
+    /// 確率の合計（100を超えてはならない）
+    pub fn total_percent(&self) -> u32 {
+        self.wildcard_percent + self.bomb_percent + self.garbage_percent
+    }
+
+    /// 1ブロック分の種類を抽選する
+    pub fn roll<R: RandomProvider>(&self, provider: &mut R) -> BlockKind {
+        if self.total_percent() == 0 {
+            return BlockKind::Normal;
+        }
+        let value = provider.gen_range(0, 100) as u32;
+        if value < self.wildcard_percent {
+            BlockKind::Wildcard
+        } else if value < self.wildcard_percent + self.bomb_percent {
+            BlockKind::Bomb
+        } else if value < self.total_percent() {
+            BlockKind::Garbage
+        } else {
+            BlockKind::Normal
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -146,16 +201,16 @@ pub struct Tetromino {
     pub shape: TetrominoShape, // Made public for SRS testing
     pub pos: (i8, i8),
     blocks: Vec<((i8, i8), GameColor)>,
+    // 各ブロックの種類（blocksと同じインデックスで、回転しても色と同様にブロックに追従する）
+    kinds: [BlockKind; 4],
     rotation_state: u8, // SRS rotation state: 0, 1, 2, 3
 }
 
 impl Tetromino {
-    pub fn new_random() -> Self {
-        let shape = TETROMINO_BAG.lock().unwrap().next();
-
+    /// 隣接ブロックが同じ色にならない配色をランダムに選んだピースを返す
+    fn random_colored<R: RandomProvider>(shape: TetrominoShape, provider: &mut R) -> Self {
         // Loop until a valid coloring is found
         loop {
-            let mut provider = create_default_random_provider();
             let colors = [
                 *provider.choose(&COLOR_PALETTE).unwrap(),
                 *provider.choose(&COLOR_PALETTE).unwrap(),
@@ -188,6 +243,12 @@ impl Tetromino {
         }
     }
 
+    /// 各ブロックの種類を指定したピースを返す
+    pub fn with_block_kinds(mut self, kinds: [BlockKind; 4]) -> Self {
+        self.kinds = kinds;
+        self
+    }
+
     pub fn from_shape(shape: TetrominoShape, colors: [GameColor; 4]) -> Self {
         let matrix = match shape {
             TetrominoShape::I => &Self::SHAPES[0],
@@ -207,6 +268,7 @@ impl Tetromino {
             shape,
             pos: ((BOARD_WIDTH as i8) / 2 - 2, 0),
             blocks,
+            kinds: [BlockKind::Normal; 4],
             rotation_state: 0, // Initial rotation state
         }
     }
@@ -218,6 +280,13 @@ impl Tetromino {
         })
     }
 
+    /// 各ブロックの座標と、固定時に盤面へ置かれるセルを返す
+    pub fn iter_cells(&self) -> impl Iterator<Item = ((i8, i8), Cell)> + '_ {
+        self.iter_blocks()
+            .zip(self.kinds.iter())
+            .map(|((pos, color), kind)| (pos, kind.to_cell(color)))
+    }
+
     /// Gets the colors of the blocks in order
     /// Used for testing color consistency during rotations
     #[allow(dead_code)]
@@ -237,27 +306,33 @@ impl Tetromino {
     /// Phase 4: Complete SRS standard wall kick implementation
     #[allow(dead_code)]
     pub fn rotated_with_wall_kick(&self) -> Self {
-        let original_state = self.get_rotation_state();
-        let target_state = (original_state + 1) % 4;
+        // For now, check basic bounds (in full game would check board collision)
+        self.rotated_with_kicks(true, |candidate| self.is_position_valid(candidate))
+            // If all offsets fail, return normal rotation (in real game might fail)
+            .unwrap_or_else(|| self.rotated())
+    }
 
-        // Get SRS wall kick offset table for this transition
-        let offsets = self.get_srs_wall_kick_offsets(original_state, target_state);
+    /// SRSの壁蹴りつきで回転する
+    ///
+    /// 回転後の位置にオフセット表の順でずらしながら`fits`で判定し、最初に収まった位置を返す。
+    /// どのオフセットでも収まらなければNone（回転できない）
+    pub fn rotated_with_kicks(
+        &self,
+        clockwise: bool,
+        fits: impl Fn(&Self) -> bool,
+    ) -> Option<Self> {
+        let rotated = if clockwise {
+            self.rotated()
+        } else {
+            self.rotated_counter_clockwise()
+        };
+        let offsets = self.get_srs_wall_kick_offsets(self.rotation_state, rotated.rotation_state);
 
         // Try each offset in order until one works or all fail
-        for &[offset_x, offset_y] in offsets {
-            let mut candidate_piece = self.rotated();
-            candidate_piece.pos = (
-                candidate_piece.pos.0 + offset_x,
-                candidate_piece.pos.1 + offset_y,
-            );
-
-            // For now, check basic bounds (in full game would check board collision)
-            if self.is_position_valid(&candidate_piece) {
-                return candidate_piece;
-            }
-        }
-        // If all offsets fail, return normal rotation (in real game might fail)
-        self.rotated()
+        offsets
+            .iter()
+            .map(|&[offset_x, offset_y]| rotated.moved(offset_x, offset_y))
+            .find(|candidate| fits(candidate))
     }
 
     /// Get SRS standard wall kick offsets for a rotation transition
@@ -445,22 +520,13 @@ mod tests {
     use std::collections::HashSet;
 
     #[test]
-    fn test_new_random_uses_7_bag_system() {
-        use crate::random::{DeterministicRandomProvider, RandomProviderImpl};
-
-        let deterministic_provider =
-            DeterministicRandomProvider::new(vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7]);
-        let mut test_bag = TetrominoBag {
-            bag: TetrominoShape::all_shapes(),
-            random_provider: RandomProviderImpl::Deterministic(deterministic_provider),
-        };
-        test_bag.random_provider.shuffle(&mut test_bag.bag);
-
-        let mut generated_shapes = Vec::new();
-        for _ in 0..14 {
+    fn test_piece_generator_uses_7_bag_system() {
+        let mut generator = PieceGenerator::new(3);
+        let rates = SpecialBlockRates::default();
+        let generated_shapes: Vec<_> = (0..14)
             // Generate enough pieces for two full bags
-            generated_shapes.push(test_bag.next());
-        }
+            .map(|_| generator.next_piece(&rates).shape)
+            .collect();
 
         // Check the first bag
         let first_bag_shapes: HashSet<_> = generated_shapes[0..7].iter().collect();
@@ -481,7 +547,7 @@ mod tests {
 
     #[test]
     fn test_new_tetromino_uses_only_three_colors() {
-        let tetromino = Tetromino::new_random();
+        let tetromino = PieceGenerator::new(1).next_piece(&SpecialBlockRates::default());
         let allowed_colors = [GameColor::Cyan, GameColor::Magenta, GameColor::Yellow];
 
         for (_, color) in tetromino.iter_blocks() {
@@ -493,11 +559,84 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_block_kinds_follow_blocks_through_rotation() {
+        let kinds = [
+            BlockKind::Bomb,
+            BlockKind::Normal,
+            BlockKind::Wildcard,
+            BlockKind::Normal,
+        ];
+        let piece =
+            Tetromino::from_shape(TetrominoShape::T, [GameColor::Cyan; 4]).with_block_kinds(kinds);
+        let rotated = piece.rotated().rotated_counter_clockwise().rotated();
+
+        // 回転後もインデックスごとの種類は変わらない（色と同じくブロックに追従する）
+        let cells: Vec<Cell> = rotated.iter_cells().map(|(_, cell)| cell).collect();
+        assert_eq!(
+            cells,
+            vec![
+                Cell::Bomb,
+                Cell::Occupied(GameColor::Cyan),
+                Cell::Wildcard,
+                Cell::Occupied(GameColor::Cyan),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_special_block_rates_roll() {
+        use crate::random::DeterministicRandomProvider;
+
+        let rates = SpecialBlockRates {
+            wildcard_percent: 10,
+            bomb_percent: 10,
+            garbage_percent: 10,
+        };
+        let mut provider = DeterministicRandomProvider::new(vec![5, 15, 25, 35]);
+        let kinds: Vec<BlockKind> = (0..4).map(|_| rates.roll(&mut provider)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                BlockKind::Wildcard,
+                BlockKind::Bomb,
+                BlockKind::Garbage,
+                BlockKind::Normal
+            ]
+        );
+
+        // 既定値ではすべて通常ブロック
+        assert_eq!(
+            SpecialBlockRates::default().roll(&mut provider),
+            BlockKind::Normal
+        );
+    }
+
+    #[test]
+    fn test_piece_generator_replays_from_clone() {
+        let rates = SpecialBlockRates::default();
+        let mut generator = PieceGenerator::new(7);
+
+        // 最初の7個は7種類がちょうど1個ずつ
+        let mut shapes: Vec<TetrominoShape> =
+            (0..7).map(|_| generator.next_piece(&rates).shape).collect();
+        shapes.sort_by_key(|shape| *shape as u8);
+        assert_eq!(shapes, TetrominoShape::all_shapes());
+
+        // 途中で複製した生成器からは、配色も含めて同じピースが出てくる
+        generator.next_piece(&rates);
+        let mut copy = generator.clone();
+        for _ in 0..10 {
+            assert_eq!(generator.next_piece(&rates), copy.next_piece(&rates));
+        }
+    }
+
     #[test]
     fn test_adjacent_blocks_have_different_colors() {
         // ループを複数回実行して、ランダム性の問題を検出する確率を上げる
+        let mut generator = PieceGenerator::new(100);
         for _ in 0..100 {
-            let tetromino = Tetromino::new_random();
+            let tetromino = generator.next_piece(&SpecialBlockRates::default());
             let blocks = &tetromino.blocks;
 
             // すべてのブロックのペアをチェック
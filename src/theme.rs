@@ -0,0 +1,398 @@
+//! CLI版の配色テーマ
+//!
+//! 描画は`GameColor`で行い、端末に送るときにテーマのRGBと端末の色数（トゥルーカラー・256色・16色・
+//! 色なし）に合わせて変換します。ブロックの種類ごとの役割（連結ブロック・Solid・ゴースト・枠）に
+//! どの色を使うかと、色に頼らない記号表示（`src/glyph.rs`）を使うかもテーマで決めます。
+//! テーマはテキストファイル（書式は`themes/high-contrast.txt`）で読み込めます。
+
+use crossterm::style::Color;
+
+use crate::game_color::GameColor;
+
+/// 組み込みテーマ（classicはファイルを持たない既定の配色）
+pub const BUILTIN_THEMES: [(&str, &str); 2] = [
+    ("high-contrast", include_str!("../themes/high-contrast.txt")),
+    ("solarized", include_str!("../themes/solarized.txt")),
+];
+
+/// テーマファイルでの色名（`GameColor`の宣言順）
+const COLOR_NAMES: [(&str, GameColor); 16] = [
+    ("cyan", GameColor::Cyan),
+    ("magenta", GameColor::Magenta),
+    ("yellow", GameColor::Yellow),
+    ("grey", GameColor::Grey),
+    ("red", GameColor::Red),
+    ("green", GameColor::Green),
+    ("blue", GameColor::Blue),
+    ("white", GameColor::White),
+    ("black", GameColor::Black),
+    ("dark-grey", GameColor::DarkGrey),
+    ("dark-red", GameColor::DarkRed),
+    ("dark-green", GameColor::DarkGreen),
+    ("dark-blue", GameColor::DarkBlue),
+    ("dark-yellow", GameColor::DarkYellow),
+    ("dark-magenta", GameColor::DarkMagenta),
+    ("dark-cyan", GameColor::DarkCyan),
+];
+
+/// 端末が表示できる色数
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    /// `NO_COLOR`が指定されている、または色を表示できない端末
+    NoColor,
+}
+
+impl ColorDepth {
+    /// 環境変数`NO_COLOR`・`COLORTERM`・`TERM`から色数を判定する
+    pub fn from_env() -> ColorDepth {
+        let var = |name| std::env::var(name).ok();
+        Self::detect(var("NO_COLOR"), var("COLORTERM"), var("TERM"))
+    }
+
+    /// `NO_COLOR`は空でなければ値に関係なく色を使わない（https://no-color.org/）
+    pub fn detect(
+        no_color: Option<String>,
+        colorterm: Option<String>,
+        term: Option<String>,
+    ) -> ColorDepth {
+        if no_color.is_some_and(|value| !value.is_empty()) {
+            return ColorDepth::NoColor;
+        }
+        if matches!(colorterm.as_deref(), Some("truecolor" | "24bit")) {
+            return ColorDepth::TrueColor;
+        }
+        match term.as_deref() {
+            Some("dumb") => ColorDepth::NoColor,
+            Some(term) if term.contains("256color") => ColorDepth::Ansi256,
+            _ => ColorDepth::Ansi16,
+        }
+    }
+}
+
+/// 画面に使う文字の範囲（枠・ハーフブロックなどの記号をUnicodeで描くか、ASCIIだけで描くか）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Charset {
+    Unicode,
+    Ascii,
+}
+
+impl Charset {
+    /// ロケール（`LC_ALL`・`LC_CTYPE`・`LANG`の順で最初に設定されているもの）から判定する
+    pub fn from_locale() -> Charset {
+        let var = |name| std::env::var(name).ok();
+        Self::detect(var("LC_ALL"), var("LC_CTYPE"), var("LANG"))
+    }
+
+    /// 文字コードがUTF-8のロケールならUnicode、それ以外（`C`・未設定を含む）はASCII
+    pub fn detect(
+        lc_all: Option<String>,
+        lc_ctype: Option<String>,
+        lang: Option<String>,
+    ) -> Charset {
+        let locale = [lc_all, lc_ctype, lang]
+            .into_iter()
+            .flatten()
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if locale.contains("utf-8") || locale.contains("utf8") {
+            Charset::Unicode
+        } else {
+            Charset::Ascii
+        }
+    }
+}
+
+/// 役割に使う色（ブロックの色に合わせるか、固定の色にするか）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoleColor {
+    /// ブロックの色そのまま
+    Block,
+    /// ブロックの色の暗い色
+    Dark,
+    Fixed(GameColor),
+}
+
+impl RoleColor {
+    pub fn resolve(self, block: GameColor) -> GameColor {
+        match self {
+            RoleColor::Block => block,
+            RoleColor::Dark => dark_variant(block),
+            RoleColor::Fixed(color) => color,
+        }
+    }
+}
+
+/// 配色テーマ
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// 色ごとのRGB（Noneなら`GameColor::to_rgb`の値）
+    palette: [Option<(u8, u8, u8)>; 16],
+    /// 連結ブロックの背景色と連結数の文字色
+    pub connected: RoleColor,
+    pub connected_text: GameColor,
+    pub solid: GameColor,
+    pub ghost: RoleColor,
+    pub frame: GameColor,
+    /// 色ごとに形の違う記号でブロック・ゴースト・連結ブロックを描く
+    pub glyphs: bool,
+    /// 枠などの記号に使う文字（テーマファイルではなく、ロケールか`--charset`で決める）
+    pub charset: Charset,
+    /// 色を送らない端末（`NO_COLOR`など）向けに、色に頼らない記号で描く（端末の色数で決める）
+    pub monochrome: bool,
+}
+
+/// 既定の配色（これまでの見た目）
+pub const CLASSIC: Theme = Theme {
+    palette: [None; 16],
+    connected: RoleColor::Block,
+    connected_text: GameColor::Black,
+    solid: GameColor::Grey,
+    ghost: RoleColor::Block,
+    frame: GameColor::Grey,
+    glyphs: false,
+    charset: Charset::Unicode,
+    monochrome: false,
+};
+
+impl Theme {
+    /// 組み込みテーマの名前かテーマファイルのパスから読み込む
+    pub fn load(name_or_path: &str) -> Result<Theme, String> {
+        if name_or_path == "classic" {
+            return Ok(CLASSIC);
+        }
+        if let Some((_, text)) = BUILTIN_THEMES
+            .iter()
+            .find(|(name, _)| *name == name_or_path)
+        {
+            return Theme::parse(text);
+        }
+        let text = std::fs::read_to_string(name_or_path)
+            .map_err(|err| format!("{}: {}", name_or_path, err))?;
+        Theme::parse(&text).map_err(|message| format!("{}: {}", name_or_path, message))
+    }
+
+    /// テーマファイルのテキストを読み取る（書かなかった項目はclassicの値）
+    pub fn parse(text: &str) -> Result<Theme, String> {
+        let mut theme = CLASSIC;
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("expected 'key: value': {}", line))?;
+            let value = value.trim();
+            match key.trim() {
+                "connected" => theme.connected = parse_role(value)?,
+                "connected-text" => theme.connected_text = parse_color_name(value)?,
+                "solid" => theme.solid = parse_color_name(value)?,
+                "ghost" => theme.ghost = parse_role(value)?,
+                "frame" => theme.frame = parse_color_name(value)?,
+                "glyphs" => {
+                    theme.glyphs = match value {
+                        "on" => true,
+                        "off" => false,
+                        _ => {
+                            return Err(format!(
+                                "invalid glyphs value: {} (expected on or off)",
+                                value
+                            ))
+                        }
+                    }
+                }
+                key => {
+                    let color =
+                        parse_color_name(key).map_err(|_| format!("unknown theme key: {}", key))?;
+                    theme.palette[color as usize] = Some(parse_rgb(value)?);
+                }
+            }
+        }
+        Ok(theme)
+    }
+
+    pub fn rgb(&self, color: GameColor) -> (u8, u8, u8) {
+        self.palette[color as usize].unwrap_or_else(|| color.to_rgb())
+    }
+
+    /// 端末に送る色（16色の端末では基本色の名前どおり、色なしなら既定色）
+    pub fn terminal_color(&self, color: GameColor, depth: ColorDepth) -> Color {
+        match depth {
+            ColorDepth::TrueColor => {
+                let (r, g, b) = self.rgb(color);
+                Color::Rgb { r, g, b }
+            }
+            ColorDepth::Ansi256 => Color::AnsiValue(ansi256_index(self.rgb(color))),
+            ColorDepth::Ansi16 => Color::from(color),
+            ColorDepth::NoColor => Color::Reset,
+        }
+    }
+}
+
+/// 明るい色に対応する暗い色
+pub fn dark_variant(color: GameColor) -> GameColor {
+    match color {
+        GameColor::Cyan => GameColor::DarkCyan,
+        GameColor::Magenta => GameColor::DarkMagenta,
+        GameColor::Yellow => GameColor::DarkYellow,
+        GameColor::Red => GameColor::DarkRed,
+        GameColor::Green => GameColor::DarkGreen,
+        GameColor::Blue => GameColor::DarkBlue,
+        GameColor::White | GameColor::Grey => GameColor::DarkGrey,
+        other => other,
+    }
+}
+
+fn parse_color_name(value: &str) -> Result<GameColor, String> {
+    COLOR_NAMES
+        .iter()
+        .find(|(name, _)| *name == value)
+        .map(|&(_, color)| color)
+        .ok_or_else(|| format!("unknown color: {}", value))
+}
+
+fn parse_role(value: &str) -> Result<RoleColor, String> {
+    match value {
+        "block" => Ok(RoleColor::Block),
+        "dark" => Ok(RoleColor::Dark),
+        name => parse_color_name(name).map(RoleColor::Fixed),
+    }
+}
+
+/// `#rrggbb`を読み取る
+fn parse_rgb(value: &str) -> Result<(u8, u8, u8), String> {
+    let hex = value
+        .strip_prefix('#')
+        .filter(|hex| hex.len() == 6 && hex.chars().all(|ch| ch.is_ascii_hexdigit()))
+        .ok_or_else(|| format!("invalid color value: {} (expected #rrggbb)", value))?;
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).unwrap();
+    Ok((channel(0), channel(2), channel(4)))
+}
+
+/// RGBに最も近い256色パレットの番号（6x6x6の色立方体か、24段階のグレー）
+fn ansi256_index((r, g, b): (u8, u8, u8)) -> u8 {
+    if r == g && g == b {
+        return match r {
+            0..=7 => 16,
+            249..=255 => 231,
+            _ => 232 + ((r - 8 + 5) / 10).min(23),
+        };
+    }
+    let level = |value: u8| ((value as u16 * 5 + 127) / 255) as u8;
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_depth_follows_environment() {
+        let env = |value: &str| Some(value.to_string());
+        assert_eq!(
+            ColorDepth::detect(None, env("truecolor"), env("xterm-256color")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::detect(None, None, env("xterm-256color")),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(
+            ColorDepth::detect(None, None, env("xterm")),
+            ColorDepth::Ansi16
+        );
+        assert_eq!(
+            ColorDepth::detect(env("1"), env("truecolor"), env("xterm")),
+            ColorDepth::NoColor
+        );
+        // 空のNO_COLORは指定なしとみなす
+        assert_eq!(
+            ColorDepth::detect(env(""), None, env("xterm")),
+            ColorDepth::Ansi16
+        );
+    }
+
+    #[test]
+    fn test_charset_follows_locale() {
+        let env = |value: &str| Some(value.to_string());
+        assert_eq!(
+            Charset::detect(None, None, env("ja_JP.UTF-8")),
+            Charset::Unicode
+        );
+        assert_eq!(
+            Charset::detect(None, env("en_US.utf8"), env("C")),
+            Charset::Unicode
+        );
+        // LC_ALLが優先される
+        assert_eq!(
+            Charset::detect(env("C"), None, env("en_US.UTF-8")),
+            Charset::Ascii
+        );
+        assert_eq!(Charset::detect(env(""), None, None), Charset::Ascii);
+    }
+
+    #[test]
+    fn test_terminal_color_for_each_depth() {
+        let theme = Theme::parse("cyan: #2aa198").unwrap();
+        assert_eq!(
+            theme.terminal_color(GameColor::Cyan, ColorDepth::TrueColor),
+            Color::Rgb {
+                r: 0x2a,
+                g: 0xa1,
+                b: 0x98
+            }
+        );
+        assert_eq!(
+            CLASSIC.terminal_color(GameColor::Red, ColorDepth::Ansi256),
+            Color::AnsiValue(196)
+        );
+        assert_eq!(
+            CLASSIC.terminal_color(GameColor::Grey, ColorDepth::Ansi256),
+            Color::AnsiValue(244)
+        );
+        assert_eq!(
+            theme.terminal_color(GameColor::Cyan, ColorDepth::Ansi16),
+            Color::Cyan
+        );
+        assert_eq!(
+            theme.terminal_color(GameColor::Cyan, ColorDepth::NoColor),
+            Color::Reset
+        );
+    }
+
+    #[test]
+    fn test_parse_theme_roles_and_errors() {
+        let theme = Theme::parse(
+            "# comment\nconnected: dark\nconnected-text: white\nsolid: dark-grey\nghost: red\n",
+        )
+        .unwrap();
+        assert_eq!(
+            theme.connected.resolve(GameColor::Cyan),
+            GameColor::DarkCyan
+        );
+        assert_eq!(theme.connected_text, GameColor::White);
+        assert_eq!(theme.solid, GameColor::DarkGrey);
+        assert_eq!(theme.ghost.resolve(GameColor::Cyan), GameColor::Red);
+        assert_eq!(theme.frame, CLASSIC.frame);
+
+        assert!(Theme::parse("frame: block").is_err());
+        assert!(Theme::parse("cyan: #12345").is_err());
+        assert!(Theme::parse("sparkle: on").is_err());
+        assert!(Theme::parse("solid grey").is_err());
+        assert!(Theme::parse("glyphs: on").unwrap().glyphs);
+        assert!(Theme::parse("glyphs: yes").is_err());
+    }
+
+    #[test]
+    fn test_builtin_themes_load() {
+        assert_eq!(Theme::load("classic").unwrap(), CLASSIC);
+        for (name, _) in BUILTIN_THEMES {
+            assert!(Theme::load(name).is_ok(), "{}", name);
+        }
+        assert!(Theme::load("no/such/theme.txt").is_err());
+    }
+}
@@ -0,0 +1,244 @@
+//! 仮想端末
+//!
+//! `Renderer`への描画を実際の端末と同じように文字のグリッドへ反映します。`CrosstermRenderer`が
+//! フレームを組み立てるオフスクリーンバッファとして使うほか、テストでは画面を文字と色のテキストとして
+//! 取り出し、描画コマンドの列ではなく「画面に何が映っているか」を`src/tests/golden/`の
+//! ゴールデン画面テストで検証します。
+
+use std::io;
+
+use crate::game_color::GameColor;
+use crate::render::Renderer;
+use crate::theme::{self, Theme};
+
+/// 画面の1マス（色がNoneなら端末の既定色）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TerminalCell {
+    pub ch: char,
+    pub foreground: Option<GameColor>,
+    pub background: Option<GameColor>,
+}
+
+impl TerminalCell {
+    const BLANK: TerminalCell = TerminalCell {
+        ch: ' ',
+        foreground: None,
+        background: None,
+    };
+}
+
+/// 文字と色のグリッドを持つ`Renderer`
+#[derive(Clone, Debug, PartialEq)]
+pub struct VirtualTerminal {
+    width: usize,
+    height: usize,
+    cells: Vec<Vec<TerminalCell>>,
+    cursor: (usize, usize),
+    foreground: Option<GameColor>,
+    background: Option<GameColor>,
+    theme: Theme,
+}
+
+impl VirtualTerminal {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![vec![TerminalCell::BLANK; width]; height],
+            cursor: (0, 0),
+            foreground: None,
+            background: None,
+            theme: theme::CLASSIC,
+        }
+    }
+
+    /// 描画に使うテーマ（`Renderer::theme`で描画側に渡す）
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn cell(&self, x: usize, y: usize) -> TerminalCell {
+        self.cells[y][x]
+    }
+
+    /// 画面の文字（各行の末尾の空白は除く）
+    #[cfg(test)]
+    pub fn text(&self) -> String {
+        self.layer(|cell| cell.ch, ' ')
+    }
+
+    /// 画面の文字と色を、文字・前景色・背景色の3層のテキストで出力する
+    ///
+    /// 色は1文字の記号（`color_code`）で、既定色は`.`。前景色は空白以外の文字にだけ付ける
+    #[cfg(test)]
+    pub fn dump(&self) -> String {
+        let foreground = self.layer(
+            |cell| match (cell.ch, cell.foreground) {
+                (' ', _) | (_, None) => '.',
+                (_, Some(color)) => color_code(color),
+            },
+            '.',
+        );
+        let background = self.layer(|cell| cell.background.map_or('.', color_code), '.');
+        format!(
+            "{}\n--- foreground ---\n{}\n--- background ---\n{}\n",
+            self.text(),
+            foreground,
+            background
+        )
+    }
+
+    /// 各マスを1文字にした行を、末尾の`blank`を除いて改行でつなぐ
+    #[cfg(test)]
+    fn layer(&self, symbol: impl Fn(&TerminalCell) -> char, blank: char) -> String {
+        self.cells
+            .iter()
+            .map(|row| {
+                let line: String = row.iter().map(&symbol).collect();
+                line.trim_end_matches(blank).to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// 右端で折り返し、最下行からはみ出したら1行スクロールする
+    fn put_char(&mut self, ch: char) {
+        if self.cursor.0 >= self.width {
+            self.cursor = (0, self.cursor.1 + 1);
+        }
+        if self.cursor.1 >= self.height {
+            self.cells.remove(0);
+            self.cells.push(vec![TerminalCell::BLANK; self.width]);
+            self.cursor.1 = self.height - 1;
+        }
+        let (x, y) = self.cursor;
+        self.cells[y][x] = TerminalCell {
+            ch,
+            foreground: self.foreground,
+            background: self.background,
+        };
+        self.cursor.0 += 1;
+    }
+}
+
+/// 色の記号（明るい色は大文字、暗い色は小文字。Greyは`A`、DarkGreyは`a`、Blackは`K`）
+#[cfg(test)]
+pub fn color_code(color: GameColor) -> char {
+    match color {
+        GameColor::Cyan => 'C',
+        GameColor::Magenta => 'M',
+        GameColor::Yellow => 'Y',
+        GameColor::Grey => 'A',
+        GameColor::Red => 'R',
+        GameColor::Green => 'G',
+        GameColor::Blue => 'B',
+        GameColor::White => 'W',
+        GameColor::Black => 'K',
+        GameColor::DarkGrey => 'a',
+        GameColor::DarkRed => 'r',
+        GameColor::DarkGreen => 'g',
+        GameColor::DarkBlue => 'b',
+        GameColor::DarkYellow => 'y',
+        GameColor::DarkMagenta => 'm',
+        GameColor::DarkCyan => 'c',
+    }
+}
+
+impl Renderer for VirtualTerminal {
+    /// 画面全体を現在の背景色の空白で消す（カーソルは動かさない）
+    fn clear_screen(&mut self) -> io::Result<()> {
+        let blank = TerminalCell {
+            background: self.background,
+            ..TerminalCell::BLANK
+        };
+        self.cells = vec![vec![blank; self.width]; self.height];
+        Ok(())
+    }
+
+    fn move_to(&mut self, x: u16, y: u16) -> io::Result<()> {
+        // 実際の端末と同じく、画面外への移動は端に寄せる
+        self.cursor = (
+            (x as usize).min(self.width - 1),
+            (y as usize).min(self.height - 1),
+        );
+        Ok(())
+    }
+
+    fn set_foreground_color(&mut self, color: GameColor) -> io::Result<()> {
+        self.foreground = Some(color);
+        Ok(())
+    }
+
+    fn set_background_color(&mut self, color: GameColor) -> io::Result<()> {
+        self.background = Some(color);
+        Ok(())
+    }
+
+    fn print(&mut self, s: &str) -> io::Result<()> {
+        for ch in s.chars() {
+            self.put_char(ch);
+        }
+        Ok(())
+    }
+
+    fn reset_color(&mut self) -> io::Result<()> {
+        self.foreground = None;
+        self.background = None;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (self.width as u16, self.height as u16)
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.theme
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_moves_cursor_wraps_and_keeps_colors() {
+        let mut terminal = VirtualTerminal::new(4, 2);
+        terminal.move_to(2, 0).unwrap();
+        terminal.set_foreground_color(GameColor::Cyan).unwrap();
+        terminal.set_background_color(GameColor::DarkBlue).unwrap();
+        terminal.print("abc").unwrap();
+        terminal.reset_color().unwrap();
+        terminal.print("d").unwrap();
+
+        assert_eq!(terminal.text(), "  ab\ncd");
+        assert_eq!(
+            terminal.cell(0, 1),
+            TerminalCell {
+                ch: 'c',
+                foreground: Some(GameColor::Cyan),
+                background: Some(GameColor::DarkBlue),
+            }
+        );
+        assert_eq!(
+            terminal.dump(),
+            "  ab\ncd\n--- foreground ---\n..CC\nC\n--- background ---\n..bb\nb\n"
+        );
+    }
+
+    #[test]
+    fn test_clear_fills_with_background_and_bottom_line_scrolls() {
+        let mut terminal = VirtualTerminal::new(3, 2);
+        terminal.print("abcdef").unwrap();
+        terminal.print("g").unwrap();
+        assert_eq!(terminal.text(), "def\ng");
+
+        terminal.set_background_color(GameColor::Red).unwrap();
+        terminal.clear_screen().unwrap();
+        assert_eq!(terminal.text(), "\n");
+        assert_eq!(terminal.cell(2, 1).background, Some(GameColor::Red));
+    }
+}